@@ -1,3 +1,4 @@
 pub mod abi_utils;
+pub mod calldata;
 pub mod constants;
 pub mod sierra_types;
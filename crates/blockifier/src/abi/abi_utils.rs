@@ -1,11 +1,14 @@
 use cairo_felt::Felt252;
 use num_integer::Integer;
+use num_traits::ToPrimitive;
 use sha3::{Digest, Keccak256};
 use starknet_api::core::{ContractAddress, EntryPointSelector, L2_ADDRESS_UPPER_BOUND};
 use starknet_api::hash::{pedersen_hash, StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 
 use crate::abi::constants;
+use crate::abi::sierra_types::{SierraTypeError, SierraTypeResult};
+use crate::execution::call_info::Retdata;
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
 
 #[cfg(test)]
@@ -52,9 +55,38 @@ pub fn get_storage_var_address(storage_var_name: &str, args: &[StarkFelt]) -> St
         .expect("Should be within bounds as retrieved mod L2_ADDRESS_UPPER_BOUND.")
 }
 
+/// Returns the storage address of a member of a struct stored in a Starknet storage variable, given
+/// the name and arguments of the variable, and the offset of the member within the struct.
+pub fn get_storage_var_member_address(
+    storage_var_name: &str,
+    args: &[StarkFelt],
+    offset: u8,
+) -> StorageKey {
+    let base_address = get_storage_var_address(storage_var_name, args);
+    let member_address = (stark_felt_to_felt(*base_address.0.key()) + Felt252::from(offset))
+        .mod_floor(&Felt252::from_bytes_be(&L2_ADDRESS_UPPER_BOUND.to_bytes_be()));
+
+    StorageKey::try_from(felt_to_stark_felt(&member_address))
+        .expect("Should be within bounds as retrieved mod L2_ADDRESS_UPPER_BOUND.")
+}
+
 /// Returns the storage key inside the fee token corresponding to the first storage cell where the
 /// balance of contract_address is stored. Note that the reference implementation of an ERC20 stores
 /// the balance in two consecutive storage cells.
 pub fn get_fee_token_var_address(contract_address: ContractAddress) -> StorageKey {
     get_storage_var_address("ERC20_balances", &[*contract_address.0.key()])
 }
+
+/// Decodes a serialized Cairo 1 enum (e.g., `Result` or `Option`) from the given retdata, returning
+/// the index of its variant and the serialized payload of the variant.
+/// Enums are serialized discriminant-first; e.g., `Result::Ok(x)` is serialized as `[0, x]`.
+pub fn decode_enum_variant(retdata: &Retdata) -> SierraTypeResult<(u64, &[StarkFelt])> {
+    let (discriminant, payload) =
+        retdata.0.split_first().ok_or(SierraTypeError::MissingEnumDiscriminant)?;
+    let discriminant = stark_felt_to_felt(*discriminant);
+    let variant = discriminant
+        .to_u64()
+        .ok_or(SierraTypeError::ValueTooLargeForType { val: discriminant, ty: "u64" })?;
+
+    Ok((variant, payload))
+}
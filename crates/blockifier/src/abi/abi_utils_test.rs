@@ -1,12 +1,18 @@
+use assert_matches::assert_matches;
 use cairo_felt::Felt252;
 use num_bigint::BigUint;
 use starknet_api::core::EntryPointSelector;
 use starknet_api::hash::StarkFelt;
 use starknet_api::stark_felt;
 
-use crate::abi::abi_utils::selector_from_name;
+use crate::abi::abi_utils::{
+    decode_enum_variant, get_storage_var_address, get_storage_var_member_address,
+    selector_from_name,
+};
 use crate::abi::constants as abi_constants;
-use crate::abi::sierra_types::felt_to_u128;
+use crate::abi::sierra_types::{felt_to_u128, next_storage_key, SierraTypeError};
+use crate::execution::call_info::Retdata;
+use crate::retdata;
 use crate::transaction::constants as transaction_constants;
 
 #[test]
@@ -55,3 +61,40 @@ fn test_value_too_large_for_type() {
         "Felt 340282366920938463463374607431768211456 is too big to convert to 'u128'."
     );
 }
+
+#[test]
+fn test_get_storage_var_member_address() {
+    let index = stark_felt!(7_u8);
+    let base_address = get_storage_var_address("two_counters", &[index]);
+
+    // The first member is stored at the base address, and the second right after it.
+    assert_eq!(get_storage_var_member_address("two_counters", &[index], 0), base_address);
+    assert_eq!(
+        get_storage_var_member_address("two_counters", &[index], 1),
+        next_storage_key(&base_address).unwrap()
+    );
+}
+
+#[test]
+fn test_decode_enum_variant() {
+    // `Result::Ok((7, 8))`.
+    let ok_retdata = retdata![stark_felt!(0_u8), stark_felt!(7_u8), stark_felt!(8_u8)];
+    let (variant, payload) = decode_enum_variant(&ok_retdata).unwrap();
+    assert_eq!(variant, 0);
+    assert_eq!(payload, &[stark_felt!(7_u8), stark_felt!(8_u8)]);
+
+    // `Option::None`.
+    let none_retdata = retdata![stark_felt!(1_u8)];
+    assert_eq!(decode_enum_variant(&none_retdata).unwrap(), (1, [].as_slice()));
+
+    // Negative flows.
+    assert_matches!(
+        decode_enum_variant(&retdata![]),
+        Err(SierraTypeError::MissingEnumDiscriminant)
+    );
+    let large_discriminant_retdata = retdata![stark_felt!("0x10000000000000000")];
+    assert_matches!(
+        decode_enum_variant(&large_discriminant_retdata),
+        Err(SierraTypeError::ValueTooLargeForType { ty: "u64", .. })
+    );
+}
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
+use thiserror::Error;
+
+use crate::abi::constants::MAX_SHORT_STRING_LENGTH;
+use crate::abi::sierra_types::SierraU256;
+
+#[cfg(test)]
+#[path = "calldata_test.rs"]
+mod test;
+
+pub type CalldataResult<T> = Result<T, CalldataError>;
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum CalldataError {
+    #[error(
+        "Short string {0:?} is invalid; expected at most {max_length} ASCII characters, the first \
+         of which is not NUL.",
+        max_length = MAX_SHORT_STRING_LENGTH
+    )]
+    InvalidShortString(String),
+    #[error("Expected {n_expected} more felts, but only {n_remaining} remain.")]
+    NotEnoughFelts { n_expected: usize, n_remaining: usize },
+    #[error("{n_trailing} felts remain after decoding.")]
+    TrailingFelts { n_trailing: usize },
+    #[error("Felt {val} is too big to convert to '{ty}'.")]
+    ValueTooLargeForType { val: StarkFelt, ty: &'static str },
+}
+
+/// A value that is passed to (or returned from) Cairo functions as a sequence of felts, following
+/// the Cairo 1.0 `Serde` layout.
+pub trait CairoSerde: Sized {
+    /// Appends the felt representation of the value to `felts`.
+    fn serialize(&self, felts: &mut Vec<StarkFelt>);
+
+    /// Reads a value from the beginning of `felts`, and advances it past the consumed felts.
+    fn deserialize(felts: &mut &[StarkFelt]) -> CalldataResult<Self>;
+}
+
+/// Returns the felt representation of the given value.
+pub fn encode_felts<T: CairoSerde>(value: &T) -> Vec<StarkFelt> {
+    let mut felts = vec![];
+    value.serialize(&mut felts);
+    felts
+}
+
+/// Decodes a value from the given felts; all felts must be consumed.
+pub fn decode_felts<T: CairoSerde>(felts: &[StarkFelt]) -> CalldataResult<T> {
+    let mut remaining_felts = felts;
+    let value = T::deserialize(&mut remaining_felts)?;
+    if !remaining_felts.is_empty() {
+        return Err(CalldataError::TrailingFelts { n_trailing: remaining_felts.len() });
+    }
+
+    Ok(value)
+}
+
+// Utils.
+
+fn take_felt(felts: &mut &[StarkFelt]) -> CalldataResult<StarkFelt> {
+    let (felt, rest) = felts
+        .split_first()
+        .ok_or(CalldataError::NotEnoughFelts { n_expected: 1, n_remaining: 0 })?;
+    *felts = rest;
+    Ok(*felt)
+}
+
+fn felt_to_u128(felt: StarkFelt) -> CalldataResult<u128> {
+    let (high_bytes, low_bytes) = felt.bytes().split_at(16);
+    if high_bytes.iter().any(|&byte| byte != 0) {
+        return Err(CalldataError::ValueTooLargeForType { val: felt, ty: "u128" });
+    }
+
+    Ok(u128::from_be_bytes(low_bytes.try_into().expect("Slice should be of length 16.")))
+}
+
+fn felt_to_usize(felt: StarkFelt) -> CalldataResult<usize> {
+    usize::try_from(felt_to_u128(felt)?)
+        .map_err(|_| CalldataError::ValueTooLargeForType { val: felt, ty: "usize" })
+}
+
+// Implementations.
+
+impl CairoSerde for StarkFelt {
+    fn serialize(&self, felts: &mut Vec<StarkFelt>) {
+        felts.push(*self);
+    }
+
+    fn deserialize(felts: &mut &[StarkFelt]) -> CalldataResult<Self> {
+        take_felt(felts)
+    }
+}
+
+impl CairoSerde for u128 {
+    fn serialize(&self, felts: &mut Vec<StarkFelt>) {
+        felts.push(StarkFelt::from(*self));
+    }
+
+    fn deserialize(felts: &mut &[StarkFelt]) -> CalldataResult<Self> {
+        felt_to_u128(take_felt(felts)?)
+    }
+}
+
+/// A `u256` is serialized as two `u128` limbs; the low limb comes first.
+impl CairoSerde for SierraU256 {
+    fn serialize(&self, felts: &mut Vec<StarkFelt>) {
+        self.low_val.serialize(felts);
+        self.high_val.serialize(felts);
+    }
+
+    fn deserialize(felts: &mut &[StarkFelt]) -> CalldataResult<Self> {
+        let low_val = u128::deserialize(felts)?;
+        let high_val = u128::deserialize(felts)?;
+        Ok(Self { low_val, high_val })
+    }
+}
+
+/// An array is serialized as its length, followed by its elements.
+impl<T: CairoSerde> CairoSerde for Vec<T> {
+    fn serialize(&self, felts: &mut Vec<StarkFelt>) {
+        felts.push(StarkFelt::from(self.len() as u64));
+        for element in self {
+            element.serialize(felts);
+        }
+    }
+
+    fn deserialize(felts: &mut &[StarkFelt]) -> CalldataResult<Self> {
+        let length = felt_to_usize(take_felt(felts)?)?;
+        // Each element takes at least one felt; avoids allocating for bogus lengths.
+        if length > felts.len() {
+            return Err(CalldataError::NotEnoughFelts {
+                n_expected: length,
+                n_remaining: felts.len(),
+            });
+        }
+
+        (0..length).map(|_| T::deserialize(felts)).collect()
+    }
+}
+
+/// A Cairo short string: at most 31 ASCII characters, packed into a single felt (big-endian).
+/// Leading NUL characters are rejected, as they are lost in the packing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShortString(String);
+
+impl ShortString {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<&str> for ShortString {
+    type Error = CalldataError;
+
+    fn try_from(value: &str) -> CalldataResult<Self> {
+        if !value.is_ascii() || value.len() > MAX_SHORT_STRING_LENGTH || value.starts_with('\0') {
+            return Err(CalldataError::InvalidShortString(value.to_string()));
+        }
+
+        Ok(Self(value.to_string()))
+    }
+}
+
+impl CairoSerde for ShortString {
+    fn serialize(&self, felts: &mut Vec<StarkFelt>) {
+        let mut bytes = [0_u8; 32];
+        bytes[32 - self.0.len()..].copy_from_slice(self.0.as_bytes());
+        felts.push(StarkFelt::new(bytes).expect("A short string should fit in a felt."));
+    }
+
+    fn deserialize(felts: &mut &[StarkFelt]) -> CalldataResult<Self> {
+        let felt = take_felt(felts)?;
+        let bytes = felt.bytes();
+        // The first byte must be zero, since a short string has at most 31 characters.
+        let first_char_index = bytes.iter().position(|&byte| byte != 0).unwrap_or(bytes.len());
+        let string = String::from_utf8_lossy(&bytes[first_char_index..]).into_owned();
+        if first_char_index == 0 || !string.is_ascii() {
+            return Err(CalldataError::InvalidShortString(string));
+        }
+
+        Ok(Self(string))
+    }
+}
+
+/// Builds calldata for Cairo 1.0 functions, argument by argument.
+#[derive(Debug, Default)]
+pub struct CalldataBuilder {
+    felts: Vec<StarkFelt>,
+}
+
+impl CalldataBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn arg<T: CairoSerde>(mut self, value: &T) -> Self {
+        value.serialize(&mut self.felts);
+        self
+    }
+
+    pub fn felt(self, value: impl Into<StarkFelt>) -> Self {
+        self.arg(&value.into())
+    }
+
+    pub fn u256(self, value: SierraU256) -> Self {
+        self.arg(&value)
+    }
+
+    /// Appends the array length, followed by its elements.
+    pub fn array<T: CairoSerde>(mut self, values: &[T]) -> Self {
+        self.felts.push(StarkFelt::from(values.len() as u64));
+        for value in values {
+            value.serialize(&mut self.felts);
+        }
+        self
+    }
+
+    pub fn short_string(self, value: &ShortString) -> Self {
+        self.arg(value)
+    }
+
+    pub fn build(self) -> Calldata {
+        Calldata(Arc::new(self.felts))
+    }
+}
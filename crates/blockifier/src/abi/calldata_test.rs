@@ -0,0 +1,84 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+
+use crate::abi::calldata::{
+    decode_felts, encode_felts, CalldataBuilder, CalldataError, ShortString,
+};
+use crate::abi::sierra_types::SierraU256;
+
+#[test]
+fn test_calldata_builder() {
+    let calldata = CalldataBuilder::new()
+        .felt(7_u8)
+        .u256(SierraU256 { low_val: 1, high_val: 2 })
+        .array(&[stark_felt!(3_u8), stark_felt!(4_u8)])
+        .short_string(&ShortString::try_from("hello").unwrap())
+        .build();
+
+    let expected_felts = vec![
+        stark_felt!(7_u8),
+        // u256: low limb first.
+        stark_felt!(1_u8),
+        stark_felt!(2_u8),
+        // Array: length prefix.
+        stark_felt!(2_u8),
+        stark_felt!(3_u8),
+        stark_felt!(4_u8),
+        stark_felt!("0x68656c6c6f"),
+    ];
+    assert_eq!(*calldata.0, expected_felts);
+}
+
+#[test]
+fn test_encode_decode_round_trip() {
+    let u256_value = SierraU256 { low_val: u128::MAX, high_val: 5 };
+    assert_eq!(decode_felts::<SierraU256>(&encode_felts(&u256_value)).unwrap(), u256_value);
+
+    let nested_array = vec![vec![1_u128, 2], vec![], vec![3]];
+    assert_eq!(decode_felts::<Vec<Vec<u128>>>(&encode_felts(&nested_array)).unwrap(), nested_array);
+
+    let short_string = ShortString::try_from("Starknet").unwrap();
+    let decoded_short_string = decode_felts::<ShortString>(&encode_felts(&short_string)).unwrap();
+    assert_eq!(decoded_short_string.as_str(), "Starknet");
+}
+
+#[rstest]
+#[case::too_long("abcdefghijklmnopqrstuvwxyz012345")]
+#[case::non_ascii("héllo")]
+#[case::leading_nul("\0hello")]
+fn test_invalid_short_string(#[case] value: &str) {
+    assert_eq!(
+        ShortString::try_from(value).unwrap_err(),
+        CalldataError::InvalidShortString(value.to_string())
+    );
+}
+
+#[test]
+fn test_decode_errors() {
+    // Missing high limb.
+    assert_matches!(
+        decode_felts::<SierraU256>(&[stark_felt!(1_u8)]),
+        Err(CalldataError::NotEnoughFelts { n_expected: 1, n_remaining: 0 })
+    );
+
+    // Length prefix exceeds the number of elements.
+    assert_matches!(
+        decode_felts::<Vec<StarkFelt>>(&[stark_felt!(3_u8), stark_felt!(1_u8)]),
+        Err(CalldataError::NotEnoughFelts { n_expected: 3, n_remaining: 1 })
+    );
+
+    // A u128 limb must fit in 128 bits.
+    let too_large = stark_felt!("0x100000000000000000000000000000000");
+    assert_matches!(
+        decode_felts::<u128>(&[too_large]),
+        Err(CalldataError::ValueTooLargeForType { ty: "u128", .. })
+    );
+
+    assert_matches!(
+        decode_felts::<StarkFelt>(&[stark_felt!(1_u8), stark_felt!(2_u8)]),
+        Err(CalldataError::TrailingFelts { n_trailing: 1 })
+    );
+}
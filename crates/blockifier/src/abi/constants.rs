@@ -14,6 +14,9 @@ pub const L1_TO_L2_MSG_HEADER_SIZE: usize = 5;
 pub const L2_TO_L1_MSG_HEADER_SIZE: usize = 3;
 pub const CLASS_UPDATE_SIZE: usize = 1;
 
+// Cairo short strings are packed into a single field element.
+pub const MAX_SHORT_STRING_LENGTH: usize = 31;
+
 // Starknet solidity contract-related constants.
 pub const N_DEFAULT_TOPICS: usize = 1; // Events have one default topic.
 
@@ -33,6 +36,7 @@ pub const MAX_VALIDATE_STEPS_PER_TX: usize = 1_000_000;
 pub const MAX_STEPS_PER_TX: usize = 4_000_000;
 pub const GAS_USAGE: &str = "l1_gas_usage";
 pub const N_STEPS_RESOURCE: &str = "n_steps";
+pub const N_EVENTS_RESOURCE: &str = "n_events";
 
 // Casm hash calculation-related constants.
 pub const CAIRO0_ENTRY_POINT_STRUCT_SIZE: usize = 2;
@@ -43,6 +47,13 @@ pub const N_STEPS_PER_PEDERSEN: usize = 8;
 pub const STEP_GAS_COST: u64 = 100;
 pub const RANGE_CHECK_GAS_COST: u64 = 70;
 pub const MEMORY_HOLE_GAS_COST: u64 = 10;
+// Builtin gas costs, per builtin application.
+pub const BITWISE_GAS_COST: u64 = 583;
+pub const EC_OP_GAS_COST: u64 = 4085;
+pub const ECDSA_GAS_COST: u64 = 10561;
+pub const KECCAK_BUILTIN_GAS_COST: u64 = 136189;
+pub const PEDERSEN_GAS_COST: u64 = 4050;
+pub const POSEIDON_GAS_COST: u64 = 491;
 
 // An estimation of the initial gas for a transaction to run with. This solution is temporary and
 // this value will become a field of the transaction.
@@ -22,6 +22,8 @@ pub type SierraTypeResult<T> = Result<T, SierraTypeError>;
 pub enum SierraTypeError {
     #[error("Felt {val} is too big to convert to '{ty}'.")]
     ValueTooLargeForType { val: Felt252, ty: &'static str },
+    #[error("Cannot decode an enum from empty data; expected a variant discriminant.")]
+    MissingEnumDiscriminant,
     #[error(transparent)]
     MemoryError(#[from] MemoryError),
     #[error(transparent)]
@@ -87,6 +89,7 @@ impl SierraType for SierraU128 {
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct SierraU256 {
     pub low_val: u128,
     pub high_val: u128,
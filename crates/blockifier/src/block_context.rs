@@ -1,35 +1,361 @@
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::{ChainId, ContractAddress};
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Fee;
+use thiserror::Error;
 
+use crate::abi::constants::{MAX_SHORT_STRING_LENGTH, SYSCALL_BASE_GAS_COST};
+use crate::execution::contract_class::CompilerVersion;
+use crate::execution::syscalls::SyscallSelector;
 use crate::transaction::objects::FeeType;
+use crate::versioned_constants::VersionedConstants;
+
+#[cfg(test)]
+#[path = "block_context_test.rs"]
+pub mod test;
+
+#[derive(Debug, Error)]
+pub enum BlockContextError {
+    #[error("Chain ID {chain_id} is not an ASCII string.")]
+    ChainIdNotAscii { chain_id: String },
+    #[error("Chain ID {chain_id} is too long: {length} bytes; maximum is {max_length}.")]
+    ChainIdTooLong { chain_id: String, length: usize, max_length: usize },
+    #[error("Fee split percentages must sum to 100; got {total_percentage}.")]
+    InvalidFeeSplit { total_percentage: u32 },
+    #[error(
+        "Gas cost {gas_cost} of syscall {selector:?} is lower than the syscall base gas cost \
+         {base_gas_cost}."
+    )]
+    SyscallGasCostTooLow { selector: SyscallSelector, gas_cost: u64, base_gas_cost: u64 },
+    #[error(
+        "Block timestamp {block_timestamp} precedes the previous block's timestamp \
+         {prev_block_timestamp}."
+    )]
+    TimestampRegression { block_timestamp: u64, prev_block_timestamp: u64 },
+    #[error("Starknet version {version} is not supported.")]
+    UnsupportedStarknetVersion { version: String },
+}
 
 #[derive(Clone, Debug)]
 pub struct BlockContext {
-    pub chain_id: ChainId,
+    pub block_info: BlockInfo,
+    pub chain_info: Arc<ChainInfo>,
+    pub versioned_constants: Arc<VersionedConstants>,
+}
+
+impl BlockContext {
+    pub fn new(
+        block_info: BlockInfo,
+        chain_info: ChainInfo,
+        versioned_constants: VersionedConstants,
+    ) -> Self {
+        Self {
+            block_info,
+            chain_info: Arc::new(chain_info),
+            versioned_constants: Arc::new(versioned_constants),
+        }
+    }
+
+    /// Returns a context for another block, sharing this context's chain info and versioned
+    /// constants.
+    pub fn with_block_info(&self, block_info: BlockInfo) -> Self {
+        Self {
+            block_info,
+            chain_info: Arc::clone(&self.chain_info),
+            versioned_constants: Arc::clone(&self.versioned_constants),
+        }
+    }
+
+    /// Returns a mutable reference to the chain info, cloning it first if it is shared with other
+    /// contexts.
+    pub fn chain_info_mut(&mut self) -> &mut ChainInfo {
+        Arc::make_mut(&mut self.chain_info)
+    }
+
+    pub fn chain_id(&self) -> &ChainId {
+        &self.chain_info.chain_id
+    }
+
+    pub fn block_number(&self) -> BlockNumber {
+        self.block_info.block_number
+    }
+
+    pub fn block_timestamp(&self) -> BlockTimestamp {
+        self.block_info.block_timestamp
+    }
+
+    pub fn sequencer_address(&self) -> ContractAddress {
+        self.block_info.sequencer_address
+    }
+
+    pub fn fee_token_addresses(&self) -> &FeeTokenAddresses {
+        &self.chain_info.fee_token_addresses
+    }
+
+    pub fn vm_resource_fee_cost(&self) -> &HashMap<String, f64> {
+        &self.chain_info.vm_resource_fee_cost
+    }
+
+    pub fn gas_prices(&self) -> &GasPrices {
+        &self.block_info.gas_prices
+    }
+
+    pub fn invoke_tx_max_n_steps(&self) -> u32 {
+        self.chain_info.invoke_tx_max_n_steps
+    }
+
+    pub fn validate_max_n_steps(&self) -> u32 {
+        self.chain_info.validate_max_n_steps
+    }
+
+    pub fn max_recursion_depth(&self) -> usize {
+        self.chain_info.max_recursion_depth
+    }
+
+    pub fn fee_token_address(&self, fee_type: &FeeType) -> ContractAddress {
+        self.chain_info.fee_token_address(fee_type)
+    }
+
+    /// Returns the fee transfers, as (recipient, amount) pairs, that collect the given fee
+    /// according to the chain's fee recipient.
+    pub fn fee_transfers(&self, actual_fee: Fee) -> Vec<(ContractAddress, Fee)> {
+        self.chain_info.fee_recipient.transfers(self.block_info.sequencer_address, actual_fee)
+    }
+
+    /// Returns the hash of the given block, if it was supplied by the caller.
+    pub fn block_hash_of(&self, block_number: BlockNumber) -> Option<StarkFelt> {
+        self.block_info.block_hashes.get(&block_number).copied()
+    }
+
+    /// Validates that the chain ID encodes a Cairo short string, as required by the transaction
+    /// hash computation.
+    pub fn validate_chain_id(&self) -> Result<(), BlockContextError> {
+        let chain_id = &self.chain_info.chain_id.0;
+        if !chain_id.is_ascii() {
+            return Err(BlockContextError::ChainIdNotAscii { chain_id: chain_id.clone() });
+        }
+        if chain_id.len() > MAX_SHORT_STRING_LENGTH {
+            return Err(BlockContextError::ChainIdTooLong {
+                chain_id: chain_id.clone(),
+                length: chain_id.len(),
+                max_length: MAX_SHORT_STRING_LENGTH,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Validates that the block timestamp does not precede the previous block's timestamp, if the
+    /// latter was supplied by the caller.
+    pub fn validate_timestamp(&self) -> Result<(), BlockContextError> {
+        let block_timestamp = self.block_info.block_timestamp;
+        match self.block_info.prev_block_timestamp {
+            Some(prev_block_timestamp) if block_timestamp < prev_block_timestamp => {
+                Err(BlockContextError::TimestampRegression {
+                    block_timestamp: block_timestamp.0,
+                    prev_block_timestamp: prev_block_timestamp.0,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Per-block data; replaced for every block.
+#[derive(Clone, Debug)]
+pub struct BlockInfo {
     pub block_number: BlockNumber,
     pub block_timestamp: BlockTimestamp,
+    // The timestamp of the previous block, if known; see [BlockContext::validate_timestamp].
+    pub prev_block_timestamp: Option<BlockTimestamp>,
 
     // Fee-related.
     pub sequencer_address: ContractAddress,
+    pub gas_prices: GasPrices,
+
+    // Hashes of previous blocks, served by the `get_block_hash` syscall.
+    pub block_hashes: HashMap<BlockNumber, StarkFelt>,
+}
+
+/// Chain-level data; shared across blocks.
+#[derive(Clone, Debug)]
+pub struct ChainInfo {
+    pub chain_id: ChainId,
+
+    // Fee-related.
     pub fee_token_addresses: FeeTokenAddresses,
     pub vm_resource_fee_cost: Arc<HashMap<String, f64>>,
-    pub gas_prices: GasPrices,
+    pub fee_recipient: FeeRecipient,
+    // If set, an L1 handler transaction fails unless the fee paid on L1 covers its actual fee;
+    // otherwise, any nonzero fee is accepted.
+    pub enforce_l1_handler_fee: bool,
 
     // Limits.
     pub invoke_tx_max_n_steps: u32,
     pub validate_max_n_steps: u32,
     pub max_recursion_depth: usize,
+    // In bytes.
+    pub max_contract_class_size: usize,
+    // In felts.
+    pub max_bytecode_length: usize,
+    // The upper bound is compared on (major, minor) only.
+    pub supported_compiler_versions: RangeInclusive<CompilerVersion>,
+    // In felts.
+    pub max_event_data_size: usize,
+    pub max_l1_message_payload_size: usize,
+    // The maximal number of storage slots a transaction's execution may modify, if limited.
+    pub max_storage_writes: Option<usize>,
+    pub block_resource_limits: BlockResourceLimits,
+
+    // Syscall pricing.
+    pub syscall_gas_costs: SyscallGasCosts,
+    pub calldata_l1_gas_costs: CalldataL1GasCosts,
 }
 
-impl BlockContext {
+impl ChainInfo {
     pub fn fee_token_address(&self, fee_type: &FeeType) -> ContractAddress {
         self.fee_token_addresses.get_by_fee_type(fee_type)
     }
 }
 
+/// Cumulative resource limits of a block's transactions; see
+/// [crate::block_execution::BlockResourceTracker]. Unset limits are not enforced.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BlockResourceLimits {
+    pub max_n_steps: Option<usize>,
+    pub max_l1_gas_usage: Option<usize>,
+    pub max_n_events: Option<usize>,
+}
+
+/// Overrides of the versioned syscall gas costs (see [VersionedConstants]), for chains that reprice
+/// syscalls.
+#[derive(Clone, Debug, Default)]
+pub struct SyscallGasCosts(HashMap<SyscallSelector, u64>);
+
+impl SyscallGasCosts {
+    /// Overriding costs may not be lower than [SYSCALL_BASE_GAS_COST], which is pre-charged by the
+    /// compiled contract code.
+    pub fn new(gas_costs: HashMap<SyscallSelector, u64>) -> Result<Self, BlockContextError> {
+        if let Some((&selector, &gas_cost)) =
+            gas_costs.iter().find(|(_, &gas_cost)| gas_cost < SYSCALL_BASE_GAS_COST)
+        {
+            return Err(BlockContextError::SyscallGasCostTooLow {
+                selector,
+                gas_cost,
+                base_gas_cost: SYSCALL_BASE_GAS_COST,
+            });
+        }
+
+        Ok(Self(gas_costs))
+    }
+
+    /// Returns the overriding gas cost of the given syscall, if any.
+    pub fn get(&self, selector: &SyscallSelector) -> Option<u64> {
+        self.0.get(selector).copied()
+    }
+}
+
+/// The L1 gas charged per felt of an account transaction's calldata and signature, for chains that
+/// price them. Starknet does not charge for them; hence, the costs are zero by default.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CalldataL1GasCosts {
+    pub per_calldata_felt: usize,
+    pub per_signature_felt: usize,
+}
+
+impl CalldataL1GasCosts {
+    /// Returns the L1 gas charged for a calldata and a signature of the given lengths.
+    pub fn l1_gas(&self, calldata_length: usize, signature_length: usize) -> usize {
+        calldata_length * self.per_calldata_felt + signature_length * self.per_signature_felt
+    }
+}
+
+/// The destination of the fees collected by the fee transfer.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum FeeRecipient {
+    /// The fee is transferred to the block's sequencer.
+    #[default]
+    Sequencer,
+    /// The fee is transferred to a fixed address, e.g., one that nobody controls.
+    Burn(ContractAddress),
+    /// The fee is divided between several addresses.
+    Split(FeeSplit),
+}
+
+impl FeeRecipient {
+    /// Returns the (recipient, amount) pairs that collect the given fee.
+    /// Zero-amount transfers are omitted; in particular, a zero fee requires no transfers.
+    pub fn transfers(
+        &self,
+        sequencer_address: ContractAddress,
+        actual_fee: Fee,
+    ) -> Vec<(ContractAddress, Fee)> {
+        let transfers = match self {
+            Self::Sequencer => vec![(sequencer_address, actual_fee)],
+            Self::Burn(burn_address) => vec![(*burn_address, actual_fee)],
+            Self::Split(split) => split.transfers(actual_fee),
+        };
+        transfers.into_iter().filter(|(_, amount)| *amount != Fee(0)).collect()
+    }
+
+    /// Returns the maximal number of transfers collecting a fee; fewer are made if some of the
+    /// amounts are zero.
+    pub fn max_n_transfers(&self) -> usize {
+        match self {
+            Self::Sequencer | Self::Burn(_) => 1,
+            Self::Split(split) => split.shares.len(),
+        }
+    }
+}
+
+/// A division of the fee between several addresses, by percentages that sum to 100.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeSplit {
+    shares: Vec<(ContractAddress, u8)>,
+}
+
+impl FeeSplit {
+    pub fn new(shares: Vec<(ContractAddress, u8)>) -> Result<Self, BlockContextError> {
+        let total_percentage: u32 =
+            shares.iter().map(|(_, percentage)| u32::from(*percentage)).sum();
+        if total_percentage != 100 {
+            return Err(BlockContextError::InvalidFeeSplit { total_percentage });
+        }
+
+        Ok(Self { shares })
+    }
+
+    pub fn shares(&self) -> &[(ContractAddress, u8)] {
+        &self.shares
+    }
+
+    /// Divides the fee by the shares' percentages, rounding down; the remainder goes to the first
+    /// share, so that the amounts sum to the fee.
+    fn transfers(&self, actual_fee: Fee) -> Vec<(ContractAddress, Fee)> {
+        let mut amounts: Vec<u128> = self
+            .shares
+            .iter()
+            .map(|(_, percentage)| {
+                // Avoid overflow: split the fee into a quotient and a remainder by 100.
+                let percentage = u128::from(*percentage);
+                (actual_fee.0 / 100) * percentage + (actual_fee.0 % 100) * percentage / 100
+            })
+            .collect();
+        let distributed: u128 = amounts.iter().sum();
+        amounts[0] += actual_fee.0 - distributed;
+
+        self.shares
+            .iter()
+            .zip(amounts)
+            .map(|((recipient, _), amount)| (*recipient, Fee(amount)))
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FeeTokenAddresses {
     pub strk_fee_token_address: ContractAddress,
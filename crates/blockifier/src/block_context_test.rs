@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::{ChainId, ContractAddress, PatriciaKey};
+use starknet_api::hash::StarkHash;
+use starknet_api::transaction::Fee;
+use starknet_api::{contract_address, patricia_key};
+
+use crate::abi::constants::SYSCALL_BASE_GAS_COST;
+use crate::block_context::{
+    BlockContext, BlockContextError, BlockInfo, CalldataL1GasCosts, FeeRecipient, FeeSplit,
+    SyscallGasCosts,
+};
+use crate::execution::syscalls::SyscallSelector;
+
+#[test]
+fn test_with_block_info() {
+    let block_context = BlockContext::create_for_testing();
+    let block_info = BlockInfo { block_number: BlockNumber(2), ..block_context.block_info.clone() };
+    let mut next_block_context = block_context.with_block_info(block_info);
+    assert_eq!(next_block_context.block_number(), BlockNumber(2));
+    assert_eq!(next_block_context.chain_id(), block_context.chain_id());
+    assert!(Arc::ptr_eq(&next_block_context.chain_info, &block_context.chain_info));
+
+    // Modifying the chain info of a context leaves the contexts sharing it unchanged.
+    next_block_context.chain_info_mut().chain_id = ChainId("SN_CUSTOM".to_string());
+    assert!(!Arc::ptr_eq(&next_block_context.chain_info, &block_context.chain_info));
+    assert_ne!(next_block_context.chain_id(), block_context.chain_id());
+}
+
+#[test]
+fn test_validate_chain_id() {
+    let block_context_with_chain_id = |chain_id: &str| {
+        let mut block_context = BlockContext::create_for_testing();
+        block_context.chain_info_mut().chain_id = ChainId(chain_id.to_string());
+        block_context
+    };
+
+    BlockContext::create_for_testing().validate_chain_id().unwrap();
+    block_context_with_chain_id(&"a".repeat(31)).validate_chain_id().unwrap();
+    assert_matches!(
+        block_context_with_chain_id("SN_MÄINNET").validate_chain_id().unwrap_err(),
+        BlockContextError::ChainIdNotAscii { .. }
+    );
+    assert_matches!(
+        block_context_with_chain_id(&"a".repeat(32)).validate_chain_id().unwrap_err(),
+        BlockContextError::ChainIdTooLong { length: 32, max_length: 31, .. }
+    );
+}
+
+#[test]
+fn test_validate_timestamp() {
+    let block_context_with_timestamps =
+        |block_timestamp: u64, prev_block_timestamp: Option<u64>| {
+            let mut block_context = BlockContext::create_for_testing();
+            block_context.block_info.block_timestamp = BlockTimestamp(block_timestamp);
+            block_context.block_info.prev_block_timestamp =
+                prev_block_timestamp.map(BlockTimestamp);
+            block_context
+        };
+
+    // Monotonic timestamps.
+    block_context_with_timestamps(10, None).validate_timestamp().unwrap();
+    block_context_with_timestamps(10, Some(9)).validate_timestamp().unwrap();
+    block_context_with_timestamps(10, Some(10)).validate_timestamp().unwrap();
+
+    // Regressing timestamp.
+    assert_matches!(
+        block_context_with_timestamps(10, Some(11)).validate_timestamp().unwrap_err(),
+        BlockContextError::TimestampRegression { block_timestamp: 10, prev_block_timestamp: 11 }
+    );
+}
+
+#[test]
+fn test_fee_split_validation() {
+    let (first, second) = (contract_address!("0x1"), contract_address!("0x2"));
+
+    FeeSplit::new(vec![(first, 100)]).unwrap();
+    FeeSplit::new(vec![(first, 60), (second, 40)]).unwrap();
+    assert_matches!(
+        FeeSplit::new(vec![]).unwrap_err(),
+        BlockContextError::InvalidFeeSplit { total_percentage: 0 }
+    );
+    assert_matches!(
+        FeeSplit::new(vec![(first, 60), (second, 60)]).unwrap_err(),
+        BlockContextError::InvalidFeeSplit { total_percentage: 120 }
+    );
+    assert_matches!(
+        FeeSplit::new(vec![(first, 255), (second, 255)]).unwrap_err(),
+        BlockContextError::InvalidFeeSplit { total_percentage: 510 }
+    );
+}
+
+#[test]
+fn test_fee_recipient_transfers() {
+    let sequencer_address = contract_address!("0x5e9");
+    let (first, second, third) =
+        (contract_address!("0x1"), contract_address!("0x2"), contract_address!("0x3"));
+
+    assert_eq!(
+        FeeRecipient::Sequencer.transfers(sequencer_address, Fee(1000)),
+        vec![(sequencer_address, Fee(1000))]
+    );
+    assert_eq!(
+        FeeRecipient::Burn(first).transfers(sequencer_address, Fee(1000)),
+        vec![(first, Fee(1000))]
+    );
+    assert_eq!(FeeRecipient::Sequencer.transfers(sequencer_address, Fee(0)), vec![]);
+
+    let split =
+        FeeRecipient::Split(FeeSplit::new(vec![(first, 50), (second, 30), (third, 20)]).unwrap());
+    assert_eq!(
+        split.transfers(sequencer_address, Fee(1000)),
+        vec![(first, Fee(500)), (second, Fee(300)), (third, Fee(200))]
+    );
+    // The rounding remainder goes to the first share.
+    assert_eq!(
+        split.transfers(sequencer_address, Fee(1001)),
+        vec![(first, Fee(501)), (second, Fee(300)), (third, Fee(200))]
+    );
+    // Zero-amount transfers are omitted.
+    assert_eq!(split.transfers(sequencer_address, Fee(3)), vec![(first, Fee(3))]);
+    // No overflow on large fees.
+    assert_eq!(
+        FeeRecipient::Split(FeeSplit::new(vec![(first, 1), (second, 99)]).unwrap())
+            .transfers(sequencer_address, Fee(u128::MAX))
+            .into_iter()
+            .map(|(_, amount)| amount.0)
+            .fold(0_u128, |total, amount| total.checked_add(amount).unwrap()),
+        u128::MAX
+    );
+}
+
+#[test]
+fn test_block_context_fee_transfers() {
+    let block_context = BlockContext::create_for_testing();
+    assert_eq!(
+        block_context.fee_transfers(Fee(7)),
+        vec![(block_context.block_info.sequencer_address, Fee(7))]
+    );
+}
+
+#[test]
+fn test_syscall_gas_costs_below_base_cost() {
+    let syscall_gas_costs =
+        |gas_cost| SyscallGasCosts::new(HashMap::from([(SyscallSelector::StorageRead, gas_cost)]));
+
+    let syscall_gas_costs_at_base_cost = syscall_gas_costs(SYSCALL_BASE_GAS_COST).unwrap();
+    assert_eq!(
+        syscall_gas_costs_at_base_cost.get(&SyscallSelector::StorageRead),
+        Some(SYSCALL_BASE_GAS_COST)
+    );
+    assert_matches!(
+        syscall_gas_costs(SYSCALL_BASE_GAS_COST - 1).unwrap_err(),
+        BlockContextError::SyscallGasCostTooLow {
+            selector: SyscallSelector::StorageRead,
+            gas_cost,
+            base_gas_cost: SYSCALL_BASE_GAS_COST,
+        } if gas_cost == SYSCALL_BASE_GAS_COST - 1
+    );
+}
+
+#[test]
+fn test_calldata_l1_gas_costs() {
+    // Calldata and signature are not charged by default.
+    assert_eq!(CalldataL1GasCosts::default().l1_gas(100, 10), 0);
+    let calldata_l1_gas_costs = CalldataL1GasCosts { per_calldata_felt: 16, per_signature_felt: 8 };
+    assert_eq!(calldata_l1_gas_costs.l1_gas(100, 10), 1680);
+}
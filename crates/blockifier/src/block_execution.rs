@@ -1,10 +1,19 @@
+use cairo_felt::Felt252;
 use starknet_api::block::{BlockHash, BlockNumber};
 use starknet_api::core::ContractAddress;
-use starknet_api::hash::StarkFelt;
+use starknet_api::hash::{pedersen_hash, pedersen_hash_array, StarkFelt};
 use starknet_api::state::StorageKey;
+use starknet_api::transaction::{TransactionHash, TransactionSignature};
+use thiserror::Error;
 
 use crate::abi::constants;
-use crate::state::state_api::{State, StateResult};
+use crate::block_context::{BlockContext, BlockResourceLimits};
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::{State, StateReader, StateResult};
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
 
 #[cfg(test)]
 #[path = "block_execution_test.rs"]
@@ -29,3 +38,178 @@ pub fn pre_process_block(
 
     Ok(())
 }
+
+// Block execution.
+
+/// Executes the given transactions, in order, against the given state, charging fees and running
+/// validation. Each transaction is isolated: its state changes are applied if it executes
+/// (possibly reverted, in which case only its fee charge is applied), and discarded if it fails;
+/// see [ExecutableTransaction::execute].
+/// If `stop_on_error` is set, stops at the first failed transaction; the results then cover the
+/// executed transactions only.
+pub fn execute_transactions<S: StateReader>(
+    txs: Vec<Transaction>,
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    stop_on_error: bool,
+) -> Vec<TransactionExecutionResult<TransactionExecutionInfo>> {
+    let mut results = Vec::with_capacity(txs.len());
+    for tx in txs {
+        let result = tx.execute(state, block_context, true, true);
+        let failed = result.is_err();
+        results.push(result);
+        if failed && stop_on_error {
+            break;
+        }
+    }
+
+    results
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum BlockExecutionError {
+    #[error("Block is full: {resource} usage {usage} exceeds the block limit {limit}.")]
+    BlockFull { resource: String, usage: usize, limit: usize },
+}
+
+/// Accumulates the resources of the transactions executed in a block, to signal when the block is
+/// full w.r.t. the chain's [BlockResourceLimits].
+#[derive(Clone, Debug)]
+pub struct BlockResourceTracker {
+    limits: BlockResourceLimits,
+    n_steps: usize,
+    l1_gas_usage: usize,
+    n_events: usize,
+}
+
+impl BlockResourceTracker {
+    pub fn new(block_context: &BlockContext) -> Self {
+        Self {
+            limits: block_context.chain_info.block_resource_limits,
+            n_steps: 0,
+            l1_gas_usage: 0,
+            n_events: 0,
+        }
+    }
+
+    /// Adds the resources of an executed transaction to the block's usage. Fails with
+    /// [BlockExecutionError::BlockFull] if that exceeds a limit, in which case the usage is left
+    /// unchanged.
+    /// As the transaction must then be excluded from the block, execute it on a transactional state
+    /// (see [CachedState::create_transactional]), and commit that state only if this succeeds.
+    pub fn add(
+        &mut self,
+        tx_execution_info: &TransactionExecutionInfo,
+    ) -> Result<(), BlockExecutionError> {
+        let actual_resources = &tx_execution_info.actual_resources;
+        let n_steps = self.n_steps + actual_resources.get_or_zero(constants::N_STEPS_RESOURCE);
+        let l1_gas_usage = self.l1_gas_usage + actual_resources.get_or_zero(constants::GAS_USAGE);
+        let n_events = self.n_events + tx_execution_info.n_events();
+
+        for (resource, usage, limit) in [
+            (constants::N_STEPS_RESOURCE, n_steps, self.limits.max_n_steps),
+            (constants::GAS_USAGE, l1_gas_usage, self.limits.max_l1_gas_usage),
+            (constants::N_EVENTS_RESOURCE, n_events, self.limits.max_n_events),
+        ] {
+            if let Some(limit) = limit {
+                if usage > limit {
+                    return Err(BlockExecutionError::BlockFull {
+                        resource: resource.to_string(),
+                        usage,
+                        limit,
+                    });
+                }
+            }
+        }
+
+        self.n_steps = n_steps;
+        self.l1_gas_usage = l1_gas_usage;
+        self.n_events = n_events;
+        Ok(())
+    }
+
+    pub fn n_steps(&self) -> usize {
+        self.n_steps
+    }
+
+    pub fn l1_gas_usage(&self) -> usize {
+        self.l1_gas_usage
+    }
+
+    pub fn n_events(&self) -> usize {
+        self.n_events
+    }
+}
+
+// Block post-processing.
+
+/// The height of the Patricia tree committing to the transactions of a block.
+pub const TRANSACTION_COMMITMENT_TREE_HEIGHT: u8 = 64;
+
+/// Returns the commitment to the transactions of a block, given their hashes and signatures: the
+/// root of a height-64 Patricia tree, whose i-th leaf is `h(tx_hash, h(signature))` of the i-th
+/// transaction. Transactions without a signature (e.g., L1 handlers) take an empty one.
+/// The signatures are taken alongside the hashes since the protocol's leaves commit to both; the
+/// hashes alone do not determine the commitment.
+pub fn transaction_commitment(txs: &[(TransactionHash, TransactionSignature)]) -> StarkFelt {
+    let leaves: Vec<(u64, StarkFelt)> = txs
+        .iter()
+        .enumerate()
+        .map(|(index, (tx_hash, signature))| {
+            (index as u64, pedersen_hash(&tx_hash.0, &pedersen_hash_array(&signature.0)))
+        })
+        .collect();
+
+    match build_patricia_node(&leaves, TRANSACTION_COMMITMENT_TREE_HEIGHT) {
+        Some(root) => root.hash(),
+        None => StarkFelt::default(),
+    }
+}
+
+/// A Patricia tree node, represented as a path of `length` edges (possibly zero) leading down to
+/// a binary node or a leaf with the given hash.
+struct PatriciaNode {
+    bottom_hash: StarkFelt,
+    path: u64,
+    length: u8,
+}
+
+impl PatriciaNode {
+    fn hash(&self) -> StarkFelt {
+        if self.length == 0 {
+            return self.bottom_hash;
+        }
+
+        let edge_hash = pedersen_hash(&self.bottom_hash, &StarkFelt::from(self.path));
+        felt_to_stark_felt(&(stark_felt_to_felt(edge_hash) + Felt252::from(self.length)))
+    }
+}
+
+/// Builds the subtree of the given height over the given (index-sorted) leaves, whose indices
+/// agree on all bits above that height. Returns `None` for an empty subtree.
+fn build_patricia_node(leaves: &[(u64, StarkFelt)], height: u8) -> Option<PatriciaNode> {
+    if leaves.is_empty() {
+        return None;
+    }
+    if height == 0 {
+        return Some(PatriciaNode { bottom_hash: leaves[0].1, path: 0, length: 0 });
+    }
+
+    let bit = height - 1;
+    let split_index = leaves.partition_point(|(index, _leaf)| (index >> bit) & 1 == 0);
+    let (left_leaves, right_leaves) = leaves.split_at(split_index);
+    match (build_patricia_node(left_leaves, bit), build_patricia_node(right_leaves, bit)) {
+        (Some(left), Some(right)) => Some(PatriciaNode {
+            bottom_hash: pedersen_hash(&left.hash(), &right.hash()),
+            path: 0,
+            length: 0,
+        }),
+        (Some(child), None) => Some(PatriciaNode { length: child.length + 1, ..child }),
+        (None, Some(child)) => Some(PatriciaNode {
+            path: (1 << child.length) | child.path,
+            length: child.length + 1,
+            ..child
+        }),
+        (None, None) => unreachable!("A non-empty subtree has at least one non-empty child."),
+    }
+}
@@ -1,12 +1,29 @@
+use cairo_felt::Felt252;
+use rstest::rstest;
 use starknet_api::block::{BlockHash, BlockNumber};
-use starknet_api::core::ContractAddress;
-use starknet_api::hash::StarkFelt;
+use starknet_api::core::{ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{pedersen_hash, StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Fee, TransactionHash, TransactionSignature, TransactionVersion};
+use starknet_api::{patricia_key, stark_felt};
 
 use crate::abi::constants;
-use crate::block_execution::pre_process_block;
+use crate::block_context::BlockContext;
+use crate::block_execution::{
+    execute_transactions, pre_process_block, transaction_commitment, BlockExecutionError,
+    BlockResourceTracker,
+};
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::invoke_tx_args;
+use crate::state::cached_state::CachedState;
 use crate::state::state_api::StateReader;
 use crate::test_utils::cached_state::create_test_state;
+use crate::test_utils::{create_calldata, CairoVersion};
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, max_fee, TestInitData,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
 
 #[test]
 fn test_pre_process_block() {
@@ -23,3 +40,148 @@ fn test_pre_process_block() {
     );
     assert_eq!(written_hash.unwrap(), block_hash);
 }
+
+#[test]
+fn test_transaction_commitment() {
+    assert_eq!(transaction_commitment(&[]), StarkFelt::default());
+
+    let txs = [
+        (TransactionHash(stark_felt!(1_u8)), TransactionSignature::default()),
+        (
+            TransactionHash(stark_felt!(2_u8)),
+            TransactionSignature(vec![stark_felt!(3_u8), stark_felt!(4_u8)]),
+        ),
+    ];
+    // h(h(h(0, a_1), a_2), n) over the signature [a_1, a_2].
+    let empty_signature_hash = pedersen_hash(&StarkFelt::default(), &StarkFelt::default());
+    let signature_hash = pedersen_hash(
+        &pedersen_hash(
+            &pedersen_hash(&StarkFelt::default(), &stark_felt!(3_u8)),
+            &stark_felt!(4_u8),
+        ),
+        &stark_felt!(2_u8),
+    );
+    let leaves = [
+        pedersen_hash(&stark_felt!(1_u8), &empty_signature_hash),
+        pedersen_hash(&stark_felt!(2_u8), &signature_hash),
+    ];
+    // An edge node: h(child, path) + length.
+    let edge_hash = |child: &StarkFelt, length: u8| {
+        let hash = pedersen_hash(child, &StarkFelt::default());
+        felt_to_stark_felt(&(stark_felt_to_felt(hash) + Felt252::from(length)))
+    };
+
+    // A single leaf at index 0 is reached through a 64-long edge of zeros.
+    assert_eq!(transaction_commitment(&txs[..1]), edge_hash(&leaves[0], 64));
+
+    // Two leaves (indices 0 and 1) split at the bottom level, below a 63-long edge of zeros.
+    let expected_commitment = edge_hash(&pedersen_hash(&leaves[0], &leaves[1]), 63);
+    assert_eq!(transaction_commitment(&txs), expected_commitment);
+
+    // The signatures are committed to.
+    let unsigned_txs =
+        txs.clone().map(|(tx_hash, _signature)| (tx_hash, TransactionSignature::default()));
+    assert_ne!(transaction_commitment(&unsigned_txs), expected_commitment);
+}
+
+#[rstest]
+#[case::continue_on_error(false)]
+#[case::stop_on_error(true)]
+fn test_execute_transactions(
+    max_fee: Fee,
+    block_context: BlockContext,
+    #[case] stop_on_error: bool,
+) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+    let mut invoke_tx = |function_name: &str, key: u8, value: u8, nonce: Option<Nonce>| {
+        let calldata = create_calldata(
+            contract_address,
+            function_name,
+            &[stark_felt!(key), stark_felt!(value)],
+        );
+        Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata,
+            version: TransactionVersion::ONE,
+            nonce: nonce.unwrap_or_else(|| nonce_manager.next(account_address)),
+        }))
+    };
+    let txs = vec![
+        invoke_tx("test_storage_read_write", 15, 1, None),
+        // Reverted; only its fee is charged.
+        invoke_tx("write_and_revert", 16, 2, None),
+        // Fails, due to an invalid nonce.
+        invoke_tx("test_storage_read_write", 17, 3, Some(Nonce(stark_felt!(100_u8)))),
+        invoke_tx("test_storage_read_write", 18, 4, None),
+    ];
+
+    let results = execute_transactions(txs, &mut state, &block_context, stop_on_error);
+    assert_eq!(results.len(), if stop_on_error { 3 } else { 4 });
+    assert!(!results[0].as_ref().unwrap().is_reverted());
+    assert!(results[1].as_ref().unwrap().is_reverted());
+    assert!(results[2].is_err());
+
+    let mut storage_at =
+        |key: u8| state.get_storage_at(contract_address, StorageKey(patricia_key!(key))).unwrap();
+    assert_eq!(storage_at(15), stark_felt!(1_u8));
+    assert_eq!(storage_at(16), stark_felt!(0_u8));
+    assert_eq!(storage_at(17), stark_felt!(0_u8));
+    let (expected_value, expected_nonce) = if stop_on_error { (0_u8, 2_u8) } else { (4_u8, 3_u8) };
+    assert_eq!(storage_at(18), stark_felt!(expected_value));
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), Nonce(stark_felt!(expected_nonce)));
+}
+
+#[rstest]
+fn test_block_resource_tracker(max_fee: Fee, mut block_context: BlockContext) {
+    let max_n_steps = 50000;
+    block_context.chain_info_mut().block_resource_limits.max_n_steps = Some(max_n_steps);
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+    let mut tracker = BlockResourceTracker::new(&block_context);
+
+    // Feed transactions until the block is full.
+    let mut n_txs_in_block: u64 = 0;
+    let (tx_n_steps, error) = loop {
+        let tx = account_invoke_tx(invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata: create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]),
+            version: TransactionVersion::ONE,
+            nonce: nonce_manager.next(account_address),
+        });
+        let mut transactional_state = CachedState::create_transactional(&mut state);
+        let tx_execution_info =
+            tx.execute(&mut transactional_state, &block_context, true, true).unwrap();
+        let tx_n_steps = tx_execution_info.actual_resources.n_steps().unwrap();
+        match tracker.add(&tx_execution_info) {
+            Ok(()) => {
+                transactional_state.commit();
+                n_txs_in_block += 1;
+            }
+            Err(error) => {
+                transactional_state.abort();
+                break (tx_n_steps, error);
+            }
+        }
+    };
+
+    assert!(n_txs_in_block > 1);
+    // The usage excludes the transaction that does not fit.
+    let n_steps = tracker.n_steps();
+    assert!(n_steps <= max_n_steps);
+    assert_eq!(
+        error,
+        BlockExecutionError::BlockFull {
+            resource: constants::N_STEPS_RESOURCE.to_string(),
+            usage: n_steps + tx_n_steps,
+            limit: max_n_steps,
+        }
+    );
+    // The state changes of the transaction that does not fit are discarded.
+    assert_eq!(
+        state.get_nonce_at(account_address).unwrap(),
+        Nonce(StarkFelt::from(n_txs_in_block))
+    );
+}
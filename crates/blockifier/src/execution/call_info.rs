@@ -1,12 +1,14 @@
 use std::collections::HashSet;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
-use starknet_api::core::{ClassHash, EthAddress};
+use starknet_api::core::{ClassHash, EntryPointSelector, EthAddress};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{EventContent, L2ToL1Payload};
 
+use crate::abi::sierra_types::{felt_to_u128, SierraU256};
 use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::execution_utils::stark_felt_to_felt;
 use crate::state::cached_state::StorageEntry;
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::TransactionExecutionResult;
@@ -53,6 +55,31 @@ pub struct CallExecution {
     pub gas_consumed: u64,
 }
 
+impl CallExecution {
+    /// Returns the returned felts.
+    pub fn retdata_felts(&self) -> &[StarkFelt] {
+        &self.retdata.0
+    }
+
+    /// Returns the returned felt, if exactly one was returned.
+    pub fn as_single_felt(&self) -> Option<StarkFelt> {
+        match self.retdata_felts() {
+            [felt] => Some(*felt),
+            _ => None,
+        }
+    }
+
+    /// Returns the returned `u256`, if exactly one was returned; i.e., if the retdata consists of
+    /// its low and high `u128` words.
+    pub fn as_u256(&self) -> Option<SierraU256> {
+        let to_u128 = |felt: &StarkFelt| felt_to_u128(&stark_felt_to_felt(*felt)).ok();
+        match self.retdata_felts() {
+            [low, high] => Some(SierraU256 { low_val: to_u128(low)?, high_val: to_u128(high)? }),
+            _ => None,
+        }
+    }
+}
+
 /// Represents the full effects of executing an entry point, including the inner calls it invoked.
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct CallInfo {
@@ -81,6 +108,45 @@ impl CallInfo {
         class_hashes
     }
 
+    /// Returns an iterator over this call and its inner calls in pre-order, along with their
+    /// nesting depth relative to this call.
+    pub fn iter_with_depth(&self) -> CallInfoWithDepthIter<'_> {
+        CallInfoWithDepthIter { call_infos: vec![(0, self)] }
+    }
+
+    /// Returns the calls, including this one, whose entry point matches the given predicate;
+    /// in pre-order.
+    pub fn filter_calls(&self, predicate: impl Fn(&CallEntryPoint) -> bool) -> Vec<&CallInfo> {
+        self.into_iter().filter(|call_info| predicate(&call_info.call)).collect()
+    }
+
+    /// Returns the calls, including this one, into the given class; in pre-order.
+    pub fn calls_to_class(&self, class_hash: ClassHash) -> Vec<&CallInfo> {
+        self.filter_calls(|call| call.class_hash == Some(class_hash))
+    }
+
+    /// Returns the calls, including this one, to the given entry point selector; in pre-order.
+    pub fn calls_to_selector(&self, selector: EntryPointSelector) -> Vec<&CallInfo> {
+        self.filter_calls(|call| call.entry_point_selector == selector)
+    }
+
+    /// Returns the resources of this call (including those of its inner calls), shaped as
+    /// cairo-lang's `ExecutionResources`: builtins with no instances are omitted, as done by
+    /// cairo-lang's `filter_unused_builtins`.
+    pub fn to_cairo_lang_resources(&self) -> VmExecutionResources {
+        let VmExecutionResources { n_steps, n_memory_holes, builtin_instance_counter } =
+            &self.vm_resources;
+        VmExecutionResources {
+            n_steps: *n_steps,
+            n_memory_holes: *n_memory_holes,
+            builtin_instance_counter: builtin_instance_counter
+                .iter()
+                .filter(|(_, &count)| count > 0)
+                .map(|(builtin_name, &count)| (builtin_name.clone(), count))
+                .collect(),
+        }
+    }
+
     /// Returns the set of storage entries visited during this call execution.
     // TODO: Add unit test for this method
     pub fn get_visited_storage_entries(&self) -> HashSet<StorageEntry> {
@@ -152,6 +218,25 @@ impl<'a> Iterator for CallInfoIter<'a> {
     }
 }
 
+/// Iterates over a call and its inner calls in pre-order, yielding each call with its nesting
+/// depth (the depth of the root call is 0).
+pub struct CallInfoWithDepthIter<'a> {
+    call_infos: Vec<(usize, &'a CallInfo)>,
+}
+
+impl<'a> Iterator for CallInfoWithDepthIter<'a> {
+    type Item = (usize, &'a CallInfo);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (depth, call_info) = self.call_infos.pop()?;
+
+        // Push order is right to left.
+        self.call_infos
+            .extend(call_info.inner_calls.iter().rev().map(|inner_call| (depth + 1, inner_call)));
+        Some((depth, call_info))
+    }
+}
+
 impl<'a> IntoIterator for &'a CallInfo {
     type Item = &'a CallInfo;
     type IntoIter = CallInfoIter<'a>;
@@ -1,6 +1,9 @@
-use std::collections::HashMap;
-use std::ops::Deref;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::{Deref, RangeInclusive};
+use std::path::Path;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::{fmt, fs};
 
 use cairo_felt::Felt252;
 use cairo_lang_casm;
@@ -12,25 +15,34 @@ use cairo_vm::serde::deserialize_program::{
 use cairo_vm::types::errors::program_errors::ProgramError;
 use cairo_vm::types::program::Program;
 use cairo_vm::types::relocatable::MaybeRelocatable;
-use cairo_vm::vm::runners::builtin_runner::{HASH_BUILTIN_NAME, POSEIDON_BUILTIN_NAME};
+use cairo_vm::vm::runners::builtin_runner::{
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+    POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
+};
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
 use serde::de::Error as DeserializationError;
 use serde::{Deserialize, Deserializer};
-use starknet_api::core::EntryPointSelector;
+use starknet_api::core::{CompiledClassHash, EntryPointSelector};
 use starknet_api::deprecated_contract_class::{
     ContractClass as DeprecatedContractClass, EntryPoint, EntryPointOffset, EntryPointType,
     Program as DeprecatedProgram,
 };
+use starknet_api::hash::{pedersen_hash_array, StarkFelt};
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants::{self, CONSTRUCTOR_ENTRY_POINT_NAME};
 use crate::execution::entry_point::CallEntryPoint;
-use crate::execution::errors::PreExecutionError;
+use crate::execution::errors::{ContractClassError, PreExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, sn_api_to_cairo_vm_program};
+
+#[cfg(test)]
+#[path = "contract_class_test.rs"]
+mod test;
+
 /// Represents a runnable Starknet contract class (meaning, the program is runnable by the VM).
 /// We wrap the actual class in an Arc to avoid cloning the program when cloning the class.
 // Note: when deserializing from a SN API class JSON string, the ABI field is ignored
-// by serde, since it is not required for execution.
+// by serde, since it is not required for execution; see [ContractClassWithAbi] to retain it.
 #[derive(Clone, Debug, Eq, PartialEq, derive_more::From)]
 pub enum ContractClass {
     V0(ContractClassV0),
@@ -51,6 +63,143 @@ impl ContractClass {
             ContractClass::V1(class) => class.estimate_casm_hash_computation_resources(),
         }
     }
+
+    pub fn bytecode_length(&self) -> usize {
+        match self {
+            ContractClass::V0(class) => class.bytecode_length(),
+            ContractClass::V1(class) => class.bytecode_length(),
+        }
+    }
+
+    /// Returns the class's full dispatch table: its entry points of all types, by type
+    /// (constructor, external, L1 handler), and within each type in the class's order.
+    pub fn all_entry_points(&self) -> Vec<(EntryPointType, EntryPointSelector, EntryPointOffset)> {
+        match self {
+            ContractClass::V0(class) => {
+                flatten_entry_points(&class.entry_points_by_type, |entry_point| {
+                    (entry_point.selector, entry_point.offset)
+                })
+            }
+            ContractClass::V1(class) => {
+                flatten_entry_points(&class.entry_points_by_type, |entry_point| {
+                    (entry_point.selector, entry_point.offset)
+                })
+            }
+        }
+    }
+
+    /// Returns the names of the builtins the class requires (e.g., "pedersen_builtin"): those of
+    /// the program for Cairo 0 classes, and those of any entry point for Cairo 1 classes.
+    pub fn required_builtins(&self) -> HashSet<String> {
+        match self {
+            ContractClass::V0(class) => {
+                class.program.iter_builtins().map(|builtin| builtin.name().to_string()).collect()
+            }
+            ContractClass::V1(class) => class
+                .entry_points_by_type
+                .values()
+                .flatten()
+                .flat_map(|entry_point| entry_point.builtins.iter().cloned())
+                .collect(),
+        }
+    }
+
+    /// Verifies that all builtins the class requires are among the allowed ones; guards against
+    /// running classes that require builtins unknown to the executing version.
+    pub fn check_supported_builtins(
+        &self,
+        allowed: &HashSet<String>,
+    ) -> Result<(), PreExecutionError> {
+        let mut unsupported_builtins: Vec<String> =
+            self.required_builtins().difference(allowed).cloned().collect();
+        unsupported_builtins.sort();
+        match unsupported_builtins.into_iter().next() {
+            Some(builtin) => Err(PreExecutionError::UnsupportedBuiltin(builtin)),
+            None => Ok(()),
+        }
+    }
+
+    /// Parses a compiled (Casm) contract class, verifying that its compiled class hash matches
+    /// the expected one. Catches corrupted class files at load time.
+    pub fn try_from_json_with_expected_hash(
+        raw_contract_class: &str,
+        expected: CompiledClassHash,
+    ) -> Result<Self, ContractClassError> {
+        let CasmContractClassWithSierraProgramHash { casm_contract_class, sierra_program_hash } =
+            serde_json::from_str(raw_contract_class)
+                .map_err(|err| ContractClassError::ProgramError(err.into()))?;
+        let computed =
+            CompiledClassHash(felt_to_stark_felt(&casm_contract_class.compiled_class_hash()));
+        if computed != expected {
+            return Err(ContractClassError::CompiledClassHashMismatch { expected, computed });
+        }
+
+        Ok(ContractClassV1::try_from(casm_contract_class)?
+            .with_sierra_program_hash(sierra_program_hash)
+            .into())
+    }
+}
+
+/// Lists the given entry points by type (constructor, external, L1 handler), and within each type
+/// in the given order, with their selectors and offsets.
+fn flatten_entry_points<EP>(
+    entry_points_by_type: &HashMap<EntryPointType, Vec<EP>>,
+    selector_and_offset: impl Fn(&EP) -> (EntryPointSelector, EntryPointOffset),
+) -> Vec<(EntryPointType, EntryPointSelector, EntryPointOffset)> {
+    let entry_point_types =
+        [EntryPointType::Constructor, EntryPointType::External, EntryPointType::L1Handler];
+    let selector_and_offset = &selector_and_offset;
+    entry_point_types
+        .into_iter()
+        .flat_map(|typ| {
+            entry_points_by_type.get(&typ).into_iter().flatten().map(move |entry_point| {
+                let (selector, offset) = selector_and_offset(entry_point);
+                (typ, selector, offset)
+            })
+        })
+        .collect()
+}
+
+/// A runnable contract class, along with the raw JSON of its ABI, for tooling (e.g., explorers).
+/// Execution ignores the ABI.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractClassWithAbi {
+    pub contract_class: ContractClass,
+    pub abi: Option<String>,
+}
+
+impl ContractClassWithAbi {
+    /// Parses a compiled (Cairo 0) contract class, retaining its ABI.
+    pub fn try_from_v0_json_string(raw_contract_class: &str) -> Result<Self, ProgramError> {
+        Ok(Self {
+            contract_class: ContractClassV0::try_from_json_string(raw_contract_class)?.into(),
+            abi: parse_abi(raw_contract_class)?,
+        })
+    }
+
+    /// Parses a compiled (Casm) contract class, retaining its ABI.
+    pub fn try_from_v1_json_string(raw_contract_class: &str) -> Result<Self, ProgramError> {
+        Ok(Self {
+            contract_class: ContractClassV1::try_from_json_string(raw_contract_class)?.into(),
+            abi: parse_abi(raw_contract_class)?,
+        })
+    }
+}
+
+/// Reads the (optional) ABI from a raw class, as JSON; an ABI given as a string (as in Sierra
+/// classes) is returned as is.
+fn parse_abi(raw_contract_class: &str) -> serde_json::Result<Option<String>> {
+    #[derive(Deserialize)]
+    struct Abi {
+        #[serde(default)]
+        abi: Option<serde_json::Value>,
+    }
+
+    let Abi { abi } = serde_json::from_str(raw_contract_class)?;
+    Ok(abi.map(|abi| match abi {
+        serde_json::Value::String(abi) => abi,
+        abi => abi.to_string(),
+    }))
 }
 
 // V0.
@@ -81,6 +230,28 @@ impl ContractClassV0 {
         self.program.data_len()
     }
 
+    /// Returns a content hash of the program, suitable as a cache key: the Pedersen hash of the
+    /// hash chains of the program builtins (as short strings) and of the program bytecode.
+    /// The hinted portions of the class (hints, identifiers, reference manager and debug info), as
+    /// well as its entry points and ABI, do not affect the hash; hence, classes differing only in
+    /// them, or in their JSON formatting, share the same program hash.
+    pub fn program_hash(&self) -> StarkFelt {
+        let builtins: Vec<StarkFelt> = self
+            .program
+            .iter_builtins()
+            .map(|builtin| felt_to_stark_felt(&Felt252::from_bytes_be(builtin.name().as_bytes())))
+            .collect();
+        // Compiled programs consist of integers only.
+        let data: Vec<StarkFelt> = self
+            .program
+            .iter_data()
+            .filter_map(MaybeRelocatable::get_int_ref)
+            .map(felt_to_stark_felt)
+            .collect();
+
+        pedersen_hash_array(&[pedersen_hash_array(&builtins), pedersen_hash_array(&data)])
+    }
+
     fn estimate_casm_hash_computation_resources(&self) -> VmExecutionResources {
         let hashed_data_size = (constants::CAIRO0_ENTRY_POINT_STRUCT_SIZE * self.n_entry_points())
             + self.n_builtins()
@@ -99,6 +270,12 @@ impl ContractClassV0 {
         }
     }
 
+    /// Loads a compiled (Cairo 0) contract class from a JSON file.
+    pub fn try_from_file(path: impl AsRef<Path>) -> Result<ContractClassV0, ContractClassError> {
+        let raw_contract_class = fs::read_to_string(path)?;
+        Ok(Self::try_from_json_string(&raw_contract_class)?)
+    }
+
     pub fn try_from_json_string(raw_contract_class: &str) -> Result<ContractClassV0, ProgramError> {
         let contract_class: ContractClassV0Inner = serde_json::from_str(raw_contract_class)?;
         Ok(ContractClassV0(Arc::new(contract_class)))
@@ -143,6 +320,38 @@ impl ContractClassV1 {
         self.program.data_len()
     }
 
+    /// Returns the gas cost of a single application of each builtin, keyed by builtin name.
+    pub fn builtin_costs() -> BTreeMap<String, u64> {
+        [
+            (BITWISE_BUILTIN_NAME, constants::BITWISE_GAS_COST),
+            (EC_OP_BUILTIN_NAME, constants::EC_OP_GAS_COST),
+            (HASH_BUILTIN_NAME, constants::PEDERSEN_GAS_COST),
+            (KECCAK_BUILTIN_NAME, constants::KECCAK_BUILTIN_GAS_COST),
+            (POSEIDON_BUILTIN_NAME, constants::POSEIDON_GAS_COST),
+            (RANGE_CHECK_BUILTIN_NAME, constants::RANGE_CHECK_GAS_COST),
+            (SIGNATURE_BUILTIN_NAME, constants::ECDSA_GAS_COST),
+        ]
+        .into_iter()
+        .map(|(builtin_name, cost)| (builtin_name.to_string(), cost))
+        .collect()
+    }
+
+    /// Returns the version of the compiler that produced this (Casm) class.
+    pub fn compiler_version(&self) -> Result<CompilerVersion, ContractClassError> {
+        self.compiler_version.parse()
+    }
+
+    /// Returns the hash of the Sierra program this class was compiled from, if it was present in
+    /// the source JSON.
+    pub fn sierra_program_hash(&self) -> Option<StarkFelt> {
+        self.0.sierra_program_hash
+    }
+
+    fn with_sierra_program_hash(mut self, sierra_program_hash: Option<StarkFelt>) -> Self {
+        Arc::make_mut(&mut self.0).sierra_program_hash = sierra_program_hash;
+        self
+    }
+
     pub fn get_entry_point(
         &self,
         call: &CallEntryPoint,
@@ -187,9 +396,17 @@ impl ContractClassV1 {
         }
     }
 
+    /// Loads a compiled (Casm) contract class from a JSON file.
+    pub fn try_from_file(path: impl AsRef<Path>) -> Result<ContractClassV1, ContractClassError> {
+        let raw_contract_class = fs::read_to_string(path)?;
+        Ok(Self::try_from_json_string(&raw_contract_class)?)
+    }
+
     pub fn try_from_json_string(raw_contract_class: &str) -> Result<ContractClassV1, ProgramError> {
-        let casm_contract_class: CasmContractClass = serde_json::from_str(raw_contract_class)?;
-        let contract_class: ContractClassV1 = casm_contract_class.try_into()?;
+        let CasmContractClassWithSierraProgramHash { casm_contract_class, sierra_program_hash } =
+            serde_json::from_str(raw_contract_class)?;
+        let contract_class = ContractClassV1::try_from(casm_contract_class)?
+            .with_sierra_program_hash(sierra_program_hash);
 
         Ok(contract_class)
     }
@@ -200,6 +417,18 @@ pub struct ContractClassV1Inner {
     pub program: Program,
     pub entry_points_by_type: HashMap<EntryPointType, Vec<EntryPointV1>>,
     pub hints: HashMap<String, Hint>,
+    pub compiler_version: String,
+    pub sierra_program_hash: Option<StarkFelt>,
+}
+
+/// A raw Casm class, along with its (optional) Sierra program hash, which is not part of
+/// `CasmContractClass`; allows reading both in a single pass.
+#[derive(Deserialize)]
+struct CasmContractClassWithSierraProgramHash {
+    #[serde(flatten)]
+    casm_contract_class: CasmContractClass,
+    #[serde(default)]
+    sierra_program_hash: Option<StarkFelt>,
 }
 
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
@@ -277,10 +506,55 @@ impl TryFrom<CasmContractClass> for ContractClassV1 {
             program,
             entry_points_by_type,
             hints: string_to_hint,
+            compiler_version: class.compiler_version,
+            sierra_program_hash: None,
         })))
     }
 }
 
+/// A (semantic) version of the Cairo compiler.
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct CompilerVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl FromStr for CompilerVersion {
+    type Err = ContractClassError;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        let invalid_version = || ContractClassError::InvalidCompilerVersion(version.to_string());
+        // Ignore pre-release and build metadata (e.g., "2.5.0-dev.0").
+        let core_version = version.split(['-', '+']).next().unwrap_or_default();
+        let components = core_version
+            .split('.')
+            .map(|component| component.parse::<u64>().map_err(|_| invalid_version()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match components[..] {
+            [major, minor, patch] => Ok(Self { major, minor, patch }),
+            _ => Err(invalid_version()),
+        }
+    }
+}
+
+impl CompilerVersion {
+    /// Returns whether this version is within the given supported versions; the upper bound is
+    /// compared on (major, minor) only, so that any of its patch versions is supported.
+    pub fn is_supported(&self, supported_versions: &RangeInclusive<CompilerVersion>) -> bool {
+        let max_version = supported_versions.end();
+        self >= supported_versions.start()
+            && (self.major, self.minor) <= (max_version.major, max_version.minor)
+    }
+}
+
+impl fmt::Display for CompilerVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
 // V0 utilities.
 
 /// Converts the program type from SN API into a Cairo VM-compatible type.
@@ -0,0 +1,229 @@
+use std::collections::HashSet;
+
+use assert_matches::assert_matches;
+use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+use cairo_vm::vm::runners::builtin_runner::{
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME,
+    SEGMENT_ARENA_BUILTIN_NAME,
+};
+use starknet_api::core::CompiledClassHash;
+use starknet_api::deprecated_contract_class::{EntryPointOffset, EntryPointType};
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::abi::constants;
+use crate::execution::contract_class::{
+    CompilerVersion, ContractClass, ContractClassV0, ContractClassV1, ContractClassWithAbi,
+};
+use crate::execution::errors::{ContractClassError, PreExecutionError};
+use crate::execution::execution_utils::felt_to_stark_felt;
+use crate::test_utils::{
+    get_contract_path, get_raw_contract_class, TEST_CONTRACT_CAIRO0_PATH,
+    TEST_CONTRACT_CAIRO1_PATH, TEST_EMPTY_CONTRACT_CAIRO0_PATH,
+};
+
+#[test]
+fn test_try_from_json_with_expected_hash() {
+    let raw_contract_class = get_raw_contract_class(TEST_CONTRACT_CAIRO1_PATH);
+    let casm_contract_class: CasmContractClass = serde_json::from_str(&raw_contract_class).unwrap();
+    let compiled_class_hash =
+        CompiledClassHash(felt_to_stark_felt(&casm_contract_class.compiled_class_hash()));
+
+    // Correct hash.
+    let contract_class =
+        ContractClass::try_from_json_with_expected_hash(&raw_contract_class, compiled_class_hash)
+            .unwrap();
+    assert_matches!(contract_class, ContractClass::V1(_));
+
+    // Wrong hash.
+    let wrong_hash = CompiledClassHash(stark_felt!(1_u8));
+    let error = ContractClass::try_from_json_with_expected_hash(&raw_contract_class, wrong_hash)
+        .unwrap_err();
+    assert_matches!(
+        error,
+        ContractClassError::CompiledClassHashMismatch { expected, computed }
+        if expected == wrong_hash && computed == compiled_class_hash
+    );
+}
+
+#[test]
+fn test_compiler_version_parsing() {
+    let version: CompilerVersion = "2.4.0".parse().unwrap();
+    assert_eq!(version, CompilerVersion { major: 2, minor: 4, patch: 0 });
+    assert_eq!(version.to_string(), "2.4.0");
+
+    // Pre-release versions are compared as their release.
+    let dev_version: CompilerVersion = "2.5.0-dev.0".parse().unwrap();
+    assert_eq!(dev_version, CompilerVersion { major: 2, minor: 5, patch: 0 });
+    assert!(version < dev_version);
+
+    for invalid_version in ["2.4", "2.4.0.1", "v2.4.0", ""] {
+        assert_matches!(
+            invalid_version.parse::<CompilerVersion>(),
+            Err(ContractClassError::InvalidCompilerVersion(version)) if version == invalid_version
+        );
+    }
+}
+
+#[test]
+fn test_compiler_version_is_supported() {
+    let version = |version: &str| version.parse::<CompilerVersion>().unwrap();
+    let supported_versions = version("1.0.0")..=version("2.5.0");
+
+    for supported_version in ["1.0.0", "2.4.0", "2.5.0", "2.5.4"] {
+        assert!(version(supported_version).is_supported(&supported_versions));
+    }
+    for unsupported_version in ["0.12.2", "2.6.0", "3.0.0"] {
+        assert!(!version(unsupported_version).is_supported(&supported_versions));
+    }
+}
+
+#[test]
+fn test_sierra_program_hash() {
+    let raw_contract_class = get_raw_contract_class(TEST_CONTRACT_CAIRO1_PATH);
+
+    // Absent from the source JSON.
+    let contract_class = ContractClassV1::try_from_json_string(&raw_contract_class).unwrap();
+    assert_eq!(contract_class.sierra_program_hash(), None);
+
+    // Present in the source JSON.
+    let sierra_program_hash = stark_felt!("0x1234");
+    let mut json: serde_json::Value = serde_json::from_str(&raw_contract_class).unwrap();
+    json["sierra_program_hash"] = serde_json::to_value(sierra_program_hash).unwrap();
+    let contract_class = ContractClassV1::try_from_json_string(&json.to_string()).unwrap();
+    assert_eq!(contract_class.sierra_program_hash(), Some(sierra_program_hash));
+}
+
+#[test]
+fn test_program_hash() {
+    let raw_contract_class = get_raw_contract_class(TEST_CONTRACT_CAIRO0_PATH);
+    let contract_class = ContractClassV0::try_from_json_string(&raw_contract_class).unwrap();
+
+    // Formatting and the ABI do not affect the hash.
+    let mut json: serde_json::Value = serde_json::from_str(&raw_contract_class).unwrap();
+    json["abi"] = serde_json::Value::Array(vec![]);
+    let reformatted_raw_contract_class = serde_json::to_string_pretty(&json).unwrap();
+    assert_ne!(reformatted_raw_contract_class, raw_contract_class);
+    let reformatted_contract_class =
+        ContractClassV0::try_from_json_string(&reformatted_raw_contract_class).unwrap();
+    assert_eq!(reformatted_contract_class.program_hash(), contract_class.program_hash());
+
+    // Different programs have different hashes.
+    let other_contract_class = ContractClassV0::try_from_json_string(&get_raw_contract_class(
+        TEST_EMPTY_CONTRACT_CAIRO0_PATH,
+    ))
+    .unwrap();
+    assert_ne!(other_contract_class.program_hash(), contract_class.program_hash());
+}
+
+#[test]
+fn test_try_from_file() {
+    let contract_class =
+        ContractClassV0::try_from_file(get_contract_path(TEST_CONTRACT_CAIRO0_PATH)).unwrap();
+    assert_eq!(
+        contract_class,
+        ContractClassV0::try_from_json_string(&get_raw_contract_class(TEST_CONTRACT_CAIRO0_PATH))
+            .unwrap()
+    );
+
+    let contract_class =
+        ContractClassV1::try_from_file(get_contract_path(TEST_CONTRACT_CAIRO1_PATH)).unwrap();
+    assert_eq!(
+        contract_class,
+        ContractClassV1::try_from_json_string(&get_raw_contract_class(TEST_CONTRACT_CAIRO1_PATH))
+            .unwrap()
+    );
+
+    // Missing file.
+    let error =
+        ContractClassV1::try_from_file(get_contract_path("./no_such_contract.json")).unwrap_err();
+    assert_matches!(error, ContractClassError::IoError(_));
+}
+
+#[test]
+fn test_builtin_costs() {
+    let builtin_costs = ContractClassV1::builtin_costs();
+    assert_eq!(builtin_costs[RANGE_CHECK_BUILTIN_NAME], constants::RANGE_CHECK_GAS_COST);
+    assert_eq!(builtin_costs[HASH_BUILTIN_NAME], constants::PEDERSEN_GAS_COST);
+}
+
+#[test]
+fn test_contract_class_with_abi() {
+    let raw_contract_class = get_raw_contract_class(TEST_CONTRACT_CAIRO0_PATH);
+    let contract_class_with_abi =
+        ContractClassWithAbi::try_from_v0_json_string(&raw_contract_class).unwrap();
+
+    // The runnable class is unaffected by the ABI.
+    assert_eq!(
+        contract_class_with_abi.contract_class,
+        ContractClass::V0(ContractClassV0::try_from_json_string(&raw_contract_class).unwrap())
+    );
+
+    // The ABI survives the round trip.
+    let raw_json: serde_json::Value = serde_json::from_str(&raw_contract_class).unwrap();
+    let abi: serde_json::Value =
+        serde_json::from_str(&contract_class_with_abi.abi.unwrap()).unwrap();
+    assert!(abi.as_array().is_some_and(|abi| !abi.is_empty()));
+    assert_eq!(abi, raw_json["abi"]);
+
+    // Casm classes carry no ABI.
+    let raw_contract_class = get_raw_contract_class(TEST_CONTRACT_CAIRO1_PATH);
+    let contract_class_with_abi =
+        ContractClassWithAbi::try_from_v1_json_string(&raw_contract_class).unwrap();
+    assert_eq!(contract_class_with_abi.abi, None);
+}
+
+#[test]
+fn test_check_supported_builtins() {
+    let cairo0_class = ContractClass::V0(ContractClassV0::from_file(TEST_CONTRACT_CAIRO0_PATH));
+    let cairo1_class = ContractClass::V1(ContractClassV1::from_file(TEST_CONTRACT_CAIRO1_PATH));
+    let to_builtin_names =
+        |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<HashSet<_>>();
+
+    let cairo0_builtins = to_builtin_names(&[
+        BITWISE_BUILTIN_NAME,
+        EC_OP_BUILTIN_NAME,
+        HASH_BUILTIN_NAME,
+        RANGE_CHECK_BUILTIN_NAME,
+    ]);
+    assert_eq!(cairo0_class.required_builtins(), cairo0_builtins);
+    let mut cairo1_builtins = cairo0_builtins.clone();
+    cairo1_builtins.insert(SEGMENT_ARENA_BUILTIN_NAME.to_string());
+    assert_eq!(cairo1_class.required_builtins(), cairo1_builtins);
+
+    for contract_class in [&cairo0_class, &cairo1_class] {
+        contract_class.check_supported_builtins(&cairo1_builtins).unwrap();
+
+        // A class requiring a builtin outside the allowed set.
+        let mut allowed_builtins = cairo1_builtins.clone();
+        allowed_builtins.remove(EC_OP_BUILTIN_NAME);
+        assert_matches!(
+            contract_class.check_supported_builtins(&allowed_builtins).unwrap_err(),
+            PreExecutionError::UnsupportedBuiltin(builtin) if builtin == EC_OP_BUILTIN_NAME
+        );
+    }
+}
+
+#[test]
+fn test_all_entry_points() {
+    let contract_class = ContractClass::V0(ContractClassV0::from_file(TEST_CONTRACT_CAIRO0_PATH));
+    let all_entry_points = contract_class.all_entry_points();
+
+    assert!(all_entry_points.contains(&(
+        EntryPointType::External,
+        selector_from_name("foo"),
+        EntryPointOffset(1045)
+    )));
+    let n_entry_points = match &contract_class {
+        ContractClass::V0(class) => class.entry_points_by_type.values().map(Vec::len).sum(),
+        ContractClass::V1(_) => unreachable!(),
+    };
+    assert_eq!(all_entry_points.len(), n_entry_points);
+
+    // Constructors are listed first.
+    let contract_class = ContractClass::V1(ContractClassV1::from_file(TEST_CONTRACT_CAIRO1_PATH));
+    let (typ, selector, _) = contract_class.all_entry_points()[0];
+    assert_eq!(typ, EntryPointType::Constructor);
+    assert_eq!(Some(selector), contract_class.constructor_selector());
+}
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
 use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
 use cairo_vm::vm::runners::cairo_runner::{
@@ -15,6 +17,7 @@ use crate::execution::contract_class::ContractClassV0;
 use crate::execution::deprecated_syscalls::hint_processor::DeprecatedSyscallHintProcessor;
 use crate::execution::entry_point::{
     CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult, ExecutionResources,
+    ExecutionTimings,
 };
 use crate::execution::errors::{
     PostExecutionError, PreExecutionError, VirtualMachineExecutionError,
@@ -40,6 +43,7 @@ pub fn execute_entry_point_call(
     resources: &mut ExecutionResources,
     context: &mut EntryPointExecutionContext,
 ) -> EntryPointExecutionResult<CallInfo> {
+    let record_timings = context.should_record_execution_timings();
     let VmExecutionContext {
         mut runner,
         mut vm,
@@ -60,9 +64,12 @@ pub fn execute_entry_point_call(
     let previous_vm_resources = syscall_handler.resources.vm_resources.clone();
 
     // Execute.
+    let vm_run_start = record_timings.then(Instant::now);
     run_entry_point(&mut vm, &mut runner, &mut syscall_handler, entry_point_pc, args)?;
+    let vm_run_duration = vm_run_start.map(|start| start.elapsed());
 
-    Ok(finalize_execution(
+    let post_validation_start = record_timings.then(Instant::now);
+    let call_info = finalize_execution(
         vm,
         runner,
         syscall_handler,
@@ -70,7 +77,17 @@ pub fn execute_entry_point_call(
         previous_vm_resources,
         implicit_args,
         n_total_args,
-    )?)
+    )?;
+    if let (Some(vm_run_duration), Some(post_validation_start)) =
+        (vm_run_duration, post_validation_start)
+    {
+        context.execution_timings = Some(ExecutionTimings {
+            vm_run_duration,
+            post_validation_duration: post_validation_start.elapsed(),
+        });
+    }
+
+    Ok(call_info)
 }
 
 pub fn initialize_execution_context<'a>(
@@ -244,6 +261,12 @@ pub fn finalize_execution(
     syscall_handler.resources.vm_resources += &vm_resources_without_inner_calls;
 
     let full_call_vm_resources = &syscall_handler.resources.vm_resources - &previous_vm_resources;
+    #[cfg(feature = "prover-io")]
+    crate::execution::execution_utils::record_vm_memory_segments(
+        &mut vm,
+        runner,
+        syscall_handler.context,
+    )?;
     Ok(CallInfo {
         call,
         execution: CallExecution {
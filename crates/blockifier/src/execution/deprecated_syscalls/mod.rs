@@ -397,7 +397,9 @@ pub fn get_block_number(
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<GetBlockNumberResponse> {
     // TODO(Yoni, 1/5/2024): disable for validate.
-    Ok(GetBlockNumberResponse { block_number: syscall_handler.context.block_context.block_number })
+    Ok(GetBlockNumberResponse {
+        block_number: syscall_handler.context.block_context.block_info.block_number,
+    })
 }
 
 // GetBlockTimestamp syscall.
@@ -423,7 +425,7 @@ pub fn get_block_timestamp(
 ) -> DeprecatedSyscallResult<GetBlockTimestampResponse> {
     // TODO(Yoni, 1/5/2024): disable for validate.
     Ok(GetBlockTimestampResponse {
-        block_timestamp: syscall_handler.context.block_context.block_timestamp,
+        block_timestamp: syscall_handler.context.block_context.block_info.block_timestamp,
     })
 }
 
@@ -476,7 +478,7 @@ pub fn get_sequencer_address(
 ) -> DeprecatedSyscallResult<GetSequencerAddressResponse> {
     syscall_handler.verify_not_in_validate_mode("get_sequencer_address")?;
     Ok(GetSequencerAddressResponse {
-        address: syscall_handler.context.block_context.sequencer_address,
+        address: syscall_handler.context.block_context.block_info.sequencer_address,
     })
 }
 
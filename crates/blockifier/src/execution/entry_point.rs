@@ -1,6 +1,9 @@
 use std::cell::RefCell;
 use std::cmp::min;
+#[cfg(any(feature = "fault-injection", test))]
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
 use cairo_vm::vm::runners::cairo_runner::{
     ExecutionResources as VmExecutionResources, ResourceTracker, RunResources,
@@ -8,6 +11,7 @@ use cairo_vm::vm::runners::cairo_runner::{
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
+use starknet_api::state::StateDiff;
 use starknet_api::transaction::{Calldata, TransactionVersion};
 
 use crate::abi::abi_utils::selector_from_name;
@@ -17,13 +21,16 @@ use crate::execution::call_info::CallInfo;
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
 use crate::execution::errors::{EntryPointExecutionError, PreExecutionError};
-use crate::execution::execution_utils::execute_entry_point_call;
-use crate::fee::os_resources::OS_RESOURCES;
-use crate::state::state_api::State;
+use crate::execution::execution_utils::{execute_entry_point_call, validate_emitted_output_sizes};
+#[cfg(any(feature = "fault-injection", test))]
+use crate::execution::syscalls::SyscallSelector;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::{State, StateReader};
 use crate::transaction::objects::{
     AccountTransactionContext, HasRelatedFeeType, TransactionExecutionResult,
 };
 use crate::transaction::transaction_types::TransactionType;
+use crate::utils::format_felt_hex;
 
 #[cfg(test)]
 #[path = "entry_point_test.rs"]
@@ -97,8 +104,19 @@ impl CallEntryPoint {
         self.class_hash = Some(class_hash);
         let contract_class = state.get_compiled_contract_class(class_hash)?;
 
-        execute_entry_point_call(self, contract_class, state, resources, context).map_err(|error| {
+        let execution_result =
+            execute_entry_point_call(self, contract_class, state, resources, context);
+        let call_info = execution_result.map_err(|error| {
             match error {
+                // The VM halts once the steps are exhausted (possibly in an inner call); the run
+                // fails on an unfinished execution, or on the failure of the inner call.
+                // The stack trace is kept for the revert error.
+                EntryPointExecutionError::VirtualMachineExecutionError(error)
+                    if context.n_remaining_steps() == 0 =>
+                {
+                    context.error_stack.push((storage_address, error.try_to_vm_trace()));
+                    EntryPointExecutionError::StepsExceeded { max_n_steps: context.max_n_steps }
+                }
                 // On VM error, pack the stack trace into the propagated error.
                 EntryPointExecutionError::VirtualMachineExecutionError(error) => {
                     context.error_stack.push((storage_address, error.try_to_vm_trace()));
@@ -115,7 +133,26 @@ impl CallEntryPoint {
                 }
                 other_error => other_error,
             }
-        })
+        })?;
+        validate_emitted_output_sizes(&call_info, &context.block_context.chain_info)?;
+
+        Ok(call_info)
+    }
+
+    /// Executes the call on top of the given state, and returns the state changes it produced
+    /// alongside its call info. The changes are committed to the given state only on success.
+    pub fn execute_and_diff<S: StateReader>(
+        &self,
+        state: &mut CachedState<S>,
+        resources: &mut ExecutionResources,
+        context: &mut EntryPointExecutionContext,
+    ) -> EntryPointExecutionResult<(CallInfo, StateDiff)> {
+        let mut transactional_state = CachedState::create_transactional(state);
+        let call_info = self.clone().execute(&mut transactional_state, resources, context)?;
+        let state_diff = transactional_state.to_starknet_api_state_diff();
+        transactional_state.commit();
+
+        Ok((call_info, state_diff))
     }
 }
 
@@ -131,6 +168,34 @@ pub struct ConstructorContext {
 pub struct ExecutionResources {
     pub vm_resources: VmExecutionResources,
     pub syscall_counter: SyscallCounter,
+    // The OS resources of the `keccak` syscall depend on its input length; they are recorded in
+    // `vm_resources` per round, rather than counted in `syscall_counter`.
+    pub n_keccak_rounds: usize,
+}
+
+/// Syscalls that fail deterministically, as if their execution failed, instead of being executed;
+/// requires the `fault-injection` feature. Applies to Cairo 1.0 syscalls.
+#[cfg(any(feature = "fault-injection", test))]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FaultConfig {
+    pub failing_syscalls: HashSet<SyscallSelector>,
+}
+
+/// Wall-clock durations of the execution phases of a call (including its inner calls).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ExecutionTimings {
+    pub vm_run_duration: Duration,
+    // Post-run validation and extraction of the call output.
+    pub post_validation_duration: Duration,
+}
+
+/// The final memory of a run, as required for proving it.
+#[cfg(feature = "prover-io")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VmMemorySegments {
+    pub relocated_memory: Vec<Option<cairo_felt::Felt252>>,
+    // Pairs of (relocated address, page ID).
+    pub public_memory: Vec<(usize, usize)>,
 }
 
 #[derive(Clone, Debug)]
@@ -139,6 +204,9 @@ pub struct EntryPointExecutionContext {
     pub account_tx_context: AccountTransactionContext,
     // VM execution limits.
     pub vm_run_resources: RunResources,
+    /// The step limit of the execution phase (validation or execution): as set on creation, or as
+    /// reduced by [EntryPointExecutionContext::subtract_steps].
+    pub max_n_steps: usize,
     /// Used for tracking events order during the current execution.
     pub n_emitted_events: usize,
     /// Used for tracking L2-to-L1 messages order during the current execution.
@@ -153,6 +221,28 @@ pub struct EntryPointExecutionContext {
 
     // The execution mode affects the behavior of the hint processor.
     pub execution_mode: ExecutionMode,
+
+    /// Experimental: if set, a Cairo 1.0 inner call that fails reverts only its own state changes,
+    /// and the failure is returned to its caller, which may recover from it; the reverted call
+    /// appears in the caller's inner calls, marked as failed.
+    pub partial_revert: bool,
+    /// With `partial_revert`, the call info of the last failed Cairo 1.0 call, so that its caller
+    /// accounts for the resources it consumed.
+    pub failed_call_info: Option<CallInfo>,
+
+    /// If set, the timings of the outermost call are recorded in `execution_timings`.
+    pub record_execution_timings: bool,
+    pub execution_timings: Option<ExecutionTimings>,
+
+    /// If set, the final memory of the outermost call is recorded in `memory_segments`.
+    #[cfg(feature = "prover-io")]
+    pub record_memory_segments: bool,
+    #[cfg(feature = "prover-io")]
+    pub memory_segments: Option<VmMemorySegments>,
+
+    /// Syscalls to fail during the execution (`fault-injection` feature only).
+    #[cfg(any(feature = "fault-injection", test))]
+    pub faults: FaultConfig,
 }
 
 impl EntryPointExecutionContext {
@@ -166,14 +256,25 @@ impl EntryPointExecutionContext {
             Self::max_steps(block_context, account_tx_context, &mode, limit_steps_by_resources)?;
         Ok(Self {
             vm_run_resources: RunResources::new(max_steps),
+            max_n_steps: max_steps,
             n_emitted_events: 0,
             n_sent_messages_to_l1: 0,
             error_stack: vec![],
             account_tx_context: account_tx_context.clone(),
             current_recursion_depth: Default::default(),
-            max_recursion_depth: block_context.max_recursion_depth,
+            max_recursion_depth: block_context.chain_info.max_recursion_depth,
             block_context: block_context.clone(),
             execution_mode: mode,
+            partial_revert: false,
+            failed_call_info: None,
+            record_execution_timings: false,
+            execution_timings: None,
+            #[cfg(feature = "prover-io")]
+            record_memory_segments: false,
+            #[cfg(feature = "prover-io")]
+            memory_segments: None,
+            #[cfg(any(feature = "fault-injection", test))]
+            faults: FaultConfig::default(),
         })
     }
 
@@ -214,30 +315,40 @@ impl EntryPointExecutionContext {
     ) -> TransactionExecutionResult<usize> {
         let block_upper_bound = match mode {
             ExecutionMode::Validate => min(
-                block_context.validate_max_n_steps as usize,
+                block_context.chain_info.validate_max_n_steps as usize,
                 constants::MAX_VALIDATE_STEPS_PER_TX,
             ),
-            ExecutionMode::Execute => {
-                min(block_context.invoke_tx_max_n_steps as usize, constants::MAX_STEPS_PER_TX)
-            }
+            ExecutionMode::Execute => min(
+                block_context.chain_info.invoke_tx_max_n_steps as usize,
+                constants::MAX_STEPS_PER_TX,
+            ),
         };
 
         if !limit_steps_by_resources || !account_tx_context.enforce_fee()? {
             return Ok(block_upper_bound);
         }
 
-        let gas_per_step =
-            block_context.vm_resource_fee_cost.get(constants::N_STEPS_RESOURCE).unwrap_or_else(
-                || panic!("{} must appear in `vm_resource_fee_cost`.", constants::N_STEPS_RESOURCE),
-            );
+        let gas_per_step = block_context
+            .chain_info
+            .vm_resource_fee_cost
+            .get(constants::N_STEPS_RESOURCE)
+            .unwrap_or_else(|| {
+                panic!("{} must appear in `vm_resource_fee_cost`.", constants::N_STEPS_RESOURCE)
+            });
 
         // New transactions derive the step limit by the L1 gas resource bounds; deprecated
         // transactions derive this value from the `max_fee`.
         let tx_gas_upper_bound = match account_tx_context {
             AccountTransactionContext::Deprecated(context) => {
-                (context.max_fee.0
-                    / block_context.gas_prices.get_by_fee_type(&account_tx_context.fee_type()))
-                    as usize
+                let gas_price = block_context
+                    .block_info
+                    .gas_prices
+                    .get_by_fee_type(&account_tx_context.fee_type());
+                if gas_price == 0 {
+                    // Execution is free, hence the max fee does not bound it.
+                    return Ok(block_upper_bound);
+                }
+                (context.max_fee.0 / gas_price) as usize
             }
             AccountTransactionContext::Current(context) => {
                 context.l1_resource_bounds()?.max_amount as usize
@@ -248,6 +359,19 @@ impl EntryPointExecutionContext {
         Ok(min(tx_upper_bound, block_upper_bound))
     }
 
+    /// Returns whether the timings of the current call should be recorded; only the timings of
+    /// the outermost call are recorded (those of inner calls are included in them).
+    pub fn should_record_execution_timings(&self) -> bool {
+        self.record_execution_timings && *self.current_recursion_depth.borrow() == 1
+    }
+
+    /// Returns whether the memory of the current call should be recorded; only the memory of the
+    /// outermost call is recorded.
+    #[cfg(feature = "prover-io")]
+    pub fn should_record_memory_segments(&self) -> bool {
+        self.record_memory_segments && *self.current_recursion_depth.borrow() == 1
+    }
+
     /// Returns the available steps in run resources.
     pub fn n_remaining_steps(&self) -> usize {
         self.vm_run_resources.get_n_steps().expect("The number of steps must be initialized.")
@@ -268,6 +392,7 @@ impl EntryPointExecutionContext {
             remaining_steps - steps_to_subtract
         };
         self.vm_run_resources = RunResources::new(new_remaining_steps);
+        self.max_n_steps = new_remaining_steps;
         self.n_remaining_steps()
     }
 
@@ -284,7 +409,12 @@ impl EntryPointExecutionContext {
             .map(|call_info| call_info.vm_resources.n_steps)
             .unwrap_or_default();
 
-        let overhead_steps = OS_RESOURCES.resources_for_tx_type(tx_type).n_steps;
+        let overhead_steps = self
+            .block_context
+            .versioned_constants
+            .os_resources
+            .resources_for_tx_type(tx_type)
+            .n_steps;
         self.subtract_steps(validate_steps + overhead_steps)
     }
 
@@ -297,7 +427,7 @@ impl EntryPointExecutionContext {
             .map(|(contract_address, trace_string)| {
                 format!(
                     "Error in the called contract ({}):\n{}",
-                    contract_address.0.key(),
+                    format_felt_hex(contract_address.0.key()),
                     trace_string
                 )
             })
@@ -1,3 +1,5 @@
+use std::time::Instant;
+
 use cairo_felt::Felt252;
 use cairo_vm::serde::deserialize_program::BuiltinName;
 use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
@@ -15,6 +17,7 @@ use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
 use crate::execution::contract_class::{ContractClassV1, EntryPointV1};
 use crate::execution::entry_point::{
     CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult, ExecutionResources,
+    ExecutionTimings,
 };
 use crate::execution::errors::{
     EntryPointExecutionError, PostExecutionError, PreExecutionError, VirtualMachineExecutionError,
@@ -23,11 +26,23 @@ use crate::execution::execution_utils::{
     read_execution_retdata, stark_felt_to_felt, write_maybe_relocatable, write_stark_felt, Args,
     ReadOnlySegments,
 };
-use crate::execution::syscalls::hint_processor::SyscallHintProcessor;
+use crate::execution::syscalls::hint_processor::{SyscallHintProcessor, OUT_OF_GAS_ERROR};
 use crate::state::state_api::State;
 
 // TODO(spapini): Try to refactor this file into a StarknetRunner struct.
 
+/// The builtins a Cairo 1.0 entry point may use.
+const SUPPORTED_BUILTINS: [BuiltinName; 8] = [
+    BuiltinName::bitwise,
+    BuiltinName::ec_op,
+    BuiltinName::ecdsa,
+    BuiltinName::output,
+    BuiltinName::pedersen,
+    BuiltinName::poseidon,
+    BuiltinName::range_check,
+    BuiltinName::segment_arena,
+];
+
 pub struct VmExecutionContext<'a> {
     pub runner: CairoRunner,
     pub vm: VirtualMachine,
@@ -52,6 +67,7 @@ pub fn execute_entry_point_call(
     resources: &mut ExecutionResources,
     context: &mut EntryPointExecutionContext,
 ) -> EntryPointExecutionResult<CallInfo> {
+    let record_timings = context.should_record_execution_timings();
     let VmExecutionContext {
         mut runner,
         mut vm,
@@ -75,6 +91,7 @@ pub fn execute_entry_point_call(
 
     // Execute.
     let program_segment_size = contract_class.bytecode_length() + program_extra_data_length;
+    let vm_run_start = record_timings.then(Instant::now);
     run_entry_point(
         &mut vm,
         &mut runner,
@@ -83,7 +100,9 @@ pub fn execute_entry_point_call(
         args,
         program_segment_size,
     )?;
+    let vm_run_duration = vm_run_start.map(|start| start.elapsed());
 
+    let post_validation_start = record_timings.then(Instant::now);
     let call_info = finalize_execution(
         vm,
         runner,
@@ -92,11 +111,31 @@ pub fn execute_entry_point_call(
         n_total_args,
         program_extra_data_length,
     )?;
-    if call_info.execution.failed {
-        return Err(EntryPointExecutionError::ExecutionFailed {
-            error_data: call_info.execution.retdata.0,
+    if let (Some(vm_run_duration), Some(post_validation_start)) =
+        (vm_run_duration, post_validation_start)
+    {
+        context.execution_timings = Some(ExecutionTimings {
+            vm_run_duration,
+            post_validation_duration: post_validation_start.elapsed(),
         });
     }
+    if call_info.execution.failed {
+        let error_data = call_info.execution.retdata.0.clone();
+        // Distinguish resource exhaustion from other panics.
+        let error = if error_data == [stark_felt!(OUT_OF_GAS_ERROR)] {
+            let gas_consumed = call_info.execution.gas_consumed;
+            EntryPointExecutionError::OutOfGas {
+                gas_consumed,
+                remaining: call_info.call.initial_gas.saturating_sub(gas_consumed),
+            }
+        } else {
+            EntryPointExecutionError::ExecutionFailed { error_data }
+        };
+        if context.partial_revert {
+            context.failed_call_info = Some(call_info);
+        }
+        return Err(error);
+    }
 
     Ok(call_info)
 }
@@ -117,17 +156,11 @@ pub fn initialize_execution_context<'a>(
     let trace_enabled = false;
     let mut vm = VirtualMachine::new(trace_enabled);
 
-    // Initialize program with all builtins.
-    let program_builtins = [
-        BuiltinName::bitwise,
-        BuiltinName::ec_op,
-        BuiltinName::ecdsa,
-        BuiltinName::output,
-        BuiltinName::pedersen,
-        BuiltinName::poseidon,
-        BuiltinName::range_check,
-        BuiltinName::segment_arena,
-    ];
+    // Initialize program with the builtins used by the entry point only.
+    let program_builtins: Vec<BuiltinName> = SUPPORTED_BUILTINS
+        .into_iter()
+        .filter(|builtin| entry_point.builtins.iter().any(|name| name == builtin.name()))
+        .collect();
     runner.initialize_function_runner_cairo_1(&mut vm, &program_builtins)?;
     let mut read_only_segments = ReadOnlySegments::default();
     let program_extra_data_length =
@@ -289,6 +322,12 @@ pub fn finalize_execution(
     syscall_handler.resources.vm_resources += &vm_resources_without_inner_calls;
 
     let full_call_vm_resources = &syscall_handler.resources.vm_resources - &previous_vm_resources;
+    #[cfg(feature = "prover-io")]
+    crate::execution::execution_utils::record_vm_memory_segments(
+        &mut vm,
+        runner,
+        syscall_handler.context,
+    )?;
     Ok(CallInfo {
         call: syscall_handler.call,
         execution: CallExecution {
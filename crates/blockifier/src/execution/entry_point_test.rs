@@ -1,8 +1,12 @@
 use std::collections::HashSet;
+use std::time::Duration;
 
+use assert_matches::assert_matches;
 use cairo_vm::serde::deserialize_program::BuiltinName;
+use cairo_vm::vm::runners::builtin_runner::{BITWISE_BUILTIN_NAME, HASH_BUILTIN_NAME};
 use num_bigint::BigInt;
 use pretty_assertions::assert_eq;
+use rstest::rstest;
 use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, PatriciaKey};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::{StarkFelt, StarkHash};
@@ -11,11 +15,17 @@ use starknet_api::transaction::Calldata;
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 
 use crate::abi::abi_utils::{get_storage_var_address, selector_from_name};
+use crate::abi::calldata::CalldataBuilder;
 use crate::abi::constants;
+use crate::abi::sierra_types::SierraU256;
 use crate::block_context::BlockContext;
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
+use crate::execution::common_hints::ExecutionMode;
 use crate::execution::contract_class::ContractClass;
-use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, ExecutionResources,
+};
+use crate::execution::entry_point_execution::{initialize_execution_context, VmExecutionContext};
 use crate::execution::errors::EntryPointExecutionError;
 use crate::retdata;
 use crate::state::cached_state::CachedState;
@@ -29,9 +39,9 @@ use crate::test_utils::{
     SECURITY_TEST_CONTRACT_ADDRESS, TEST_CLASS_HASH, TEST_CONTRACT_ADDRESS,
     TEST_CONTRACT_ADDRESS_2,
 };
+use crate::transaction::objects::{AccountTransactionContext, DeprecatedAccountTransactionContext};
 
-#[test]
-fn test_call_info_iteration() {
+fn create_nested_call_info() -> CallInfo {
     // Create nested call infos according to their expected traversal order (pre-order).
     // The tree is constructed as follows:
     //                  root (0)
@@ -52,17 +62,51 @@ fn test_call_info_iteration() {
         inner_calls: vec![left_leaf],
         ..Default::default()
     };
-    let root = CallInfo {
+    CallInfo {
         call: CallEntryPoint { calldata: calldata![stark_felt!(0_u8)], ..Default::default() },
         inner_calls: vec![inner_node, right_leaf],
         ..Default::default()
-    };
+    }
+}
 
+#[test]
+fn test_call_info_iteration() {
+    let root = create_nested_call_info();
     for (i, call_info) in root.into_iter().enumerate() {
         assert_eq!(call_info.call.calldata, calldata![stark_felt!(i as u64)]);
     }
 }
 
+#[test]
+fn test_call_info_iteration_with_depth() {
+    let root = create_nested_call_info();
+    let (depths, call_infos): (Vec<usize>, Vec<&CallInfo>) = root.iter_with_depth().unzip();
+    assert_eq!(depths, vec![0, 1, 2, 1]);
+    // The order matches that of the plain iteration.
+    assert_eq!(call_infos, root.into_iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn test_call_info_filtering() {
+    let mut root = create_nested_call_info();
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let selector = selector_from_name("foo");
+    // Route the left leaf into the class and the right leaf to the selector.
+    root.inner_calls[0].inner_calls[0].call.class_hash = Some(class_hash);
+    root.inner_calls[1].call.entry_point_selector = selector;
+
+    let calldata_of = |calls: Vec<&CallInfo>| -> Vec<Calldata> {
+        calls.into_iter().map(|call_info| call_info.call.calldata.clone()).collect()
+    };
+    assert_eq!(calldata_of(root.calls_to_class(class_hash)), vec![calldata![stark_felt!(2_u8)]]);
+    assert_eq!(calldata_of(root.calls_to_selector(selector)), vec![calldata![stark_felt!(3_u8)]]);
+    assert_eq!(
+        calldata_of(root.filter_calls(|call| call.calldata.0.len() == 1)),
+        (0..4_u8).map(|i| calldata![stark_felt!(i)]).collect::<Vec<_>>()
+    );
+    assert!(root.calls_to_class(ClassHash::default()).is_empty());
+}
+
 #[test]
 fn test_entry_point_without_arg() {
     let mut state = deprecated_create_test_state();
@@ -115,7 +159,7 @@ fn test_long_retdata() {
 #[test]
 fn test_entry_point_with_builtin() {
     let mut state = deprecated_create_test_state();
-    let calldata = calldata![stark_felt!(47_u8), stark_felt!(31_u8)];
+    let calldata = CalldataBuilder::new().felt(47_u8).felt(31_u8).build();
     let entry_point_call = CallEntryPoint {
         calldata,
         entry_point_selector: selector_from_name("bitwise_and"),
@@ -127,6 +171,29 @@ fn test_entry_point_with_builtin() {
     );
 }
 
+#[test]
+fn test_to_cairo_lang_resources() {
+    let mut state = deprecated_create_test_state();
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(47_u8), stark_felt!(31_u8)],
+        entry_point_selector: selector_from_name("bitwise_and"),
+        ..trivial_external_entry_point()
+    };
+    let mut call_info = entry_point_call.execute_directly(&mut state).unwrap();
+    call_info.vm_resources.builtin_instance_counter.insert(HASH_BUILTIN_NAME.to_string(), 0);
+
+    // Steps, memory holes and used builtins transfer as is; unused builtins are omitted.
+    let cairo_lang_resources = call_info.to_cairo_lang_resources();
+    assert_eq!(cairo_lang_resources.n_steps, call_info.vm_resources.n_steps);
+    assert_eq!(cairo_lang_resources.n_memory_holes, call_info.vm_resources.n_memory_holes);
+    let builtins = &cairo_lang_resources.builtin_instance_counter;
+    assert_eq!(builtins[BITWISE_BUILTIN_NAME], 1);
+    assert!(!builtins.contains_key(HASH_BUILTIN_NAME));
+    assert!(builtins.iter().all(|(builtin_name, &count)| {
+        count > 0 && call_info.vm_resources.builtin_instance_counter[builtin_name] == count
+    }));
+}
+
 #[test]
 fn test_entry_point_with_hint() {
     let mut state = deprecated_create_test_state();
@@ -157,6 +224,69 @@ fn test_entry_point_with_return_value() {
     );
 }
 
+#[test]
+fn test_retdata_helpers() {
+    let mut state = deprecated_create_test_state();
+    let execute = |state: &mut CachedState<DictStateReader>, name: &str, calldata: Calldata| {
+        CallEntryPoint {
+            calldata,
+            entry_point_selector: selector_from_name(name),
+            ..trivial_external_entry_point()
+        }
+        .execute_directly(state)
+        .unwrap()
+        .execution
+    };
+
+    let long_retdata = execute(&mut state, "test_long_retdata", calldata![]);
+    assert_eq!(long_retdata.retdata_felts(), &[0_u8, 1, 2, 3, 4].map(StarkFelt::from));
+    assert_eq!(long_retdata.as_single_felt(), None);
+    assert_eq!(long_retdata.as_u256(), None);
+
+    let result = execute(&mut state, "return_result", calldata![stark_felt!(23_u8)]);
+    assert_eq!(result.retdata_felts(), &[stark_felt!(23_u8)]);
+    assert_eq!(result.as_single_felt(), Some(stark_felt!(23_u8)));
+    assert_eq!(result.as_u256(), None);
+
+    let u256_result = CallExecution::from_retdata(retdata![stark_felt!(7_u8), stark_felt!(1_u8)]);
+    assert_eq!(u256_result.as_u256(), Some(SierraU256 { low_val: 7, high_val: 1 }));
+    // Words exceeding `u128` are not a valid `u256`.
+    let invalid_u256_result = CallExecution::from_retdata(retdata![
+        stark_felt!("0x100000000000000000000000000000000"),
+        stark_felt!(0_u8)
+    ]);
+    assert_eq!(invalid_u256_result.as_u256(), None);
+}
+
+#[test]
+fn test_execute_directly_timed() {
+    let mut state = deprecated_create_test_state();
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(23_u8)],
+        entry_point_selector: selector_from_name("return_result"),
+        ..trivial_external_entry_point()
+    };
+    let (call_info, timings) = entry_point_call.execute_directly_timed(&mut state).unwrap();
+    assert_eq!(call_info.execution, CallExecution::from_retdata(retdata![stark_felt!(23_u8)]));
+    assert!(timings.vm_run_duration > Duration::ZERO);
+    assert!(timings.post_validation_duration > Duration::ZERO);
+}
+
+#[cfg(feature = "prover-io")]
+#[test]
+fn test_execute_directly_with_memory_segments() {
+    let mut state = create_test_state();
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point()
+    };
+    let (_, memory_segments) =
+        entry_point_call.execute_directly_with_memory_segments(&mut state).unwrap();
+    assert!(memory_segments.relocated_memory.iter().any(Option::is_some));
+    assert!(!memory_segments.public_memory.is_empty());
+}
+
 #[test]
 fn test_entry_point_not_found_in_contract() {
     let mut state = deprecated_create_test_state();
@@ -378,8 +508,7 @@ fn test_syscall_execution_security_failures() {
         state,
         security_contract,
         "Requested contract address \
-         ContractAddress(PatriciaKey(StarkFelt(\"\
-         0x0000000000000000000000000000000000000000000000000000000000000017\"))) is not deployed",
+         0x0000000000000000000000000000000000000000000000000000000000000017 is not deployed",
         "test_bad_call_address",
         calldata![],
     );
@@ -516,6 +645,35 @@ fn test_storage_related_members() {
     );
 }
 
+#[test]
+fn test_execute_and_diff() {
+    let mut state = deprecated_create_test_state();
+    let (key, value) = (stark_felt!(1234_u16), stark_felt!(18_u8));
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![key, value],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point()
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &BlockContext::create_for_testing(),
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+
+    let (call_info, state_diff) = entry_point_call
+        .execute_and_diff(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![value]);
+    let contract_address = contract_address!(TEST_CONTRACT_ADDRESS);
+    let storage_key = StorageKey(patricia_key!(key));
+    assert_eq!(state_diff.storage_diffs[&contract_address][&storage_key], value);
+    assert!(state_diff.nonces.is_empty());
+
+    // The changes are committed to the given state.
+    assert_eq!(state.get_storage_at(contract_address, storage_key).unwrap(), value);
+}
+
 #[test]
 fn test_cairo1_entry_point_segment_arena() {
     let mut state = create_test_state();
@@ -536,6 +694,100 @@ fn test_cairo1_entry_point_segment_arena() {
     );
 }
 
+#[test]
+fn test_cairo1_entry_point_unused_builtins() {
+    let mut state = create_test_state();
+    // An entry point declaring the range check builtin only.
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point()
+    };
+
+    let contract_class = match state.get_compiled_contract_class(class_hash!(TEST_CLASS_HASH)) {
+        Ok(ContractClass::V1(class)) => class,
+        _ => panic!("Expected contract class V1."),
+    };
+    let mut resources = ExecutionResources::default();
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &BlockContext::create_for_testing(),
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+
+    let VmExecutionContext { vm, .. } = initialize_execution_context(
+        entry_point_call,
+        &contract_class,
+        &mut state,
+        &mut resources,
+        &mut context,
+    )
+    .unwrap();
+    let builtin_names: Vec<&str> =
+        vm.get_builtin_runners().iter().map(|builtin_runner| builtin_runner.name()).collect();
+    assert_eq!(builtin_names, vec![BuiltinName::range_check.name()]);
+}
+
+#[rstest]
+#[case::validate(ExecutionMode::Validate, 1000)]
+#[case::execute(ExecutionMode::Execute, 2000)]
+fn test_steps_exceeded(#[case] mode: ExecutionMode, #[case] expected_max_n_steps: usize) {
+    let mut state = deprecated_create_test_state();
+    // Use distinct limits, to verify that the limit of the given phase applies.
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.chain_info_mut().validate_max_n_steps = 1000;
+    block_context.chain_info_mut().invoke_tx_max_n_steps = 2000;
+    let account_tx_context =
+        AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default());
+    let limit_steps_by_resources = false;
+    let mut context = EntryPointExecutionContext::new(
+        &block_context,
+        &account_tx_context,
+        mode,
+        limit_steps_by_resources,
+    )
+    .unwrap();
+    assert_eq!(context.max_n_steps, expected_max_n_steps);
+
+    // Loop way past the limit.
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1000_u16)],
+        entry_point_selector: selector_from_name("recurse"),
+        ..trivial_external_entry_point()
+    };
+    let error = entry_point_call
+        .clone()
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap_err();
+    assert_matches!(
+        error,
+        EntryPointExecutionError::StepsExceeded { max_n_steps }
+        if max_n_steps == expected_max_n_steps
+    );
+    assert!(error.to_string().contains("RunResources has no remaining steps."));
+    // The VM trace is kept in the error stack.
+    assert!(context.error_trace().contains("RunResources has no remaining steps."));
+
+    // The reported limit is the one in effect, after subtracting steps.
+    let mut context = EntryPointExecutionContext::new(
+        &block_context,
+        &account_tx_context,
+        mode,
+        limit_steps_by_resources,
+    )
+    .unwrap();
+    context.subtract_steps(500);
+    let error = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap_err();
+    assert_matches!(
+        error,
+        EntryPointExecutionError::StepsExceeded { max_n_steps }
+        if max_n_steps == expected_max_n_steps - 500
+    );
+}
+
 #[test]
 fn test_stack_trace() {
     let mut state = deprecated_create_test_state();
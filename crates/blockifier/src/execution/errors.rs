@@ -1,19 +1,37 @@
 use cairo_vm::types::errors::math_errors::MathError;
+use cairo_vm::types::errors::program_errors::ProgramError;
 use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
 use cairo_vm::vm::errors::memory_errors::MemoryError;
 use cairo_vm::vm::errors::runner_errors::RunnerError;
 use cairo_vm::vm::errors::vm_errors::{VirtualMachineError, HINT_ERROR_STR};
 use num_bigint::{BigInt, TryFromBigIntError};
-use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::core::{CompiledClassHash, ContractAddress, EntryPointSelector};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use thiserror::Error;
 
 use crate::execution::execution_utils::format_panic_data;
 use crate::state::errors::StateError;
+use crate::utils::format_felt_hex;
 
 // TODO(AlonH, 21/12/2022): Implement Display for all types that appear in errors.
 
+#[derive(Debug, Error)]
+pub enum ContractClassError {
+    #[error(
+        "Compiled class hash mismatch; expected: {}, computed: {}.",
+        format_felt_hex(&expected.0),
+        format_felt_hex(&computed.0)
+    )]
+    CompiledClassHashMismatch { expected: CompiledClassHash, computed: CompiledClassHash },
+    #[error("Invalid compiler version: {0}.")]
+    InvalidCompilerVersion(String),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    ProgramError(#[from] ProgramError),
+}
+
 #[derive(Debug, Error)]
 pub enum PreExecutionError {
     #[error("Entry point {selector:?} of type {typ:?} is not unique.")]
@@ -33,12 +51,14 @@ pub enum PreExecutionError {
     #[error("No entry points of type {0:?} found in contract.")]
     NoEntryPointOfTypeFound(EntryPointType),
     #[error(transparent)]
-    ProgramError(#[from] cairo_vm::types::errors::program_errors::ProgramError),
+    ProgramError(#[from] ProgramError),
     #[error(transparent)]
     RunnerError(Box<RunnerError>),
     #[error(transparent)]
     StateError(#[from] StateError),
-    #[error("Requested contract address {0:?} is not deployed.")]
+    #[error("Builtin {0:?} is not supported.")]
+    UnsupportedBuiltin(String),
+    #[error("Requested contract address {} is not deployed.", format_felt_hex(.0.0.key()))]
     UninitializedStorageAddress(ContractAddress),
 }
 
@@ -50,6 +70,13 @@ impl From<RunnerError> for PreExecutionError {
 
 #[derive(Debug, Error)]
 pub enum PostExecutionError {
+    #[error("Event data length {data_length} exceeds the maximum of {max_data_length}.")]
+    EventDataTooLong { data_length: usize, max_data_length: usize },
+    #[error(
+        "L2-to-L1 message payload length {payload_length} exceeds the maximum of \
+         {max_payload_length}."
+    )]
+    L2ToL1PayloadTooLong { payload_length: usize, max_payload_length: usize },
     #[error(transparent)]
     MathError(#[from] cairo_vm::types::errors::math_errors::MathError),
     #[error(transparent)]
@@ -62,6 +89,9 @@ pub enum PostExecutionError {
     VirtualMachineError(#[from] VirtualMachineError),
     #[error("Malformed return data : {error_message}.")]
     MalformedReturnData { error_message: String },
+    #[cfg(feature = "prover-io")]
+    #[error(transparent)]
+    TraceError(#[from] cairo_vm::vm::errors::trace_errors::TraceError),
 }
 
 impl From<RunnerError> for PostExecutionError {
@@ -126,6 +156,8 @@ pub enum EntryPointExecutionError {
     ExecutionFailed { error_data: Vec<StarkFelt> },
     #[error("Invalid input: {input_descriptor}; {info}")]
     InvalidExecutionInput { input_descriptor: String, info: String },
+    #[error("Execution failed: out of gas; consumed {gas_consumed} gas, {remaining} remaining.")]
+    OutOfGas { gas_consumed: u64, remaining: u64 },
     #[error(transparent)]
     PostExecutionError(#[from] PostExecutionError),
     #[error(transparent)]
@@ -134,6 +166,11 @@ pub enum EntryPointExecutionError {
     RecursionDepthExceeded,
     #[error(transparent)]
     StateError(#[from] StateError),
+    #[error(
+        "Execution exceeded the step limit of {max_n_steps} steps: RunResources has no remaining \
+         steps."
+    )]
+    StepsExceeded { max_n_steps: usize },
     /// Gathers all errors from running the Cairo VM, excluding hints.
     #[error(transparent)]
     VirtualMachineExecutionError(#[from] VirtualMachineExecutionError),
@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use cairo_felt::Felt252;
 use cairo_lang_runner::casm_run::format_next_item;
 use cairo_vm::serde::deserialize_program::{
-    deserialize_array_of_bigint_hex, Attribute, HintParams, Identifier, ReferenceManager,
+    deserialize_array_of_bigint_hex, Attribute, BuiltinName, HintParams, Identifier,
+    ReferenceManager,
 };
 use cairo_vm::types::errors::program_errors::ProgramError;
 use cairo_vm::types::program::Program;
@@ -18,6 +19,7 @@ use starknet_api::deprecated_contract_class::Program as DeprecatedProgram;
 use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::Calldata;
 
+use crate::block_context::ChainInfo;
 use crate::execution::call_info::{CallInfo, Retdata};
 use crate::execution::contract_class::ContractClass;
 use crate::execution::entry_point::{
@@ -73,6 +75,35 @@ pub fn execute_entry_point_call(
     }
 }
 
+/// Validates that the events and L2-to-L1 messages emitted by the given call (excluding its inner
+/// calls, which are validated on their own) do not exceed the chain limits.
+pub fn validate_emitted_output_sizes(
+    call_info: &CallInfo,
+    chain_info: &ChainInfo,
+) -> Result<(), PostExecutionError> {
+    for ordered_event in &call_info.execution.events {
+        let data_length = ordered_event.event.data.0.len();
+        if data_length > chain_info.max_event_data_size {
+            return Err(PostExecutionError::EventDataTooLong {
+                data_length,
+                max_data_length: chain_info.max_event_data_size,
+            });
+        }
+    }
+
+    for ordered_message in &call_info.execution.l2_to_l1_messages {
+        let payload_length = ordered_message.message.payload.0.len();
+        if payload_length > chain_info.max_l1_message_payload_size {
+            return Err(PostExecutionError::L2ToL1PayloadTooLong {
+                payload_length,
+                max_payload_length: chain_info.max_l1_message_payload_size,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 pub fn read_execution_retdata(
     vm: &VirtualMachine,
     retdata_size: MaybeRelocatable,
@@ -157,6 +188,32 @@ pub fn sn_api_to_cairo_vm_program(program: DeprecatedProgram) -> Result<Program,
     Ok(program)
 }
 
+/// Builds a hint-less program from raw bytecode, using the given builtins; execution starts at
+/// offset 0. Useful for constructing minimal programs.
+pub fn program_from_felts(
+    data: &[Felt252],
+    builtins: &[BuiltinName],
+) -> Result<Program, ProgramError> {
+    let data = data.iter().cloned().map(MaybeRelocatable::from).collect();
+    let main = Some(0);
+    let hints = HashMap::new();
+    let reference_manager = ReferenceManager { references: Vec::new() };
+    let identifiers = HashMap::new();
+    let error_message_attributes = vec![];
+    let instruction_locations = None;
+
+    Program::new(
+        builtins.to_vec(),
+        data,
+        main,
+        hints,
+        reference_manager,
+        identifiers,
+        error_message_attributes,
+        instruction_locations,
+    )
+}
+
 #[derive(Debug)]
 // Invariant: read-only.
 pub struct ReadOnlySegment {
@@ -279,3 +336,28 @@ pub fn format_panic_data(felts: &[StarkFelt]) -> String {
     }
     if let [item] = &items[..] { item.clone() } else { format!("({})", items.join(", ")) }
 }
+
+/// Records the final relocated memory and public memory of a run in the given context, if
+/// requested.
+#[cfg(feature = "prover-io")]
+pub fn record_vm_memory_segments(
+    vm: &mut VirtualMachine,
+    mut runner: cairo_vm::vm::runners::cairo_runner::CairoRunner,
+    context: &mut EntryPointExecutionContext,
+) -> Result<(), PostExecutionError> {
+    if !context.should_record_memory_segments() {
+        return Ok(());
+    }
+
+    runner.finalize_segments(vm)?;
+    let segment_offsets = vm.segments.relocate_segments()?;
+    let public_memory = vm.segments.get_public_memory_addresses(&segment_offsets)?;
+    let relocate_mem = true;
+    runner.relocate(vm, relocate_mem)?;
+
+    context.memory_segments = Some(crate::execution::entry_point::VmMemorySegments {
+        relocated_memory: runner.relocated_memory,
+        public_memory,
+    });
+    Ok(())
+}
@@ -1,12 +1,15 @@
 use std::iter::zip;
 
 use cairo_felt::Felt252;
+use cairo_vm::serde::deserialize_program::BuiltinName;
 use num_bigint::BigUint;
 use num_traits::{One, Zero};
 use pretty_assertions::assert_eq;
 use starknet_api::hash::StarkFelt;
 
-use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::execution::execution_utils::{
+    felt_to_stark_felt, program_from_felts, stark_felt_to_felt,
+};
 
 fn starkfelt_to_felt_pairs() -> Vec<(StarkFelt, Felt252)> {
     // The STARK prime is 2 ^ 251 + 17 * 2 ^ 192 + 1.
@@ -45,3 +48,16 @@ fn test_felt_to_stark_felt() {
         assert_eq!(felt_to_stark_felt(&felt), equivalent_stark_felt);
     }
 }
+
+#[test]
+fn test_program_from_felts() {
+    // `[ap] = 1, ap++; ret`.
+    let data = [
+        Felt252::from(0x480680017fff8000_u64),
+        Felt252::one(),
+        Felt252::from(0x208b7fff7fff7ffe_u64),
+    ];
+    let program = program_from_felts(&data, &[BuiltinName::range_check]).unwrap();
+    assert_eq!(program.data_len(), data.len());
+    assert_eq!(program.builtins_len(), 1);
+}
@@ -47,10 +47,12 @@ use crate::execution::syscalls::{
     StorageReadResponse, StorageWriteResponse, SyscallRequest, SyscallRequestWrapper,
     SyscallResponse, SyscallResponseWrapper, SyscallResult, SyscallSelector,
 };
+use crate::state::cached_state::NestedState;
 use crate::state::errors::StateError;
 use crate::state::state_api::State;
 use crate::transaction::objects::{AccountTransactionContext, CurrentAccountTransactionContext};
 use crate::transaction::transaction_utils::update_remaining_gas;
+use crate::utils::format_felt_hex;
 
 pub type SyscallCounter = HashMap<SyscallSelector, usize>;
 
@@ -58,7 +60,7 @@ pub type SyscallCounter = HashMap<SyscallSelector, usize>;
 pub enum SyscallExecutionError {
     #[error("Bad syscall_ptr; expected: {expected_ptr:?}, got: {actual_ptr:?}.")]
     BadSyscallPointer { expected_ptr: Relocatable, actual_ptr: Relocatable },
-    #[error("Cannot replace V1 class hash with V0 class hash: {class_hash}.")]
+    #[error("Cannot replace V1 class hash with V0 class hash: {}.", format_felt_hex(&class_hash.0))]
     ForbiddenClassReplacement { class_hash: ClassHash },
     #[error("Invalid address domain: {address_domain}.")]
     InvalidAddressDomain { address_domain: StarkFelt },
@@ -105,6 +107,9 @@ pub const BLOCK_NUMBER_OUT_OF_RANGE_ERROR: &str =
 // "Invalid input length";
 pub const INVALID_INPUT_LENGTH_ERROR: &str =
     "0x000000000000000000000000496e76616c696420696e707574206c656e677468";
+// "INJECTED_FAULT"; returned by syscalls that fail by fault injection (`fault-injection` feature).
+pub const INJECTED_FAULT_ERROR: &str =
+    "0x000000000000000000000000000000000000494e4a45435445445f4641554c54";
 // "Invalid argument";
 pub const INVALID_ARGUMENT: &str =
     "0x00000000000000000000000000000000496e76616c696420617267756d656e74";
@@ -223,78 +228,84 @@ impl<'a> SyscallHintProcessor<'a> {
 
         let selector = SyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
 
-        // Keccak resource usage depends on the input length, so we increment the syscall count
-        // in the syscall execution callback.
+        // Keccak resource usage depends on the input length, so its resources are recorded in the
+        // syscall execution callback.
         if selector != SyscallSelector::Keccak {
             self.increment_syscall_count(&selector);
         }
 
+        let block_context = &self.context.block_context;
+        let Some(gas_cost) = block_context
+            .chain_info
+            .syscall_gas_costs
+            .get(&selector)
+            .or_else(|| block_context.versioned_constants.syscall_gas_cost(&selector))
+        else {
+            return Err(HintError::UnknownHint(
+                format!("Unsupported syscall selector {selector:?}.").into(),
+            ));
+        };
+
         match selector {
             SyscallSelector::CallContract => {
-                self.execute_syscall(vm, call_contract, constants::CALL_CONTRACT_GAS_COST)
-            }
-            SyscallSelector::Deploy => self.execute_syscall(vm, deploy, constants::DEPLOY_GAS_COST),
-            SyscallSelector::EmitEvent => {
-                self.execute_syscall(vm, emit_event, constants::EMIT_EVENT_GAS_COST)
+                self.execute_syscall(vm, selector, call_contract, gas_cost)
             }
+            SyscallSelector::Deploy => self.execute_syscall(vm, selector, deploy, gas_cost),
+            SyscallSelector::EmitEvent => self.execute_syscall(vm, selector, emit_event, gas_cost),
             SyscallSelector::GetBlockHash => {
-                self.execute_syscall(vm, get_block_hash, constants::GET_BLOCK_HASH_GAS_COST)
+                self.execute_syscall(vm, selector, get_block_hash, gas_cost)
             }
             SyscallSelector::GetExecutionInfo => {
-                self.execute_syscall(vm, get_execution_info, constants::GET_EXECUTION_INFO_GAS_COST)
+                self.execute_syscall(vm, selector, get_execution_info, gas_cost)
             }
-            SyscallSelector::Keccak => self.execute_syscall(vm, keccak, constants::KECCAK_GAS_COST),
+            SyscallSelector::Keccak => self.execute_syscall(vm, selector, keccak, gas_cost),
             SyscallSelector::LibraryCall => {
-                self.execute_syscall(vm, library_call, constants::LIBRARY_CALL_GAS_COST)
+                self.execute_syscall(vm, selector, library_call, gas_cost)
             }
             SyscallSelector::LibraryCallL1Handler => {
-                self.execute_syscall(vm, library_call_l1_handler, constants::LIBRARY_CALL_GAS_COST)
+                self.execute_syscall(vm, selector, library_call_l1_handler, gas_cost)
             }
             SyscallSelector::ReplaceClass => {
-                self.execute_syscall(vm, replace_class, constants::REPLACE_CLASS_GAS_COST)
+                self.execute_syscall(vm, selector, replace_class, gas_cost)
             }
             SyscallSelector::Secp256k1Add => {
-                self.execute_syscall(vm, secp256k1_add, constants::SECP256K1_ADD_GAS_COST)
+                self.execute_syscall(vm, selector, secp256k1_add, gas_cost)
+            }
+            SyscallSelector::Secp256k1GetPointFromX => {
+                self.execute_syscall(vm, selector, secp256k1_get_point_from_x, gas_cost)
             }
-            SyscallSelector::Secp256k1GetPointFromX => self.execute_syscall(
-                vm,
-                secp256k1_get_point_from_x,
-                constants::SECP256K1_GET_POINT_FROM_X_GAS_COST,
-            ),
             SyscallSelector::Secp256k1GetXy => {
-                self.execute_syscall(vm, secp256k1_get_xy, constants::SECP256K1_GET_XY_GAS_COST)
+                self.execute_syscall(vm, selector, secp256k1_get_xy, gas_cost)
             }
             SyscallSelector::Secp256k1Mul => {
-                self.execute_syscall(vm, secp256k1_mul, constants::SECP256K1_MUL_GAS_COST)
+                self.execute_syscall(vm, selector, secp256k1_mul, gas_cost)
             }
             SyscallSelector::Secp256k1New => {
-                self.execute_syscall(vm, secp256k1_new, constants::SECP256K1_NEW_GAS_COST)
+                self.execute_syscall(vm, selector, secp256k1_new, gas_cost)
             }
             SyscallSelector::Secp256r1Add => {
-                self.execute_syscall(vm, secp256r1_add, constants::SECP256R1_ADD_GAS_COST)
+                self.execute_syscall(vm, selector, secp256r1_add, gas_cost)
+            }
+            SyscallSelector::Secp256r1GetPointFromX => {
+                self.execute_syscall(vm, selector, secp256r1_get_point_from_x, gas_cost)
             }
-            SyscallSelector::Secp256r1GetPointFromX => self.execute_syscall(
-                vm,
-                secp256r1_get_point_from_x,
-                constants::SECP256R1_GET_POINT_FROM_X_GAS_COST,
-            ),
             SyscallSelector::Secp256r1GetXy => {
-                self.execute_syscall(vm, secp256r1_get_xy, constants::SECP256R1_GET_XY_GAS_COST)
+                self.execute_syscall(vm, selector, secp256r1_get_xy, gas_cost)
             }
             SyscallSelector::Secp256r1Mul => {
-                self.execute_syscall(vm, secp256r1_mul, constants::SECP256R1_MUL_GAS_COST)
+                self.execute_syscall(vm, selector, secp256r1_mul, gas_cost)
             }
             SyscallSelector::Secp256r1New => {
-                self.execute_syscall(vm, secp256r1_new, constants::SECP256R1_NEW_GAS_COST)
+                self.execute_syscall(vm, selector, secp256r1_new, gas_cost)
             }
             SyscallSelector::SendMessageToL1 => {
-                self.execute_syscall(vm, send_message_to_l1, constants::SEND_MESSAGE_TO_L1_GAS_COST)
+                self.execute_syscall(vm, selector, send_message_to_l1, gas_cost)
             }
             SyscallSelector::StorageRead => {
-                self.execute_syscall(vm, storage_read, constants::STORAGE_READ_GAS_COST)
+                self.execute_syscall(vm, selector, storage_read, gas_cost)
             }
             SyscallSelector::StorageWrite => {
-                self.execute_syscall(vm, storage_write, constants::STORAGE_WRITE_GAS_COST)
+                self.execute_syscall(vm, selector, storage_write, gas_cost)
             }
             _ => Err(HintError::UnknownHint(
                 format!("Unsupported syscall selector {selector:?}.").into(),
@@ -347,6 +358,7 @@ impl<'a> SyscallHintProcessor<'a> {
     fn execute_syscall<Request, Response, ExecuteCallback>(
         &mut self,
         vm: &mut VirtualMachine,
+        selector: SyscallSelector,
         execute_callback: ExecuteCallback,
         syscall_gas_cost: u64,
     ) -> HintExecutionResult
@@ -379,7 +391,13 @@ impl<'a> SyscallHintProcessor<'a> {
 
         // Execute.
         let mut remaining_gas = gas_counter - required_gas;
-        let original_response = execute_callback(request, vm, self, &mut remaining_gas);
+        let original_response = if self.is_fault_injected(&selector) {
+            let injected_fault_error =
+                StarkFelt::try_from(INJECTED_FAULT_ERROR).map_err(SyscallExecutionError::from)?;
+            Err(SyscallExecutionError::SyscallError { error_data: vec![injected_fault_error] })
+        } else {
+            execute_callback(request, vm, self, &mut remaining_gas)
+        };
         let response = match original_response {
             Ok(response) => {
                 SyscallResponseWrapper::Success { gas_counter: remaining_gas, response }
@@ -395,6 +413,18 @@ impl<'a> SyscallHintProcessor<'a> {
         Ok(())
     }
 
+    /// Returns whether the given syscall is set to fail, by the fault-injection configuration of
+    /// the execution context.
+    #[cfg(any(feature = "fault-injection", test))]
+    fn is_fault_injected(&self, selector: &SyscallSelector) -> bool {
+        self.context.faults.failing_syscalls.contains(selector)
+    }
+
+    #[cfg(not(any(feature = "fault-injection", test)))]
+    fn is_fault_injected(&self, _selector: &SyscallSelector) -> bool {
+        false
+    }
+
     fn read_next_syscall_selector(&mut self, vm: &mut VirtualMachine) -> SyscallResult<StarkFelt> {
         let selector = stark_felt_from_ptr(vm, &mut self.syscall_ptr)?;
 
@@ -438,16 +468,16 @@ impl<'a> SyscallHintProcessor<'a> {
         let block_info: Vec<StarkFelt> = if self.is_validate_mode() {
             vec![
                 // TODO(Yoni, 1/5/2024): set the number to be zero for `validate`.
-                StarkFelt::from(block_context.block_number.0),
+                StarkFelt::from(block_context.block_info.block_number.0),
                 // TODO(Yoni, 1/5/2024): set the timestamp to be zero for `validate`.
-                StarkFelt::from(block_context.block_timestamp.0),
+                StarkFelt::from(block_context.block_info.block_timestamp.0),
                 StarkFelt::ZERO,
             ]
         } else {
             vec![
-                StarkFelt::from(block_context.block_number.0),
-                StarkFelt::from(block_context.block_timestamp.0),
-                *block_context.sequencer_address.0.key(),
+                StarkFelt::from(block_context.block_info.block_number.0),
+                StarkFelt::from(block_context.block_info.block_timestamp.0),
+                *block_context.block_info.sequencer_address.0.key(),
             ]
         };
         let (block_info_segment_start_ptr, _) = self.allocate_data_segment(vm, block_info)?;
@@ -478,7 +508,8 @@ impl<'a> SyscallHintProcessor<'a> {
             tx_signature_start_ptr.into(),
             tx_signature_end_ptr.into(),
             stark_felt_to_felt((self.context.account_tx_context).transaction_hash().0).into(),
-            Felt252::from_bytes_be(self.context.block_context.chain_id.0.as_bytes()).into(),
+            Felt252::from_bytes_be(self.context.block_context.chain_info.chain_id.0.as_bytes())
+                .into(),
             stark_felt_to_felt((self.context.account_tx_context).nonce().0).into(),
         ];
 
@@ -643,14 +674,22 @@ pub fn execute_inner_call(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     remaining_gas: &mut u64,
 ) -> SyscallResult<ReadOnlySegment> {
-    let call_info =
-        call.execute(syscall_handler.state, syscall_handler.resources, syscall_handler.context)?;
+    let call_info = if syscall_handler.context.partial_revert {
+        execute_call_with_partial_revert(call, syscall_handler)?
+    } else {
+        call.execute(syscall_handler.state, syscall_handler.resources, syscall_handler.context)?
+    };
     let raw_retdata = &call_info.execution.retdata.0;
 
     if call_info.execution.failed {
         // TODO(spapini): Append an error word according to starknet spec if needed.
         // Something like "EXECUTION_ERROR".
-        return Err(SyscallExecutionError::SyscallError { error_data: raw_retdata.clone() });
+        let error_data = raw_retdata.clone();
+        // The gas consumed by the reverted call is still charged.
+        update_remaining_gas(remaining_gas, &call_info);
+        // Keep track of the reverted call.
+        syscall_handler.inner_calls.push(call_info);
+        return Err(SyscallExecutionError::SyscallError { error_data });
     }
 
     let retdata_segment = create_retdata_segment(vm, syscall_handler, raw_retdata)?;
@@ -661,6 +700,41 @@ pub fn execute_inner_call(
     Ok(retdata_segment)
 }
 
+/// Executes the call over a nested state, which is committed to the caller's state only if the
+/// call succeeds. If the call fails (i.e., panics, possibly by running out of gas), its changes are
+/// discarded, and its call info is returned, marked as failed; its resources are kept.
+fn execute_call_with_partial_revert(
+    call: CallEntryPoint,
+    syscall_handler: &mut SyscallHintProcessor<'_>,
+) -> SyscallResult<CallInfo> {
+    let context = &mut *syscall_handler.context;
+    let (n_emitted_events, n_sent_messages_to_l1) =
+        (context.n_emitted_events, context.n_sent_messages_to_l1);
+
+    let mut call_state = NestedState::create_nested(&mut *syscall_handler.state);
+    let result = call.execute(&mut call_state, syscall_handler.resources, context);
+    // Consume the recorded call info on every path, so that it cannot be mistaken for that of a
+    // later failure.
+    let failed_call_info = context.failed_call_info.take();
+    match result {
+        Ok(call_info) => {
+            call_state.commit_to_parent()?;
+            Ok(call_info)
+        }
+        Err(
+            EntryPointExecutionError::ExecutionFailed { .. }
+            | EntryPointExecutionError::OutOfGas { .. },
+        ) => {
+            // Drop the events and messages of the reverted call from the ordering.
+            context.n_emitted_events = n_emitted_events;
+            context.n_sent_messages_to_l1 = n_sent_messages_to_l1;
+
+            Ok(failed_call_info.expect("A failed Cairo 1.0 call should record its call info."))
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
 pub fn create_retdata_segment(
     vm: &mut VirtualMachine,
     syscall_handler: &mut SyscallHintProcessor<'_>,
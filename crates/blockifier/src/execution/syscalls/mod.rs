@@ -40,7 +40,7 @@ pub mod syscalls_test;
 pub type SyscallResult<T> = Result<T, SyscallExecutionError>;
 pub type WriteResponseResult = SyscallResult<()>;
 
-type SyscallSelector = DeprecatedSyscallSelector;
+pub type SyscallSelector = DeprecatedSyscallSelector;
 
 pub trait SyscallRequest: Sized {
     fn read(_vm: &VirtualMachine, _ptr: &mut Relocatable) -> SyscallResult<Self>;
@@ -352,7 +352,7 @@ pub fn get_block_hash(
     }
 
     let requested_block_number = request.block_number.0;
-    let current_block_number = syscall_handler.context.block_context.block_number.0;
+    let current_block_number = syscall_handler.context.block_context.block_info.block_number.0;
 
     if current_block_number < constants::STORED_BLOCK_HASH_BUFFER
         || requested_block_number > current_block_number - constants::STORED_BLOCK_HASH_BUFFER
@@ -362,6 +362,12 @@ pub fn get_block_hash(
         return Err(SyscallExecutionError::SyscallError { error_data: vec![out_of_range_error] });
     }
 
+    // Prefer block hashes supplied by the caller; fall back to the block hash contract storage.
+    let block_context = &syscall_handler.context.block_context;
+    if let Some(block_hash) = block_context.block_hash_of(request.block_number) {
+        return Ok(GetBlockHashResponse { block_hash: BlockHash(block_hash) });
+    }
+
     let key = StorageKey::try_from(StarkFelt::from(requested_block_number))?;
     let block_hash_contract_address =
         ContractAddress::try_from(StarkFelt::from(constants::BLOCK_HASH_CONTRACT_ADDRESS))?;
@@ -659,7 +665,8 @@ pub fn keccak(
         });
     }
 
-    let gas_cost = n_rounds as u64 * constants::KECCAK_ROUND_COST_GAS_COST;
+    let versioned_constants = &syscall_handler.context.block_context.versioned_constants;
+    let gas_cost = n_rounds as u64 * versioned_constants.keccak_round_gas_cost;
     if gas_cost > *remaining_gas {
         let out_of_gas_error =
             StarkFelt::try_from(OUT_OF_GAS_ERROR).map_err(SyscallExecutionError::from)?;
@@ -668,9 +675,12 @@ pub fn keccak(
     }
     *remaining_gas -= gas_cost;
 
-    // For the keccak system call we want to count the number of rounds rather than the number of
-    // syscall invocations.
-    syscall_handler.increment_syscall_count_by(&SyscallSelector::Keccak, n_rounds);
+    // Record the OS resources of the rounds in the call's resources, as they depend on the input
+    // length.
+    syscall_handler.resources.n_keccak_rounds += n_rounds;
+    let keccak_resources =
+        versioned_constants.os_resources.resources_for_syscall(&SyscallSelector::Keccak) * n_rounds;
+    syscall_handler.resources.vm_resources += &keccak_resources;
 
     let data = vm.get_integer_range(request.input_start, input_length)?;
 
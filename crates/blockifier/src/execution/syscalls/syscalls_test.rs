@@ -3,11 +3,14 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use assert_matches::assert_matches;
 use cairo_felt::Felt252;
 use cairo_lang_utils::byte_array::BYTE_ARRAY_MAGIC;
-use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::runners::builtin_runner::{KECCAK_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME};
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use cairo_vm::vm::vm_core::VirtualMachine;
 use itertools::concat;
 use num_traits::Pow;
 use pretty_assertions::assert_eq;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::{
     calculate_contract_address, ChainId, ClassHash, ContractAddress, EthAddress, Nonce, PatriciaKey,
 };
@@ -15,27 +18,34 @@ use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
-    AccountDeploymentData, Calldata, ContractAddressSalt, EventContent, EventData, EventKey, Fee,
-    L2ToL1Payload, PaymasterData, Resource, ResourceBounds, ResourceBoundsMapping, Tip,
-    TransactionHash, TransactionVersion,
+    AccountDeploymentData, Calldata, ContractAddressSalt, Event, EventContent, EventData, EventKey,
+    Fee, L2ToL1Payload, PaymasterData, Resource, ResourceBounds, ResourceBoundsMapping, Tip,
+    TransactionHash, TransactionSignature, TransactionVersion,
 };
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 use test_case::test_case;
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants;
-use crate::block_context::BlockContext;
+use crate::block_context::{BlockContext, SyscallGasCosts};
 use crate::execution::call_info::{
     CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
 };
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::contract_class::ContractClassV0;
-use crate::execution::entry_point::{CallEntryPoint, CallType};
-use crate::execution::errors::{EntryPointExecutionError, VirtualMachineExecutionError};
-use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::execution::entry_point::{
+    CallEntryPoint, CallType, EntryPointExecutionContext, ExecutionResources, FaultConfig,
+};
+use crate::execution::errors::{
+    EntryPointExecutionError, PostExecutionError, VirtualMachineExecutionError,
+};
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt, ReadOnlySegments};
 use crate::execution::syscalls::hint_processor::{
-    BLOCK_NUMBER_OUT_OF_RANGE_ERROR, L1_GAS, L2_GAS, OUT_OF_GAS_ERROR,
+    execute_inner_call, SyscallExecutionError, SyscallHintProcessor,
+    BLOCK_NUMBER_OUT_OF_RANGE_ERROR, INJECTED_FAULT_ERROR, L1_GAS, L2_GAS, OUT_OF_GAS_ERROR,
 };
+use crate::execution::syscalls::{keccak, KeccakRequest, SyscallSelector};
+use crate::fee::os_resources::OS_RESOURCES;
 use crate::state::state_api::{State, StateReader};
 use crate::test_utils::cached_state::{create_deploy_test_state, create_test_state};
 use crate::test_utils::contracts::FeatureContract;
@@ -48,8 +58,9 @@ use crate::test_utils::{
 use crate::transaction::constants::QUERY_VERSION_BASE_BIT;
 use crate::transaction::objects::{
     AccountTransactionContext, CommonAccountFields, CurrentAccountTransactionContext,
-    DeprecatedAccountTransactionContext,
+    DeprecatedAccountTransactionContext, TransactionExecutionInfo,
 };
+use crate::utils::format_felt_hex;
 use crate::{check_entry_point_execution_error_for_custom_hint, retdata};
 
 pub const REQUIRED_GAS_STORAGE_READ_WRITE_TEST: u64 = 34650;
@@ -83,6 +94,32 @@ fn test_storage_read_write() {
     assert_eq!(value_from_state, value);
 }
 
+#[test]
+fn test_storage_read_fault_injection() {
+    let mut state = create_test_state();
+
+    let key = stark_felt!(1234_u16);
+    let value = stark_felt!(18_u8);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![key, value],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point()
+    };
+
+    // Without faults, the execution succeeds.
+    let call_info = entry_point_call
+        .execute_directly_with_fault_injection(&mut state, &FaultConfig::default())
+        .unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![value]);
+
+    // A failing storage read is propagated to the contract, which panics with the syscall error.
+    let faults = FaultConfig { failing_syscalls: HashSet::from([SyscallSelector::StorageRead]) };
+    let error =
+        entry_point_call.execute_directly_with_fault_injection(&mut state, &faults).unwrap_err();
+    assert_matches!(error, EntryPointExecutionError::ExecutionFailed{ error_data }
+        if error_data == vec![stark_felt!(INJECTED_FAULT_ERROR)]);
+}
+
 #[test]
 fn test_call_contract() {
     let mut state = create_test_state();
@@ -144,6 +181,61 @@ fn test_emit_event() {
     );
 }
 
+#[test]
+fn test_emit_event_custom_gas_cost() {
+    let mut state = create_test_state();
+    let calldata = calldata![stark_felt!(0_u8), stark_felt!(0_u8)];
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_emit_event"),
+        calldata,
+        ..trivial_external_entry_point()
+    };
+
+    let canonical_gas_consumed =
+        entry_point_call.clone().execute_directly(&mut state).unwrap().execution.gas_consumed;
+
+    let extra_gas_cost = 1000;
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.chain_info_mut().syscall_gas_costs = SyscallGasCosts::new(HashMap::from([(
+        SyscallSelector::EmitEvent,
+        constants::EMIT_EVENT_GAS_COST + extra_gas_cost,
+    )]))
+    .unwrap();
+    let gas_consumed = entry_point_call
+        .execute_directly_given_block_context(&mut state, block_context)
+        .unwrap()
+        .execution
+        .gas_consumed;
+    assert_eq!(gas_consumed, canonical_gas_consumed + extra_gas_cost);
+}
+
+#[test]
+fn test_emit_event_data_too_long() {
+    let mut state = create_test_state();
+    let data = vec![stark_felt!(2021_u16), stark_felt!(2022_u16), stark_felt!(2023_u16)];
+    let calldata = Calldata(
+        concat(vec![vec![stark_felt!(0_u8)], vec![stark_felt!(data.len() as u8)], data]).into(),
+    );
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_emit_event"),
+        calldata,
+        ..trivial_external_entry_point()
+    };
+
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.chain_info_mut().max_event_data_size = 2;
+    let error = entry_point_call
+        .execute_directly_given_block_context(&mut state, block_context)
+        .unwrap_err();
+    assert_matches!(
+        error,
+        EntryPointExecutionError::PostExecutionError(PostExecutionError::EventDataTooLong {
+            data_length: 3,
+            max_data_length: 2
+        })
+    );
+}
+
 #[test]
 fn test_get_block_hash() {
     let mut state = create_test_state();
@@ -191,6 +283,36 @@ fn test_get_block_hash() {
         if error_data == vec![stark_felt!(BLOCK_NUMBER_OUT_OF_RANGE_ERROR)]);
 }
 
+#[test]
+fn test_get_block_hash_from_block_context() {
+    let mut state = create_test_state();
+    let upper_bound_block_number = CURRENT_BLOCK_NUMBER - constants::STORED_BLOCK_HASH_BUFFER;
+    let block_hash = stark_felt!(77_u64);
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.block_info.block_hashes.insert(BlockNumber(upper_bound_block_number), block_hash);
+    // A hash supplied for a block within the recent window must not be served.
+    let recent_block_number = upper_bound_block_number + 1;
+    block_context.block_info.block_hashes.insert(BlockNumber(recent_block_number), block_hash);
+
+    let execute_with_block_context = |state: &mut dyn State, requested_block_number: u64| {
+        let entry_point_call = CallEntryPoint {
+            entry_point_selector: selector_from_name("test_get_block_hash"),
+            calldata: calldata![stark_felt!(requested_block_number)],
+            ..trivial_external_entry_point()
+        };
+        entry_point_call.execute_directly_given_block_context(state, block_context.clone())
+    };
+
+    // Positive flow: the hash is read from the block context, not from the state.
+    let call_info = execute_with_block_context(&mut state, upper_bound_block_number).unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![block_hash]);
+
+    // Negative flow: block number within the forbidden recent window.
+    let error = execute_with_block_context(&mut state, recent_block_number).unwrap_err();
+    assert_matches!(error, EntryPointExecutionError::ExecutionFailed{ error_data }
+        if error_data == vec![stark_felt!(BLOCK_NUMBER_OUT_OF_RANGE_ERROR)]);
+}
+
 #[test]
 fn test_keccak() {
     let mut state = create_test_state();
@@ -202,10 +324,63 @@ fn test_keccak() {
         ..trivial_external_entry_point()
     };
 
+    let call_info = entry_point_call.execute_directly(&mut state).unwrap();
     assert_eq!(
-        entry_point_call.execute_directly(&mut state).unwrap().execution,
+        call_info.execution,
         CallExecution { gas_consumed: 354940, ..CallExecution::from_retdata(retdata![]) }
     );
+    // A single round, of a one-word input; the invalid-length input is rejected before it runs.
+    assert_eq!(call_info.vm_resources.builtin_instance_counter.get(KECCAK_BUILTIN_NAME), Some(&1));
+}
+
+#[test_case(1; "one round")]
+#[test_case(3; "three rounds")]
+fn test_keccak_resources(n_rounds: usize) {
+    let mut state = create_test_state();
+    let block_context = BlockContext::create_for_testing();
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+    let mut resources = ExecutionResources::default();
+    let hints = HashMap::new();
+    let mut vm = VirtualMachine::new(false);
+    let mut syscall_handler = SyscallHintProcessor::new(
+        &mut state,
+        &mut resources,
+        &mut context,
+        Relocatable::from((0, 0)),
+        trivial_external_entry_point(),
+        &hints,
+        ReadOnlySegments::default(),
+    );
+
+    // A keccak round absorbs 17 words.
+    let input = vec![MaybeRelocatable::from(Felt252::from(1_u8)); 17 * n_rounds];
+    let input_start = vm.add_memory_segment();
+    let input_end = vm.load_data(input_start, &input).unwrap();
+    let mut remaining_gas = constants::INITIAL_GAS_COST;
+    keccak(
+        KeccakRequest { input_start, input_end },
+        &mut vm,
+        &mut syscall_handler,
+        &mut remaining_gas,
+    )
+    .unwrap();
+
+    assert_eq!(
+        remaining_gas,
+        constants::INITIAL_GAS_COST - n_rounds as u64 * constants::KECCAK_ROUND_COST_GAS_COST
+    );
+    assert_eq!(resources.n_keccak_rounds, n_rounds);
+    assert_eq!(resources.syscall_counter.get(&SyscallSelector::Keccak), None);
+    assert_eq!(
+        resources.vm_resources,
+        OS_RESOURCES.resources_for_syscall(&SyscallSelector::Keccak) * n_rounds
+    );
+    assert_eq!(resources.vm_resources.builtin_instance_counter[KECCAK_BUILTIN_NAME], n_rounds);
 }
 
 fn verify_compiler_version(contract: FeatureContract, expected_version: &str) {
@@ -437,6 +612,77 @@ fn test_get_execution_info(
     assert!(!result.unwrap().execution.failed);
 }
 
+#[test]
+fn test_get_execution_info_from_configured_context() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut block_context = BlockContext::create_for_testing();
+    let state = &mut test_state(&block_context, BALANCE, &[(test_contract, 1)]);
+    let test_contract_address = test_contract.get_instance_address(0);
+
+    // Configure non-default values for all fields read via the syscall.
+    block_context.block_info.block_number = BlockNumber(1234);
+    block_context.block_info.block_timestamp = BlockTimestamp(1_700_000_000);
+    block_context.block_info.sequencer_address = contract_address!("0x5678");
+    block_context.chain_info_mut().chain_id = ChainId("SN_CUSTOM".to_string());
+    let account_tx_context =
+        AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext {
+            common_fields: CommonAccountFields {
+                transaction_hash: TransactionHash(stark_felt!(1991_u16)),
+                version: TransactionVersion::ONE,
+                signature: TransactionSignature(vec![stark_felt!(7_u8), stark_felt!(8_u8)]),
+                nonce: Nonce(stark_felt!(3_u16)),
+                sender_address: test_contract_address,
+                only_query: false,
+            },
+            max_fee: Fee(42),
+        });
+
+    let entry_point_selector = selector_from_name("test_get_execution_info");
+    let expected_execution_info = vec![
+        // Block info.
+        stark_felt!(1234_u16),          // Block number.
+        stark_felt!(1_700_000_000_u64), // Block timestamp.
+        stark_felt!("0x5678"),          // Sequencer address.
+        // Transaction info.
+        TransactionVersion::ONE.0,      // Transaction version.
+        *test_contract_address.0.key(), // Account address.
+        stark_felt!(42_u8),             // Max fee.
+        stark_felt!(2_u8),              // Signature length.
+        stark_felt!(7_u8),              // Signature.
+        stark_felt!(8_u8),              // Signature.
+        stark_felt!(1991_u16),          // Transaction hash.
+        stark_felt!(&*ChainId("SN_CUSTOM".to_string()).as_hex()), // Chain ID.
+        stark_felt!(3_u16),             // Nonce.
+        StarkFelt::ZERO,                // Resource bounds length.
+        StarkFelt::ZERO,                // Tip.
+        StarkFelt::ZERO,                // Paymaster data.
+        StarkFelt::ZERO,                // Nonce DA.
+        StarkFelt::ZERO,                // Fee DA.
+        StarkFelt::ZERO,                // Account data.
+        // Call info.
+        StarkFelt::ZERO,                     // Caller address.
+        *test_contract_address.0.key(),      // Storage address.
+        stark_felt!(entry_point_selector.0), // Entry point selector.
+    ];
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector,
+        storage_address: test_contract_address,
+        calldata: Calldata(expected_execution_info.into()),
+        ..trivial_external_entry_point()
+    };
+
+    let limit_steps_by_resources = false;
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &block_context,
+        &account_tx_context,
+        limit_steps_by_resources,
+    )
+    .unwrap();
+    let call_info =
+        entry_point_call.execute(state, &mut ExecutionResources::default(), &mut context).unwrap();
+    assert!(!call_info.execution.failed);
+}
+
 #[test]
 fn test_library_call() {
     let mut state = create_test_state();
@@ -466,6 +712,76 @@ fn test_library_call() {
     );
 }
 
+#[test]
+fn test_library_call_event_attribution() {
+    let mut state = create_test_state();
+
+    let (key, data) = (stark_felt!(2019_u16), stark_felt!(2021_u16));
+    let calldata = calldata![
+        stark_felt!(TEST_CLASS_HASH),            // Class hash.
+        selector_from_name("test_emit_event").0, // Function selector.
+        stark_felt!(4_u8),                       // Calldata length.
+        stark_felt!(1_u8),                       // Calldata: keys length.
+        key,                                     // Calldata: keys.
+        stark_felt!(1_u8),                       // Calldata: data length.
+        data                                     // Calldata: data.
+    ];
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_library_call"),
+        calldata,
+        class_hash: Some(class_hash!(TEST_CLASS_HASH)),
+        ..trivial_external_entry_point()
+    };
+    let call_info = entry_point_call.execute_directly(&mut state).unwrap();
+    assert_eq!(call_info.inner_calls[0].call.call_type, CallType::Delegate);
+
+    // The event is emitted by the library call, on behalf of the caller.
+    let tx_execution_info =
+        TransactionExecutionInfo { execute_call_info: Some(call_info), ..Default::default() };
+    assert_eq!(
+        tx_execution_info.ordered_events(),
+        vec![Event {
+            from_address: contract_address!(TEST_CONTRACT_ADDRESS),
+            content: EventContent { keys: vec![EventKey(key)], data: EventData(vec![data]) },
+        }]
+    );
+}
+
+#[test]
+fn test_library_call_uses_caller_storage() {
+    let mut state = create_test_state();
+
+    let (key, value) = (1234_u16, 91_u8);
+    let calldata = calldata![
+        stark_felt!(TEST_CLASS_HASH),                    // Class hash.
+        selector_from_name("test_storage_read_write").0, // Function selector.
+        stark_felt!(2_u8),                               // Calldata length.
+        stark_felt!(key),                                // Calldata: address.
+        stark_felt!(value)                               // Calldata: value.
+    ];
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_library_call"),
+        calldata,
+        class_hash: Some(class_hash!(TEST_CLASS_HASH)),
+        ..trivial_external_entry_point()
+    };
+    let (caller_storage_address, caller_caller_address) =
+        (entry_point_call.storage_address, entry_point_call.caller_address);
+
+    let call_info = entry_point_call.execute_directly(&mut state).unwrap();
+
+    // The library call runs in the (storage and caller) context of its caller.
+    let library_call = &call_info.inner_calls[0].call;
+    assert_eq!(library_call.call_type, CallType::Delegate);
+    assert_eq!(library_call.code_address, None);
+    assert_eq!(library_call.storage_address, caller_storage_address);
+    assert_eq!(library_call.caller_address, caller_caller_address);
+    assert_eq!(
+        state.get_storage_at(caller_storage_address, StorageKey(patricia_key!(key))).unwrap(),
+        stark_felt!(value)
+    );
+}
+
 #[test]
 fn test_library_call_assert_fails() {
     let mut state = create_test_state();
@@ -621,7 +937,10 @@ fn test_replace_class() {
         ..trivial_external_entry_point()
     };
     let error = entry_point_call.execute_directly(&mut state).unwrap_err().to_string();
-    assert!(error.contains("is not declared"));
+    assert!(error.contains(&format!(
+        "Class with hash {} is not declared.",
+        format_felt_hex(&stark_felt!(1234_u16))
+    )));
 
     // Replace with Cairo 0 class hash.
     let v0_class_hash = class_hash!(5678_u16);
@@ -634,7 +953,10 @@ fn test_replace_class() {
         ..trivial_external_entry_point()
     };
     let error = entry_point_call.execute_directly(&mut state).unwrap_err().to_string();
-    assert!(error.contains("Cannot replace V1 class hash with V0 class hash"));
+    assert!(error.contains(&format!(
+        "Cannot replace V1 class hash with V0 class hash: {}.",
+        format_felt_hex(&v0_class_hash.0)
+    )));
 
     // Positive flow.
     let contract_address = contract_address!(TEST_CONTRACT_ADDRESS);
@@ -647,10 +969,18 @@ fn test_replace_class() {
         ..trivial_external_entry_point()
     };
     assert_eq!(
-        entry_point_call.execute_directly(&mut state).unwrap().execution,
+        entry_point_call.clone().execute_directly(&mut state).unwrap().execution,
         CallExecution { gas_consumed: 14450, ..Default::default() }
     );
     assert_eq!(state.get_class_hash_at(contract_address).unwrap(), new_class_hash);
+    assert_eq!(
+        state.to_state_diff().address_to_class_hash.get(&contract_address),
+        Some(&new_class_hash)
+    );
+
+    // Subsequent calls are resolved against the new (empty) class.
+    let error = entry_point_call.execute_directly(&mut state).unwrap_err().to_string();
+    assert!(error.contains("not found in contract"));
 }
 
 #[test]
@@ -816,6 +1146,11 @@ fn test_deploy(
         CallExecution { retdata, gas_consumed, ..CallExecution::default() }
     );
     assert_eq!(state.get_class_hash_at(contract_address).unwrap(), class_hash);
+    // The deployment is recorded in the state diff.
+    assert_eq!(
+        state.to_state_diff().address_to_class_hash.get(&contract_address),
+        Some(&class_hash)
+    );
 }
 
 #[test]
@@ -832,8 +1167,19 @@ fn test_out_of_gas() {
         ..trivial_external_entry_point()
     };
     let error = entry_point_call.execute_directly(&mut state).unwrap_err();
-    assert_matches!(error, EntryPointExecutionError::ExecutionFailed{ error_data }
-        if error_data == vec![stark_felt!(OUT_OF_GAS_ERROR)]);
+    assert_matches!(error, EntryPointExecutionError::OutOfGas { gas_consumed, remaining }
+        if gas_consumed + remaining == REQUIRED_GAS_STORAGE_READ_WRITE_TEST - 1);
+
+    // A tiny amount of gas is exhausted before reaching any syscall.
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![key, value],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        initial_gas: 1,
+        ..trivial_external_entry_point()
+    };
+    let error = entry_point_call.execute_directly(&mut state).unwrap_err();
+    assert_matches!(error, EntryPointExecutionError::OutOfGas { gas_consumed, remaining }
+        if gas_consumed + remaining == 1);
 }
 
 #[test]
@@ -854,3 +1200,90 @@ fn test_syscall_failure_format() {
     let error = EntryPointExecutionError::ExecutionFailed { error_data };
     assert_eq!(error.to_string(), "Execution failed. Failure reason: \"Execution failure\".");
 }
+
+#[test]
+fn test_partial_revert() {
+    let mut state = create_test_state();
+    let block_context = BlockContext::create_for_testing();
+    let mut context = EntryPointExecutionContext::new_invoke(
+        &block_context,
+        &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+        true,
+    )
+    .unwrap();
+    context.partial_revert = true;
+    let mut resources = ExecutionResources::default();
+    let hints = HashMap::new();
+    let mut vm = VirtualMachine::new(false);
+
+    // Simulates a contract that calls two siblings, and recovers from the failure of the second.
+    let mut syscall_handler = SyscallHintProcessor::new(
+        &mut state,
+        &mut resources,
+        &mut context,
+        Relocatable::from((0, 0)),
+        trivial_external_entry_point(),
+        &hints,
+        ReadOnlySegments::default(),
+    );
+    let storage_read_write_call =
+        |key: StarkFelt, value: StarkFelt, initial_gas: u64| CallEntryPoint {
+            calldata: calldata![key, value],
+            entry_point_selector: selector_from_name("test_storage_read_write"),
+            initial_gas,
+            ..trivial_external_entry_point()
+        };
+    let (key, reverted_key) = (stark_felt!(1234_u16), stark_felt!(1235_u16));
+    let value = stark_felt!(18_u8);
+
+    let mut remaining_gas = constants::INITIAL_GAS_COST;
+    execute_inner_call(
+        storage_read_write_call(key, value, remaining_gas),
+        &mut vm,
+        &mut syscall_handler,
+        &mut remaining_gas,
+    )
+    .unwrap();
+
+    // Enough gas for the write, but not for the subsequent read.
+    let initial_gas = 25000;
+    let error = execute_inner_call(
+        storage_read_write_call(reverted_key, value, initial_gas),
+        &mut vm,
+        &mut syscall_handler,
+        &mut remaining_gas,
+    )
+    .unwrap_err();
+    let out_of_gas_error = vec![stark_felt!(OUT_OF_GAS_ERROR)];
+    assert_matches!(
+        error,
+        SyscallExecutionError::SyscallError { error_data }
+        if error_data == out_of_gas_error
+    );
+
+    // Both calls are tracked; only the second one is marked as reverted.
+    let inner_calls = syscall_handler.inner_calls;
+    assert_eq!(inner_calls.len(), 2);
+    assert!(!inner_calls[0].execution.failed);
+    assert!(inner_calls[1].execution.failed);
+    assert_eq!(inner_calls[1].execution.retdata, Retdata(out_of_gas_error));
+    // The resources of the reverted call are kept, and its gas is charged.
+    assert_ne!(inner_calls[1].vm_resources, VmExecutionResources::default());
+    let gas_consumed = inner_calls[1].execution.gas_consumed;
+    assert!(gas_consumed > 0);
+    assert_eq!(
+        remaining_gas,
+        constants::INITIAL_GAS_COST - inner_calls[0].execution.gas_consumed - gas_consumed
+    );
+
+    // The failed call info is consumed by its caller.
+    assert!(context.failed_call_info.is_none());
+
+    // The write of the first call persists, while that of the reverted call is discarded.
+    let storage_address = contract_address!(TEST_CONTRACT_ADDRESS);
+    let storage_at = |state: &mut dyn State, key: StarkFelt| {
+        state.get_storage_at(storage_address, StorageKey::try_from(key).unwrap()).unwrap()
+    };
+    assert_eq!(storage_at(&mut state, key), value);
+    assert_eq!(storage_at(&mut state, reverted_key), StarkFelt::default());
+}
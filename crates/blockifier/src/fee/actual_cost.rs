@@ -5,6 +5,7 @@ use crate::abi::constants as abi_constants;
 use crate::block_context::BlockContext;
 use crate::execution::call_info::CallInfo;
 use crate::execution::entry_point::ExecutionResources;
+use crate::execution::syscalls::SyscallSelector;
 use crate::state::cached_state::{CachedState, StateChanges, StateChangesCount};
 use crate::state::state_api::{StateReader, StateResult};
 use crate::transaction::objects::{
@@ -43,6 +44,7 @@ pub struct ActualCostBuilder<'a> {
     state_changes: StateChanges,
     sender_address: Option<ContractAddress>,
     l1_payload_size: Option<usize>,
+    calldata_length: usize,
     n_reverted_steps: usize,
 }
 
@@ -62,6 +64,7 @@ impl<'a> ActualCostBuilder<'a> {
             execute_call_info: None,
             state_changes: StateChanges::default(),
             l1_payload_size: None,
+            calldata_length: 0,
             n_reverted_steps: 0,
         }
     }
@@ -110,6 +113,11 @@ impl<'a> ActualCostBuilder<'a> {
         self
     }
 
+    pub fn with_calldata_length(mut self, calldata_length: usize) -> Self {
+        self.calldata_length = calldata_length;
+        self
+    }
+
     pub fn with_reverted_steps(mut self, n_reverted_steps: usize) -> Self {
         self.n_reverted_steps = n_reverted_steps;
         self
@@ -130,9 +138,33 @@ impl<'a> ActualCostBuilder<'a> {
             non_optional_call_infos,
             state_changes_count,
             self.l1_payload_size,
+        )? + self
+            .block_context
+            .chain_info
+            .calldata_l1_gas_costs
+            .l1_gas(self.calldata_length, self.account_tx_context.signature().0.len());
+        // The OS resources of the transaction type cover a single fee transfer; additional ones
+        // are charged as the `call_contract` syscalls they are executed as.
+        let n_additional_fee_transfers = match self.tx_type {
+            TransactionType::L1Handler => 0,
+            _ => self.block_context.chain_info.fee_recipient.max_n_transfers() - 1,
+        };
+        let execution_resources_with_fee_transfers;
+        let execution_resources = if n_additional_fee_transfers > 0 {
+            let mut resources = execution_resources.clone();
+            *resources.syscall_counter.entry(SyscallSelector::CallContract).or_default() +=
+                n_additional_fee_transfers;
+            execution_resources_with_fee_transfers = resources;
+            &execution_resources_with_fee_transfers
+        } else {
+            execution_resources
+        };
+        let mut actual_resources = calculate_tx_resources(
+            &self.block_context.versioned_constants,
+            execution_resources,
+            l1_gas_usage,
+            self.tx_type,
         )?;
-        let mut actual_resources =
-            calculate_tx_resources(execution_resources, l1_gas_usage, self.tx_type)?;
 
         // Add reverted steps to actual_resources' n_steps for correct fee charge.
         *actual_resources.0.get_mut(&abi_constants::N_STEPS_RESOURCE.to_string()).unwrap() +=
@@ -1,16 +1,33 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use assert_matches::assert_matches;
 use cairo_vm::vm::runners::builtin_runner::{
     BITWISE_BUILTIN_NAME, HASH_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME,
     SIGNATURE_BUILTIN_NAME,
 };
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::{Calldata, Fee, TransactionSignature, TransactionVersion};
+use starknet_api::{calldata, stark_felt};
 
 use crate::abi::constants;
-use crate::block_context::BlockContext;
-use crate::fee::fee_utils::calculate_l1_gas_by_vm_usage;
-use crate::transaction::errors::TransactionFeeError;
-use crate::transaction::objects::ResourcesMapping;
+use crate::block_context::{BlockContext, CalldataL1GasCosts};
+use crate::fee::fee_utils::{
+    calculate_fees, calculate_l1_gas_by_vm_usage, calculate_tx_fee, calculate_tx_fee_detailed,
+    calculate_tx_fee_with_rounding, charged_bytecode_size, check_fee_bounds,
+    estimate_fee_with_margin, extract_l1_gas_and_vm_usage, minimal_l1_gas, RoundingMode,
+};
+use crate::fee::gas_usage::estimate_minimal_l1_gas;
+use crate::invoke_tx_args;
+use crate::test_utils::{DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_STRK_L1_GAS_PRICE};
+use crate::transaction::errors::{TransactionExecutionError, TransactionFeeError};
+use crate::transaction::objects::{
+    AccountTransactionContext, DeprecatedAccountTransactionContext, FeeType, FeeUnit,
+    HasRelatedFeeType, ResourceName, ResourcesMapping,
+};
+use crate::transaction::test_utils::{account_invoke_tx, block_context};
+use crate::transaction::transaction_types::TransactionType;
 
 fn get_vm_resource_usage() -> ResourcesMapping {
     ResourcesMapping(HashMap::from([
@@ -44,3 +61,248 @@ fn test_calculate_l1_gas_by_vm_usage() {
         calculate_l1_gas_by_vm_usage(&block_context, &invalid_vm_resource_usage).unwrap_err();
     assert_matches!(error, TransactionFeeError::CairoResourcesNotContainedInFeeCosts);
 }
+
+#[test]
+fn test_calculate_fees() {
+    let block_context = BlockContext::create_for_account_testing();
+    let fee_type = FeeType::Eth;
+    let resources: Vec<ResourcesMapping> = [0, 100, 2000]
+        .into_iter()
+        .map(|l1_gas_usage| {
+            let mut resources = get_vm_resource_usage();
+            resources.0.insert(constants::GAS_USAGE.to_string(), l1_gas_usage);
+            resources
+        })
+        .collect();
+
+    let fees = calculate_fees(&resources, &block_context).unwrap();
+    assert_eq!(fees.len(), 3);
+    for (fee, resources) in fees.iter().zip(&resources) {
+        assert_eq!(*fee, calculate_tx_fee(resources, &block_context, &fee_type).unwrap());
+    }
+
+    // A single invalid mapping fails the whole batch.
+    let mut invalid_resources = get_vm_resource_usage();
+    invalid_resources.0.insert(constants::GAS_USAGE.to_string(), 0);
+    invalid_resources.0.insert(String::from("bad_resource_name"), 17);
+    let error =
+        calculate_fees(&[resources[0].clone(), invalid_resources], &block_context).unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::TransactionFeeError(
+            TransactionFeeError::CairoResourcesNotContainedInFeeCosts
+        )
+    );
+}
+
+#[test]
+fn test_resources_mapping_accessors() {
+    let mut resources = get_vm_resource_usage();
+
+    assert_eq!(resources.n_steps().unwrap(), 1800);
+    assert_eq!(resources.builtin(HASH_BUILTIN_NAME), 10);
+    assert_eq!(resources.builtin(ResourceName::EcOp.name()), 0);
+    assert_eq!(resources.get_or_zero("unknown_resource"), 0);
+
+    // Missing key.
+    assert_matches!(
+        resources.l1_gas_usage().unwrap_err(),
+        TransactionFeeError::MissingResource { resource: ResourceName::L1GasUsage }
+    );
+    assert_matches!(
+        extract_l1_gas_and_vm_usage(&resources).unwrap_err(),
+        TransactionFeeError::MissingResource { resource: ResourceName::L1GasUsage }
+    );
+
+    resources.0.insert(constants::GAS_USAGE.to_string(), 17);
+    let (l1_gas_usage, vm_resources) = extract_l1_gas_and_vm_usage(&resources).unwrap();
+    assert_eq!(l1_gas_usage, 17);
+    assert_eq!(vm_resources, get_vm_resource_usage());
+}
+
+#[test]
+fn test_calculate_tx_fee_missing_l1_gas_usage() {
+    // Resources without the L1 gas usage key, e.g., malformed input from a peer.
+    let block_context = BlockContext::create_for_account_testing();
+    let resources = get_vm_resource_usage();
+
+    assert_matches!(
+        calculate_tx_fee(&resources, &block_context, &FeeType::Eth).unwrap_err(),
+        TransactionFeeError::MissingResource { resource: ResourceName::L1GasUsage }
+    );
+
+    let account_tx_context = AccountTransactionContext::Deprecated(Default::default());
+    assert_matches!(
+        account_tx_context.calculate_tx_fee(&resources, &block_context).unwrap_err(),
+        TransactionExecutionError::TransactionFeeError(TransactionFeeError::MissingResource {
+            resource: ResourceName::L1GasUsage
+        })
+    );
+}
+
+#[rstest]
+#[case::max_fee_too_low(99, false)]
+#[case::exact_max_fee(100, true)]
+#[case::sufficient_max_fee(101, true)]
+fn test_check_fee_bounds(#[case] max_fee: u128, #[case] is_sufficient: bool) {
+    let min_fee = Fee(100);
+    let account_tx_context =
+        AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext {
+            max_fee: Fee(max_fee),
+            ..Default::default()
+        });
+
+    let result = check_fee_bounds(&account_tx_context, min_fee);
+    if is_sufficient {
+        result.unwrap();
+    } else {
+        assert_matches!(
+            result.unwrap_err(),
+            TransactionFeeError::MaxFeeTooLow { min_fee: actual_min_fee, max_fee: actual_max_fee }
+            if actual_min_fee == min_fee && actual_max_fee == Fee(max_fee)
+        );
+    }
+}
+
+#[rstest]
+#[case::v1(TransactionVersion::ONE, FeeUnit::Wei, DEFAULT_ETH_L1_GAS_PRICE)]
+#[case::v3(TransactionVersion::THREE, FeeUnit::Fri, DEFAULT_STRK_L1_GAS_PRICE)]
+fn test_calculate_tx_fee_detailed(
+    #[case] version: TransactionVersion,
+    #[case] expected_unit: FeeUnit,
+    #[case] expected_gas_price: u128,
+) {
+    let block_context = BlockContext::create_for_account_testing();
+    let mut resources = get_vm_resource_usage();
+    resources.0.insert(constants::GAS_USAGE.to_string(), 10);
+    let tx = account_invoke_tx(invoke_tx_args! { version });
+    let fee_type = tx.get_account_tx_context().fee_type();
+
+    let fee_result = calculate_tx_fee_detailed(&resources, &block_context, &fee_type).unwrap();
+    assert_eq!(fee_result.fee, calculate_tx_fee(&resources, &block_context, &fee_type).unwrap());
+    assert_eq!(fee_result.unit, expected_unit);
+    assert_eq!(fee_result.gas_price, expected_gas_price);
+}
+
+#[rstest]
+#[case::no_margin(0, 100)]
+#[case::half_margin(50, 150)]
+fn test_estimate_fee_with_margin(#[case] margin_percent: u8, #[case] expected_percent: u128) {
+    let block_context = BlockContext::create_for_account_testing();
+    let fee_type = FeeType::Eth;
+    let mut resources = get_vm_resource_usage();
+    resources.0.insert(constants::GAS_USAGE.to_string(), 10);
+
+    let fee = calculate_tx_fee(&resources, &block_context, &fee_type).unwrap();
+    assert_eq!(
+        estimate_fee_with_margin(&resources, &block_context, &fee_type, margin_percent).unwrap(),
+        Fee(fee.0 * expected_percent / 100)
+    );
+}
+
+#[test]
+fn test_estimate_fee_with_margin_overflow() {
+    let mut block_context = BlockContext::create_for_account_testing();
+    let fee_type = FeeType::Eth;
+    let mut resources = get_vm_resource_usage();
+    resources.0.insert(constants::GAS_USAGE.to_string(), 10);
+
+    // Set the gas price such that the fee is roughly half of the maximal one.
+    let gas_price = block_context.block_info.gas_prices.eth_l1_gas_price;
+    let l1_gas_usage =
+        calculate_tx_fee(&resources, &block_context, &fee_type).unwrap().0 / gas_price;
+    block_context.block_info.gas_prices.eth_l1_gas_price = u128::MAX / (2 * l1_gas_usage);
+    let fee = calculate_tx_fee(&resources, &block_context, &fee_type).unwrap();
+
+    let margin_percent = u8::MAX;
+    let error = estimate_fee_with_margin(&resources, &block_context, &fee_type, margin_percent)
+        .unwrap_err();
+    assert_matches!(
+        error,
+        TransactionFeeError::FeeWithMarginOverflow { fee: error_fee, margin_percent: 255 }
+        if error_fee == fee
+    );
+}
+
+#[test]
+fn test_calculate_tx_fee_with_rounding() {
+    // Set a fractional step cost, so the total L1 gas usage is 10 + 0.2501 * 1800 = 460.18.
+    let mut block_context = BlockContext::create_for_account_testing();
+    let mut vm_resource_fee_cost = (*block_context.chain_info.vm_resource_fee_cost).clone();
+    vm_resource_fee_cost.insert(constants::N_STEPS_RESOURCE.to_string(), 0.2501);
+    block_context.chain_info_mut().vm_resource_fee_cost = Arc::new(vm_resource_fee_cost);
+    let mut resources = get_vm_resource_usage();
+    resources.0.insert(constants::GAS_USAGE.to_string(), 10);
+
+    let fee_type = FeeType::Eth;
+    let gas_price = block_context.block_info.gas_prices.get_by_fee_type(&fee_type);
+    let fee_with_rounding = |rounding_mode| {
+        calculate_tx_fee_with_rounding(&resources, &block_context, &fee_type, rounding_mode)
+            .unwrap()
+    };
+
+    assert_eq!(fee_with_rounding(RoundingMode::Ceil), Fee(461 * gas_price));
+    assert_eq!(fee_with_rounding(RoundingMode::Floor), Fee(460 * gas_price));
+    assert_eq!(fee_with_rounding(RoundingMode::Nearest), Fee(460 * gas_price));
+    // The default fee calculation preserves the protocol rounding.
+    assert_eq!(
+        calculate_tx_fee(&resources, &block_context, &fee_type).unwrap(),
+        fee_with_rounding(RoundingMode::Ceil)
+    );
+}
+
+#[rstest]
+#[case::fully_visited(HashSet::from([0, 1, 2, 3, 4, 5]), 6)]
+#[case::partially_visited(HashSet::from([1, 4]), 5)]
+#[case::untouched_segment(HashSet::from([0, 1]), 2)]
+#[case::nothing_visited(HashSet::new(), 0)]
+fn test_charged_bytecode_size(#[case] visited_pcs: HashSet<usize>, #[case] expected_size: usize) {
+    // Segments: [0, 2), [2, 3), [3, 6).
+    let segment_lengths = [2, 1, 3];
+    assert_eq!(charged_bytecode_size(&visited_pcs, &segment_lengths), expected_size);
+}
+
+#[rstest]
+fn test_minimal_l1_gas(mut block_context: BlockContext) {
+    let calldata_l1_gas_costs = CalldataL1GasCosts { per_calldata_felt: 16, per_signature_felt: 8 };
+    block_context.chain_info_mut().calldata_l1_gas_costs = calldata_l1_gas_costs;
+    let minimal_l1_gas_of = |calldata_length: usize, signature_length: usize| {
+        minimal_l1_gas(
+            TransactionType::InvokeFunction,
+            calldata_length,
+            signature_length,
+            &block_context,
+        )
+        .unwrap()
+    };
+
+    // Without calldata and signature, the floor is the pre-validation estimate.
+    let bare_minimal_l1_gas = minimal_l1_gas_of(0, 0);
+    assert!(bare_minimal_l1_gas > 0);
+    assert_eq!(
+        bare_minimal_l1_gas,
+        estimate_minimal_l1_gas(&block_context, &account_invoke_tx(invoke_tx_args! {})).unwrap()
+    );
+    assert_eq!(
+        minimal_l1_gas_of(100, 0) - bare_minimal_l1_gas,
+        (100 * calldata_l1_gas_costs.per_calldata_felt) as u128
+    );
+    assert_eq!(
+        minimal_l1_gas_of(0, 100) - bare_minimal_l1_gas,
+        (100 * calldata_l1_gas_costs.per_signature_felt) as u128
+    );
+    assert!(minimal_l1_gas_of(1000, 1000) > minimal_l1_gas_of(100, 100));
+
+    // The estimate accounts for the transaction's calldata and signature.
+    let tx = account_invoke_tx(invoke_tx_args! {
+        calldata: calldata![stark_felt!(1_u8), stark_felt!(2_u8), stark_felt!(3_u8)],
+        signature: TransactionSignature(vec![stark_felt!(1_u8); 2]),
+    });
+    assert_eq!(estimate_minimal_l1_gas(&block_context, &tx).unwrap(), minimal_l1_gas_of(3, 2));
+
+    // Deploying an account also updates a class hash.
+    assert!(
+        minimal_l1_gas(TransactionType::DeployAccount, 0, 0, &block_context).unwrap()
+            > bare_minimal_l1_gas
+    );
+}
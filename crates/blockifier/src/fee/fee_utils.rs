@@ -1,27 +1,32 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use starknet_api::hash::StarkFelt;
-use starknet_api::transaction::Fee;
+use starknet_api::transaction::{Fee, MessageToL1};
 
 use crate::abi::constants;
 use crate::block_context::BlockContext;
+use crate::fee::gas_usage::{calculate_tx_gas_usage, get_onchain_data_cost};
+use crate::state::cached_state::StateChangesCount;
 use crate::state::state_api::StateReader;
 use crate::transaction::errors::TransactionFeeError;
 use crate::transaction::objects::{
-    AccountTransactionContext, FeeType, HasRelatedFeeType, ResourcesMapping, TransactionFeeResult,
+    AccountTransactionContext, FeeResult, FeeType, FeeUnit, HasRelatedFeeType, ResourcesMapping,
+    TransactionExecutionResult, TransactionFeeResult,
 };
+use crate::transaction::transaction_types::TransactionType;
 
 #[cfg(test)]
 #[path = "fee_test.rs"]
 pub mod test;
 
-pub fn extract_l1_gas_and_vm_usage(resources: &ResourcesMapping) -> (usize, ResourcesMapping) {
+pub fn extract_l1_gas_and_vm_usage(
+    resources: &ResourcesMapping,
+) -> TransactionFeeResult<(usize, ResourcesMapping)> {
+    let l1_gas_usage = resources.l1_gas_usage()?;
     let mut vm_resource_usage = resources.0.clone();
-    let l1_gas_usage = vm_resource_usage
-        .remove(constants::GAS_USAGE)
-        .expect("`ResourcesMapping` does not have the key `l1_gas_usage`.");
+    vm_resource_usage.remove(constants::GAS_USAGE);
 
-    (l1_gas_usage, ResourcesMapping(vm_resource_usage))
+    Ok((l1_gas_usage, ResourcesMapping(vm_resource_usage)))
 }
 
 /// Calculates the L1 gas consumed when submitting the underlying Cairo program to SHARP.
@@ -31,7 +36,7 @@ pub fn calculate_l1_gas_by_vm_usage(
     block_context: &BlockContext,
     vm_resource_usage: &ResourcesMapping,
 ) -> TransactionFeeResult<f64> {
-    let vm_resource_fee_costs = &block_context.vm_resource_fee_cost;
+    let vm_resource_fee_costs = &block_context.chain_info.vm_resource_fee_cost;
     let vm_resource_names = HashSet::<&String>::from_iter(vm_resource_usage.0.keys());
     if !vm_resource_names.is_subset(&HashSet::from_iter(vm_resource_fee_costs.keys())) {
         return Err(TransactionFeeError::CairoResourcesNotContainedInFeeCosts);
@@ -48,6 +53,27 @@ pub fn calculate_l1_gas_by_vm_usage(
     Ok(vm_l1_gas_usage)
 }
 
+/// The rounding applied to the (fractional) total L1 gas usage.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RoundingMode {
+    /// Protocol behavior.
+    #[default]
+    Ceil,
+    Floor,
+    /// Round half away from zero.
+    Nearest,
+}
+
+impl RoundingMode {
+    pub fn round(&self, value: f64) -> f64 {
+        match self {
+            Self::Ceil => value.ceil(),
+            Self::Floor => value.floor(),
+            Self::Nearest => value.round(),
+        }
+    }
+}
+
 /// Computes and returns the total L1 gas consumption.
 /// We add the l1_gas_usage (which may include, for example, the direct cost of L2-to-L1 messages)
 /// to the gas consumed by Cairo VM resource.
@@ -55,11 +81,20 @@ pub fn calculate_tx_l1_gas_usage(
     resources: &ResourcesMapping,
     block_context: &BlockContext,
 ) -> TransactionFeeResult<u128> {
-    let (l1_gas_usage, vm_resources) = extract_l1_gas_and_vm_usage(resources);
+    calculate_tx_l1_gas_usage_with_rounding(resources, block_context, RoundingMode::Ceil)
+}
+
+/// Same as [calculate_tx_l1_gas_usage], with the given rounding of the total L1 gas usage.
+pub fn calculate_tx_l1_gas_usage_with_rounding(
+    resources: &ResourcesMapping,
+    block_context: &BlockContext,
+    rounding_mode: RoundingMode,
+) -> TransactionFeeResult<u128> {
+    let (l1_gas_usage, vm_resources) = extract_l1_gas_and_vm_usage(resources)?;
     let l1_gas_by_vm_usage = calculate_l1_gas_by_vm_usage(block_context, &vm_resources)?;
     let total_l1_gas_usage = l1_gas_usage as f64 + l1_gas_by_vm_usage;
 
-    Ok(total_l1_gas_usage.ceil() as u128)
+    Ok(rounding_mode.round(total_l1_gas_usage) as u128)
 }
 
 pub fn get_fee_by_l1_gas_usage(
@@ -67,7 +102,48 @@ pub fn get_fee_by_l1_gas_usage(
     l1_gas_usage: u128,
     fee_type: &FeeType,
 ) -> Fee {
-    Fee(l1_gas_usage * block_context.gas_prices.get_by_fee_type(fee_type))
+    Fee(l1_gas_usage * block_context.block_info.gas_prices.get_by_fee_type(fee_type))
+}
+
+/// Returns the minimal L1 gas an account transaction requires, independent of its execution: the
+/// constant overhead of its type (e.g., a bare transfer), plus the L1 gas the chain charges for its
+/// calldata and signature (see [crate::block_context::CalldataL1GasCosts]). The transaction's
+/// resource bounds (or max fee) may be validated against this floor.
+pub fn minimal_l1_gas(
+    tx_type: TransactionType,
+    calldata_length: usize,
+    signature_length: usize,
+    block_context: &BlockContext,
+) -> TransactionFeeResult<u128> {
+    // TODO(Dori, 1/8/2023): Give names to the constant VM step estimates and regression-test them.
+    let os_steps_for_type =
+        block_context.versioned_constants.os_resources.resources_for_tx_type(&tx_type).n_steps;
+    // We consider the following state changes: sender balance update (storage update) + nonce
+    // increment (contract modification) (we exclude the sequencer balance update and the ERC20
+    // contract modification since it occurs for every tx).
+    // DeployAccount also updates the address -> class hash mapping.
+    let n_class_hash_updates = match tx_type {
+        TransactionType::DeployAccount => 1,
+        TransactionType::Declare | TransactionType::InvokeFunction | TransactionType::L1Handler => {
+            0
+        }
+    };
+    let gas_cost = get_onchain_data_cost(StateChangesCount {
+        n_storage_updates: 1,
+        n_class_hash_updates,
+        n_compiled_class_hash_updates: 0,
+        n_modified_contracts: 1,
+    }) + block_context
+        .chain_info
+        .calldata_l1_gas_costs
+        .l1_gas(calldata_length, signature_length);
+
+    let resources = ResourcesMapping(HashMap::from([
+        (constants::GAS_USAGE.to_string(), gas_cost),
+        (constants::N_STEPS_RESOURCE.to_string(), os_steps_for_type),
+    ]));
+
+    calculate_tx_l1_gas_usage(&resources, block_context)
 }
 
 /// Calculates the fee that should be charged, given execution resources.
@@ -76,10 +152,69 @@ pub fn calculate_tx_fee(
     block_context: &BlockContext,
     fee_type: &FeeType,
 ) -> TransactionFeeResult<Fee> {
-    let l1_gas_usage = calculate_tx_l1_gas_usage(resources, block_context)?;
+    calculate_tx_fee_with_rounding(resources, block_context, fee_type, RoundingMode::Ceil)
+}
+
+/// Calculates the fee that should be charged, given execution resources, along with the unit and
+/// the L1 gas price it was calculated with.
+pub fn calculate_tx_fee_detailed(
+    resources: &ResourcesMapping,
+    block_context: &BlockContext,
+    fee_type: &FeeType,
+) -> TransactionFeeResult<FeeResult> {
+    Ok(FeeResult {
+        fee: calculate_tx_fee(resources, block_context, fee_type)?,
+        unit: FeeUnit::from(*fee_type),
+        gas_price: block_context.block_info.gas_prices.get_by_fee_type(fee_type),
+    })
+}
+
+/// Calculates the fee given execution resources, with the given rounding of the L1 gas usage.
+/// Intended for estimations; charged fees are always calculated with [RoundingMode::Ceil].
+pub fn calculate_tx_fee_with_rounding(
+    resources: &ResourcesMapping,
+    block_context: &BlockContext,
+    fee_type: &FeeType,
+    rounding_mode: RoundingMode,
+) -> TransactionFeeResult<Fee> {
+    let l1_gas_usage =
+        calculate_tx_l1_gas_usage_with_rounding(resources, block_context, rounding_mode)?;
     Ok(get_fee_by_l1_gas_usage(block_context, l1_gas_usage, fee_type))
 }
 
+/// Calculates the fee given execution resources, inflated by the given safety margin (e.g., 10
+/// for 110% of the fee). Intended for estimations, never for charging.
+pub fn estimate_fee_with_margin(
+    resources: &ResourcesMapping,
+    block_context: &BlockContext,
+    fee_type: &FeeType,
+    margin_percent: u8,
+) -> TransactionFeeResult<Fee> {
+    let fee = calculate_tx_fee(resources, block_context, fee_type)?;
+    // Computes `fee * margin_percent / 100` without overflowing on the intermediate product.
+    let margin = u128::from(margin_percent);
+    let fee_margin = (fee.0 / 100) * margin + (fee.0 % 100) * margin / 100;
+    let fee_with_margin = fee
+        .0
+        .checked_add(fee_margin)
+        .ok_or(TransactionFeeError::FeeWithMarginOverflow { fee, margin_percent })?;
+
+    Ok(Fee(fee_with_margin))
+}
+
+/// Calculates the fees that should be charged for a batch of execution resources, in ETH (the fee
+/// token of pre-V3 transactions); fails on the first resources mapping whose fee cannot be
+/// computed.
+pub fn calculate_fees(
+    resources: &[ResourcesMapping],
+    block_context: &BlockContext,
+) -> TransactionExecutionResult<Vec<Fee>> {
+    Ok(resources
+        .iter()
+        .map(|resources| calculate_tx_fee(resources, block_context, &FeeType::Eth))
+        .collect::<TransactionFeeResult<_>>()?)
+}
+
 /// Returns the current fee balance and a boolean indicating whether the balance covers the fee.
 pub fn get_balance_and_if_covers_fee(
     state: &mut dyn StateReader,
@@ -100,6 +235,20 @@ pub fn get_balance_and_if_covers_fee(
     ))
 }
 
+/// Verifies that the fee the sender committed to pay covers the given minimal fee.
+/// Meant to be checked before execution, to cheaply reject underfunded transactions.
+pub fn check_fee_bounds(
+    account_tx_context: &AccountTransactionContext,
+    min_fee: Fee,
+) -> TransactionFeeResult<()> {
+    let max_fee = account_tx_context.committed_fee()?;
+    if max_fee < min_fee {
+        return Err(TransactionFeeError::MaxFeeTooLow { min_fee, max_fee });
+    }
+
+    Ok(())
+}
+
 /// Verifies that, given the current state, the account can cover the resource upper bounds.
 /// Error may indicate insufficient balance, or some other error.
 pub fn verify_can_pay_committed_bounds(
@@ -107,15 +256,7 @@ pub fn verify_can_pay_committed_bounds(
     account_tx_context: &AccountTransactionContext,
     block_context: &BlockContext,
 ) -> TransactionFeeResult<()> {
-    let committed_fee = match account_tx_context {
-        AccountTransactionContext::Current(context) => {
-            let l1_bounds = context.l1_resource_bounds()?;
-            // Sender will not be charged by `max_price_per_unit`, but this check should not depend
-            // on the current gas price.
-            Fee(l1_bounds.max_amount as u128 * l1_bounds.max_price_per_unit)
-        }
-        AccountTransactionContext::Deprecated(context) => context.max_fee,
-    };
+    let committed_fee = account_tx_context.committed_fee()?;
     let (balance_low, balance_high, can_pay) =
         get_balance_and_if_covers_fee(state, account_tx_context, block_context, committed_fee)?;
     if can_pay {
@@ -141,3 +282,29 @@ pub fn verify_can_pay_committed_bounds(
         })
     }
 }
+
+/// Returns the total length of the bytecode segments in which at least one PC was visited; only
+/// these segments are charged for. The segments are consecutive, starting at PC 0.
+pub fn charged_bytecode_size(visited_pcs: &HashSet<usize>, segment_lengths: &[usize]) -> usize {
+    let mut segment_start = 0;
+    let mut charged_size = 0;
+    for &segment_length in segment_lengths {
+        let segment_end = segment_start + segment_length;
+        if (segment_start..segment_end).any(|pc| visited_pcs.contains(&pc)) {
+            charged_size += segment_length;
+        }
+        segment_start = segment_end;
+    }
+
+    charged_size
+}
+
+/// Returns the L1 gas consumed by sending the given L2-to-L1 messages: a base cost per message
+/// (its header in the messages segment, the L1 message counter update and log emission) plus a
+/// cost per payload word. This is the messages share of the `GAS_USAGE` resource.
+pub fn l1_gas_for_messages(messages: &[MessageToL1]) -> u64 {
+    let l2_to_l1_payloads_length: Vec<usize> =
+        messages.iter().map(|message| message.payload.0.len()).collect();
+    // An empty state diff has no onchain data cost.
+    calculate_tx_gas_usage(&l2_to_l1_payloads_length, StateChangesCount::default(), None) as u64
+}
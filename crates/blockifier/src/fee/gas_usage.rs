@@ -1,16 +1,13 @@
-use std::collections::HashMap;
-
 use starknet_api::transaction::Fee;
 
-use super::fee_utils::{calculate_tx_l1_gas_usage, get_fee_by_l1_gas_usage};
+use super::fee_utils::{get_fee_by_l1_gas_usage, minimal_l1_gas};
 use crate::abi::constants;
 use crate::block_context::BlockContext;
 use crate::fee::eth_gas_constants;
-use crate::fee::os_resources::OS_RESOURCES;
 use crate::state::cached_state::StateChangesCount;
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::objects::{
-    HasRelatedFeeType, ResourcesMapping, TransactionExecutionResult, TransactionPreValidationResult,
+    HasRelatedFeeType, TransactionExecutionResult, TransactionPreValidationResult,
 };
 
 #[cfg(test)]
@@ -161,43 +158,14 @@ fn get_event_emission_cost(n_topics: usize, data_length: usize) -> usize {
         + data_length * eth_gas_constants::GAS_PER_LOG_DATA_WORD
 }
 
-/// Return an estimated lower bound for the L1 gas on an account transaction.
+/// Return an estimated lower bound for the L1 gas on an account transaction: the constant overhead
+/// of its type, plus the cost of its calldata and signature (see [minimal_l1_gas]).
 pub fn estimate_minimal_l1_gas(
     block_context: &BlockContext,
     tx: &AccountTransaction,
 ) -> TransactionPreValidationResult<u128> {
-    // TODO(Dori, 1/8/2023): Give names to the constant VM step estimates and regression-test them.
-    let os_steps_for_type = OS_RESOURCES.resources_for_tx_type(&tx.tx_type()).n_steps;
-    let gas_cost: usize = match tx {
-        // We consider the following state changes: sender balance update (storage update) + nonce
-        // increment (contract modification) (we exclude the sequencer balance update and the ERC20
-        // contract modification since it occurs for every tx).
-        AccountTransaction::Declare(_) => get_onchain_data_cost(StateChangesCount {
-            n_storage_updates: 1,
-            n_class_hash_updates: 0,
-            n_compiled_class_hash_updates: 0,
-            n_modified_contracts: 1,
-        }),
-        AccountTransaction::Invoke(_) => get_onchain_data_cost(StateChangesCount {
-            n_storage_updates: 1,
-            n_class_hash_updates: 0,
-            n_compiled_class_hash_updates: 0,
-            n_modified_contracts: 1,
-        }),
-        // DeployAccount also updates the address -> class hash mapping.
-        AccountTransaction::DeployAccount(_) => get_onchain_data_cost(StateChangesCount {
-            n_storage_updates: 1,
-            n_class_hash_updates: 1,
-            n_compiled_class_hash_updates: 0,
-            n_modified_contracts: 1,
-        }),
-    };
-    let resources = ResourcesMapping(HashMap::from([
-        (constants::GAS_USAGE.to_string(), gas_cost),
-        (constants::N_STEPS_RESOURCE.to_string(), os_steps_for_type),
-    ]));
-
-    Ok(calculate_tx_l1_gas_usage(&resources, block_context)?)
+    let signature_length = tx.get_account_tx_context().signature().0.len();
+    Ok(minimal_l1_gas(tx.tx_type(), tx.calldata_length(), signature_length, block_context)?)
 }
 
 pub fn estimate_minimal_fee(
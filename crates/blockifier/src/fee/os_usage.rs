@@ -5,7 +5,6 @@ use serde::Deserialize;
 
 use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
 use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
-use crate::fee::os_resources::OS_RESOURCES;
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::transaction_types::TransactionType;
 
@@ -29,20 +28,29 @@ impl OsResources {
             .get(tx_type)
             .unwrap_or_else(|| panic!("should contain transaction type '{tx_type:?}'."))
     }
+
+    /// Returns the OS resources of a single invocation of the given syscall; for `Keccak`, of a
+    /// single round.
+    pub fn resources_for_syscall(
+        &self,
+        syscall_selector: &DeprecatedSyscallSelector,
+    ) -> &VmExecutionResources {
+        self.execute_syscalls.get(syscall_selector).unwrap_or_else(|| {
+            panic!("OS resources of syscall '{syscall_selector:?}' are unknown.")
+        })
+    }
 }
 
 /// Calculates the additional resources needed for the OS to run the given syscalls;
 /// i.e., the resources of the Starknet OS function `execute_syscalls`.
 pub fn get_additional_os_resources(
+    os_resources: &OsResources,
     syscall_counter: &SyscallCounter,
     tx_type: TransactionType,
 ) -> Result<VmExecutionResources, TransactionExecutionError> {
     let mut os_additional_vm_resources = VmExecutionResources::default();
     for (syscall_selector, count) in syscall_counter {
-        let syscall_resources =
-            OS_RESOURCES.execute_syscalls.get(syscall_selector).unwrap_or_else(|| {
-                panic!("OS resources of syscall '{syscall_selector:?}' are unknown.")
-            });
+        let syscall_resources = os_resources.resources_for_syscall(syscall_selector);
         os_additional_vm_resources += &(syscall_resources * *count);
     }
 
@@ -50,6 +58,6 @@ pub fn get_additional_os_resources(
     // i.e., the resources of the Starknet OS function `execute_transactions_inner`.
     // Also adds the resources needed for the fee transfer execution, performed in the end·
     // of every transaction.
-    let os_resources = OS_RESOURCES.resources_for_tx_type(&tx_type);
-    Ok(&os_additional_vm_resources + os_resources)
+    let tx_type_resources = os_resources.resources_for_tx_type(&tx_type);
+    Ok(&os_additional_vm_resources + tx_type_resources)
 }
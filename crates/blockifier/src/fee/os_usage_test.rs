@@ -4,7 +4,7 @@ use cairo_vm::vm::runners::builtin_runner;
 use strum::IntoEnumIterator;
 
 use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
-use crate::fee::os_usage::OS_RESOURCES;
+use crate::fee::os_resources::OS_RESOURCES;
 use crate::transaction::transaction_types::TransactionType;
 
 #[test]
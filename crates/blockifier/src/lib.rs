@@ -3,8 +3,11 @@ pub mod block_context;
 pub mod block_execution;
 pub mod execution;
 pub mod fee;
+#[cfg(feature = "scale-codec")]
+pub mod scale_codecs;
 pub mod state;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
 pub mod transaction;
 pub mod utils;
+pub mod versioned_constants;
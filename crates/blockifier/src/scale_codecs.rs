@@ -0,0 +1,98 @@
+use parity_scale_codec::{Decode, Encode, EncodeAsRef, Error, Input, Output};
+use starknet_api::hash::StarkFelt;
+
+use crate::transaction::objects::ResourcesMapping;
+
+#[cfg(test)]
+#[path = "scale_codecs_test.rs"]
+mod test;
+
+/// A SCALE codec for [StarkFelt], over its 32-byte (big-endian) representation.
+/// Enables encoding structs with felt fields, via `#[codec(encoded_as = "StarkFeltCodec")]`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StarkFeltCodec(pub StarkFelt);
+
+impl Encode for StarkFeltCodec {
+    fn size_hint(&self) -> usize {
+        32
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(self.0.bytes());
+    }
+}
+
+impl Decode for StarkFeltCodec {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let mut bytes = [0_u8; 32];
+        input.read(&mut bytes)?;
+        let felt = StarkFelt::new(bytes).map_err(|_| Error::from("Felt out of range."))?;
+
+        Ok(Self(felt))
+    }
+}
+
+impl From<StarkFelt> for StarkFeltCodec {
+    fn from(felt: StarkFelt) -> Self {
+        Self(felt)
+    }
+}
+
+impl From<StarkFeltCodec> for StarkFelt {
+    fn from(codec: StarkFeltCodec) -> Self {
+        codec.0
+    }
+}
+
+/// Encodes a borrowed [StarkFelt]; see [StarkFeltCodec].
+pub struct StarkFeltCodecRef<'a>(pub &'a StarkFelt);
+
+impl Encode for StarkFeltCodecRef<'_> {
+    fn size_hint(&self) -> usize {
+        32
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(self.0.bytes());
+    }
+}
+
+impl<'a> From<&'a StarkFelt> for StarkFeltCodecRef<'a> {
+    fn from(felt: &'a StarkFelt) -> Self {
+        Self(felt)
+    }
+}
+
+impl<'a> EncodeAsRef<'a, StarkFelt> for StarkFeltCodec {
+    type RefType = StarkFeltCodecRef<'a>;
+}
+
+/// Encodes the entries in key order, so that equal mappings have equal encodings; usages are
+/// encoded as `u64`. Decoding rejects entries out of key order, or with duplicate keys, keeping the
+/// encoding canonical.
+impl Encode for ResourcesMapping {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        let entries: Vec<(String, u64)> =
+            self.sorted().into_iter().map(|(key, usage)| (key, usage as u64)).collect();
+        entries.encode_to(dest);
+    }
+}
+
+impl Decode for ResourcesMapping {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let entries = Vec::<(String, u64)>::decode(input)?;
+        if entries.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+            return Err(Error::from("Resources are not strictly sorted by key."));
+        }
+
+        entries
+            .into_iter()
+            .map(|(key, usage)| {
+                let usage =
+                    usize::try_from(usage).map_err(|_| Error::from("Usage out of range."))?;
+                Ok((key, usage))
+            })
+            .collect::<Result<_, Error>>()
+            .map(Self)
+    }
+}
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use parity_scale_codec::{Decode, Encode};
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+
+use crate::scale_codecs::{StarkFeltCodec, StarkFeltCodecRef};
+use crate::transaction::objects::ResourcesMapping;
+
+#[test]
+fn test_stark_felt_codec_round_trip() {
+    for felt in [stark_felt!("0x1234abcd"), StarkFelt::default()] {
+        let encoded = StarkFeltCodec(felt).encode();
+        assert_eq!(encoded, felt.bytes());
+        assert_eq!(StarkFeltCodecRef(&felt).encode(), encoded);
+
+        let decoded = StarkFeltCodec::decode(&mut encoded.as_slice()).unwrap();
+        assert_eq!(StarkFelt::from(decoded), felt);
+    }
+}
+
+#[test]
+fn test_stark_felt_codec_decode_errors() {
+    // Too short.
+    assert!(StarkFeltCodec::decode(&mut [0_u8; 31].as_slice()).is_err());
+    // Out of the field range.
+    assert!(StarkFeltCodec::decode(&mut [u8::MAX; 32].as_slice()).is_err());
+}
+
+#[test]
+fn test_resources_mapping_canonical_encoding() {
+    let entries = [("n_steps", 1000), ("l1_gas_usage", 17), ("range_check_builtin", 24)];
+    let resources =
+        ResourcesMapping(HashMap::from(entries.map(|(key, usage)| (key.to_string(), usage))));
+    let mut reversed_entries = HashMap::new();
+    for (key, usage) in entries.into_iter().rev() {
+        reversed_entries.insert(key.to_string(), usage);
+    }
+    let reversed_resources = ResourcesMapping(reversed_entries);
+
+    let encoded = resources.encode();
+    assert_eq!(reversed_resources.encode(), encoded);
+    assert_eq!(
+        resources.sorted().into_keys().collect::<Vec<_>>(),
+        vec!["l1_gas_usage", "n_steps", "range_check_builtin"]
+    );
+    assert_eq!(ResourcesMapping::decode(&mut encoded.as_slice()).unwrap(), resources);
+}
+
+#[test]
+fn test_resources_mapping_decode_rejects_non_canonical_entries() {
+    let entries = |keys: &[&str]| -> Vec<(String, u64)> {
+        keys.iter().map(|key| (key.to_string(), 1)).collect()
+    };
+    let sorted = entries(&["l1_gas_usage", "n_steps"]).encode();
+    let resources = ResourcesMapping::decode(&mut sorted.as_slice()).unwrap();
+    assert_eq!(resources.encode(), sorted);
+
+    for keys in [["n_steps", "l1_gas_usage"], ["n_steps", "n_steps"]] {
+        let encoded = entries(&keys).encode();
+        assert!(ResourcesMapping::decode(&mut encoded.as_slice()).is_err());
+    }
+}
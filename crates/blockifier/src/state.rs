@@ -1,3 +1,6 @@
+#[cfg(feature = "async")]
+pub mod async_state_api;
 pub mod cached_state;
 pub mod errors;
 pub mod state_api;
+pub mod timed_sized_cache;
@@ -0,0 +1,87 @@
+use std::future::{ready, Future};
+use std::pin::Pin;
+
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::execution::contract_class::ContractClass;
+use crate::state::state_api::{StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "async_state_api_test.rs"]
+mod test;
+
+pub type StateFuture<'a, T> = Pin<Box<dyn Future<Output = StateResult<T>> + Send + 'a>>;
+
+/// A non-blocking counterpart of [StateReader], for readers backed by asynchronous storage.
+///
+/// Every synchronous `StateReader` is also an `AsyncStateReader`, whose futures are immediately
+/// ready.
+pub trait AsyncStateReader: Send {
+    /// Returns the storage value under the given key in the given contract instance.
+    /// Default: 0 for an uninitialized contract address.
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateFuture<'_, StarkFelt>;
+
+    /// Returns the nonce of the given contract instance.
+    /// Default: 0 for an uninitialized contract address.
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateFuture<'_, Nonce>;
+
+    /// Returns the class hash of the contract class at the given contract instance.
+    /// Default: 0 (uninitialized class hash) for an uninitialized contract address.
+    fn get_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+    ) -> StateFuture<'_, ClassHash>;
+
+    /// Returns the contract class of the given class hash.
+    fn get_compiled_contract_class(
+        &mut self,
+        class_hash: ClassHash,
+    ) -> StateFuture<'_, ContractClass>;
+
+    /// Returns the compiled class hash of the given class hash.
+    fn get_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+    ) -> StateFuture<'_, CompiledClassHash>;
+}
+
+impl<S: StateReader + Send> AsyncStateReader for S {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateFuture<'_, StarkFelt> {
+        Box::pin(ready(StateReader::get_storage_at(self, contract_address, key)))
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateFuture<'_, Nonce> {
+        Box::pin(ready(StateReader::get_nonce_at(self, contract_address)))
+    }
+
+    fn get_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+    ) -> StateFuture<'_, ClassHash> {
+        Box::pin(ready(StateReader::get_class_hash_at(self, contract_address)))
+    }
+
+    fn get_compiled_contract_class(
+        &mut self,
+        class_hash: ClassHash,
+    ) -> StateFuture<'_, ContractClass> {
+        Box::pin(ready(StateReader::get_compiled_contract_class(self, class_hash)))
+    }
+
+    fn get_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+    ) -> StateFuture<'_, CompiledClassHash> {
+        Box::pin(ready(StateReader::get_compiled_class_hash(self, class_hash)))
+    }
+}
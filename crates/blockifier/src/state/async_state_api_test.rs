@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::execution::contract_class::ContractClass;
+// The async reader trait is not imported, since its methods clash with `StateReader`'s.
+use crate::state::async_state_api::{self, StateFuture};
+use crate::state::cached_state::CachedState;
+use crate::state::errors::StateError;
+use crate::state::state_api::StateReader;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::{get_test_contract_class, TEST_CLASS_HASH};
+
+struct NoopWaker;
+
+impl Wake for NoopWaker {
+    fn wake(self: Arc<Self>) {}
+}
+
+/// Polls the given future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut context = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+/// A mock asynchronous reader, counting its reads.
+#[derive(Default)]
+struct MockAsyncStateReader {
+    state: DictStateReader,
+    n_reads: usize,
+}
+
+impl MockAsyncStateReader {
+    fn read<T: Send + 'static>(&mut self, result: Result<T, StateError>) -> StateFuture<'_, T> {
+        self.n_reads += 1;
+        Box::pin(async move {
+            // Yield once, as a database-backed reader would.
+            let mut yielded = false;
+            std::future::poll_fn(|context| {
+                if yielded {
+                    return Poll::Ready(());
+                }
+                yielded = true;
+                context.waker().wake_by_ref();
+                Poll::Pending
+            })
+            .await;
+            result
+        })
+    }
+}
+
+impl async_state_api::AsyncStateReader for MockAsyncStateReader {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateFuture<'_, StarkFelt> {
+        let result = self.state.get_storage_at(contract_address, key);
+        self.read(result)
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateFuture<'_, Nonce> {
+        let result = self.state.get_nonce_at(contract_address);
+        self.read(result)
+    }
+
+    fn get_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+    ) -> StateFuture<'_, ClassHash> {
+        let result = self.state.get_class_hash_at(contract_address);
+        self.read(result)
+    }
+
+    fn get_compiled_contract_class(
+        &mut self,
+        class_hash: ClassHash,
+    ) -> StateFuture<'_, ContractClass> {
+        let result = self.state.get_compiled_contract_class(class_hash);
+        self.read(result)
+    }
+
+    fn get_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+    ) -> StateFuture<'_, CompiledClassHash> {
+        let result = self.state.get_compiled_class_hash(class_hash);
+        self.read(result)
+    }
+}
+
+#[test]
+fn test_sync_reader_as_async_reader() {
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let value = stark_felt!("0x5");
+    let mut reader = DictStateReader {
+        storage_view: HashMap::from([((contract_address, key), value)]),
+        ..Default::default()
+    };
+
+    let future =
+        async_state_api::AsyncStateReader::get_storage_at(&mut reader, contract_address, key);
+    assert_eq!(block_on(future).unwrap(), value);
+}
+
+#[test]
+fn test_prefetch_async() {
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let value = stark_felt!("0x5");
+    let nonce = Nonce(stark_felt!(3_u8));
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let mut async_reader = MockAsyncStateReader {
+        state: DictStateReader {
+            storage_view: HashMap::from([((contract_address, key), value)]),
+            address_to_nonce: HashMap::from([(contract_address, nonce)]),
+            address_to_class_hash: HashMap::from([(contract_address, class_hash)]),
+            class_hash_to_class: HashMap::from([(class_hash, get_test_contract_class())]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    // The (empty) synchronous reader must not be accessed for prefetched values.
+    let mut state = CachedState::from(DictStateReader::default());
+
+    block_on(state.prefetch_async(
+        &mut async_reader,
+        &[contract_address],
+        &[(contract_address, key)],
+        &[class_hash],
+    ))
+    .unwrap();
+    assert_eq!(async_reader.n_reads, 4);
+
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), value);
+    assert_eq!(state.get_nonce_at(contract_address).unwrap(), nonce);
+    assert_eq!(state.get_class_hash_at(contract_address).unwrap(), class_hash);
+    assert_eq!(state.get_compiled_contract_class(class_hash).unwrap(), get_test_contract_class());
+
+    // Prefetching cached values does not read them again.
+    block_on(state.prefetch_async(
+        &mut async_reader,
+        &[contract_address],
+        &[(contract_address, key)],
+        &[class_hash],
+    ))
+    .unwrap();
+    assert_eq!(async_reader.n_reads, 4);
+}
@@ -4,9 +4,10 @@ use std::sync::{Arc, Mutex, MutexGuard};
 use cached::{Cached, SizedCache};
 use derive_more::IntoIterator;
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
-use starknet_api::state::StorageKey;
+use starknet_api::state::{ContractClass as SierraContractClass, StateDiff, StorageKey};
 
 use crate::abi::abi_utils::get_fee_token_var_address;
 use crate::execution::contract_class::ContractClass;
@@ -32,6 +33,7 @@ pub struct CachedState<S: StateReader> {
     class_hash_to_class: ContractClassMapping,
     // Invariant: managed by CachedState.
     global_class_hash_to_class: GlobalContractCache,
+    cache_eviction_hook: CacheEvictionHook,
 }
 
 impl<S: StateReader> CachedState<S> {
@@ -41,6 +43,23 @@ impl<S: StateReader> CachedState<S> {
             cache: StateCache::default(),
             class_hash_to_class: HashMap::default(),
             global_class_hash_to_class,
+            cache_eviction_hook: CacheEvictionHook::default(),
+        }
+    }
+
+    /// Sets a callback invoked with the class hash of every entry that this state's insertions
+    /// evict from the (bounded) global contract cache; replaces any previously set callback.
+    pub fn on_cache_evict(&mut self, callback: CacheEvictionCallback) {
+        self.cache_eviction_hook = CacheEvictionHook(Some(callback));
+    }
+
+    fn insert_into_global_cache(&mut self, class_hash: ClassHash, contract_class: ContractClass) {
+        let evicted_class_hash =
+            self.global_class_hash_to_class.insert_and_get_evicted(class_hash, contract_class);
+        if let (Some(evicted_class_hash), Some(callback)) =
+            (evicted_class_hash, &mut self.cache_eviction_hook.0)
+        {
+            callback(evicted_class_hash);
         }
     }
 
@@ -52,6 +71,25 @@ impl<S: StateReader> CachedState<S> {
         CachedState::new(MutRefState::new(state), global_class_hash_to_class)
     }
 
+    /// Runs `f` against a transactional instance of this state; its changes are committed if `f`
+    /// succeeds, and discarded otherwise.
+    pub fn with_transaction<F, T>(&mut self, f: F) -> StateResult<T>
+    where
+        F: FnOnce(&mut TransactionalState<'_, S>) -> StateResult<T>,
+    {
+        let mut transactional_state = CachedState::create_transactional(self);
+        match f(&mut transactional_state) {
+            Ok(value) => {
+                transactional_state.commit();
+                Ok(value)
+            }
+            Err(error) => {
+                transactional_state.abort();
+                Err(error)
+            }
+        }
+    }
+
     /// Returns the storage changes done through this state.
     /// For each contract instance (address) we have three attributes: (class hash, nonce, storage
     /// root); the state updates correspond to them.
@@ -103,11 +141,16 @@ impl<S: StateReader> CachedState<S> {
         })
     }
 
+    /// Returns the number of storage slots modified in this state, w.r.t. the underlying state.
+    pub fn n_storage_updates(&self) -> usize {
+        self.cache.get_storage_updates().len()
+    }
+
     /// Drains contract-class cache collected during execution and updates the global cache.
     pub fn move_classes_to_global_cache(&mut self) {
         let contract_class_updates: Vec<_> = self.class_hash_to_class.drain().collect();
         for (key, value) in contract_class_updates {
-            self.global_class_hash_to_class().cache_set(key, value);
+            self.insert_into_global_cache(key, value);
         }
     }
 
@@ -126,6 +169,35 @@ impl<S: StateReader> CachedState<S> {
         self.cache.compiled_class_hash_writes.extend(cache_updates.compiled_class_hash_writes);
     }
 
+    /// Exports the writes done through this state, so that they can be persisted and later
+    /// imported into a fresh state over the same reader.
+    pub fn export_snapshot(&self) -> StateSnapshot {
+        StateSnapshot {
+            nonce_writes: sorted_entries(&self.cache.nonce_writes),
+            class_hash_writes: sorted_entries(&self.cache.class_hash_writes),
+            storage_writes: sorted_entries(&self.cache.storage_writes)
+                .into_iter()
+                .map(|((contract_address, key), value)| (contract_address, key, value))
+                .collect(),
+            compiled_class_hash_writes: sorted_entries(&self.cache.compiled_class_hash_writes),
+        }
+    }
+
+    /// Applies the writes of an exported snapshot on top of this state.
+    pub fn import_snapshot(&mut self, snapshot: StateSnapshot) {
+        self.update_cache(StateCache {
+            nonce_writes: snapshot.nonce_writes.into_iter().collect(),
+            class_hash_writes: snapshot.class_hash_writes.into_iter().collect(),
+            storage_writes: snapshot
+                .storage_writes
+                .into_iter()
+                .map(|(contract_address, key, value)| ((contract_address, key), value))
+                .collect(),
+            compiled_class_hash_writes: snapshot.compiled_class_hash_writes.into_iter().collect(),
+            ..Default::default()
+        });
+    }
+
     pub fn update_contract_class_caches(
         &mut self,
         local_contract_cache_updates: ContractClassMapping,
@@ -195,6 +267,98 @@ impl<S: StateReader> CachedState<S> {
             address_to_nonce: IndexMap::from_iter(nonces),
         }
     }
+
+    /// Converts the accumulated changes into a starknet_api `StateDiff`.
+    /// Class hash updates of previously undeployed addresses are reported as deployed contracts,
+    /// and the rest as replaced classes.
+    /// Note: declared classes are reported with a default (Sierra) contract class, since only
+    /// their compiled form is held by the state; deprecated declared classes are not reported.
+    pub fn to_starknet_api_state_diff(&mut self) -> StateDiff {
+        let commitment_state_diff = self.to_state_diff();
+
+        let (deployed_contracts, replaced_classes) = commitment_state_diff
+            .address_to_class_hash
+            .into_iter()
+            .partition(|(contract_address, _class_hash)| {
+                self.cache
+                    .class_hash_initial_values
+                    .get(contract_address)
+                    .copied()
+                    .unwrap_or_default()
+                    == ClassHash::default()
+            });
+        let declared_classes = commitment_state_diff
+            .class_hash_to_compiled_class_hash
+            .into_iter()
+            .map(|(class_hash, compiled_class_hash)| {
+                (class_hash, (compiled_class_hash, SierraContractClass::default()))
+            })
+            .collect();
+
+        StateDiff {
+            deployed_contracts,
+            storage_diffs: commitment_state_diff.storage_updates,
+            declared_classes,
+            deprecated_declared_classes: IndexMap::new(),
+            nonces: commitment_state_diff.address_to_nonce,
+            replaced_classes,
+        }
+    }
+
+    /// Loads the given contract classes into the cache, so that subsequent reads of these classes
+    /// do not access the underlying reader. Classes already cached are not re-read.
+    pub fn prefetch_classes(&mut self, class_hashes: &[ClassHash]) -> StateResult<()> {
+        let unique_class_hashes: HashSet<ClassHash> = class_hashes.iter().copied().collect();
+        for class_hash in unique_class_hashes {
+            self.get_compiled_contract_class(class_hash)?;
+        }
+
+        Ok(())
+    }
+
+    /// Loads the given nonces, class hashes, storage entries and classes into the cache, using the
+    /// given asynchronous reader; subsequent reads of these values do not access the underlying
+    /// (synchronous) reader. Values already in the cache are not re-read.
+    /// The given reader must reflect the same state as the underlying reader.
+    // The async reader trait is not imported, as every (sync) reader implements it as well.
+    #[cfg(feature = "async")]
+    pub async fn prefetch_async<R: crate::state::async_state_api::AsyncStateReader + ?Sized>(
+        &mut self,
+        reader: &mut R,
+        contract_addresses: &[ContractAddress],
+        storage_entries: &[StorageEntry],
+        class_hashes: &[ClassHash],
+    ) -> StateResult<()> {
+        for &contract_address in contract_addresses {
+            if self.cache.get_nonce_at(contract_address).is_none() {
+                let nonce = reader.get_nonce_at(contract_address).await?;
+                self.cache.set_nonce_initial_value(contract_address, nonce);
+            }
+            if self.cache.get_class_hash_at(contract_address).is_none() {
+                let class_hash = reader.get_class_hash_at(contract_address).await?;
+                self.cache.set_class_hash_initial_value(contract_address, class_hash);
+            }
+        }
+
+        for &(contract_address, key) in storage_entries {
+            if self.cache.get_storage_at(contract_address, key).is_none() {
+                let storage_value = reader.get_storage_at(contract_address, key).await?;
+                self.cache.set_storage_initial_value(contract_address, key, storage_value);
+            }
+        }
+
+        for &class_hash in class_hashes {
+            if self.class_hash_to_class.contains_key(&class_hash)
+                || self.global_class_hash_to_class().cache_get(&class_hash).is_some()
+            {
+                continue;
+            }
+            let contract_class = reader.get_compiled_contract_class(class_hash).await?;
+            self.class_hash_to_class.insert(class_hash, contract_class);
+        }
+
+        Ok(())
+    }
 }
 
 impl<S: StateReader> From<S> for CachedState<S> {
@@ -246,30 +410,23 @@ impl<S: StateReader> StateReader for CachedState<S> {
         Ok(*class_hash)
     }
 
-    #[allow(clippy::map_entry)]
-    // Clippy solution don't work because it required two mutable ref to self
-    // Could probably be solved with interior mutability
+    /// Consults the local cache, then the global cache and finally the underlying reader; a class
+    /// read from the reader is also inserted into the global cache, so that other states sharing
+    /// it skip the read.
     fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
-        if !self.class_hash_to_class.contains_key(&class_hash) {
-            let contract_class = self.global_class_hash_to_class().cache_get(&class_hash).cloned();
-
-            match contract_class {
-                Some(contract_class_from_global_cache) => {
-                    self.class_hash_to_class.insert(class_hash, contract_class_from_global_cache);
-                }
-                None => {
-                    let contract_class_from_db =
-                        self.state.get_compiled_contract_class(class_hash)?;
-                    self.class_hash_to_class.insert(class_hash, contract_class_from_db);
-                }
-            }
+        if let Some(contract_class) = self.class_hash_to_class.get(&class_hash) {
+            return Ok(contract_class.clone());
         }
 
-        let contract_class = self
-            .class_hash_to_class
-            .get(&class_hash)
-            .cloned()
-            .expect("The class hash must appear in the cache.");
+        let contract_class = match self.global_class_hash_to_class.get(&class_hash) {
+            Some(contract_class_from_global_cache) => contract_class_from_global_cache,
+            None => {
+                let contract_class_from_db = self.state.get_compiled_contract_class(class_hash)?;
+                self.insert_into_global_cache(class_hash, contract_class_from_db.clone());
+                contract_class_from_db
+            }
+        };
+        self.class_hash_to_class.insert(class_hash, contract_class.clone());
 
         Ok(contract_class)
     }
@@ -340,6 +497,10 @@ impl<S: StateReader> State for CachedState<S> {
         self.cache.set_compiled_class_hash_write(class_hash, compiled_class_hash);
         Ok(())
     }
+
+    fn global_contract_cache(&self) -> GlobalContractCache {
+        self.global_class_hash_to_class.clone()
+    }
 }
 
 #[cfg(any(feature = "testing", test))]
@@ -350,6 +511,7 @@ impl Default for CachedState<crate::test_utils::dict_state_reader::DictStateRead
             cache: Default::default(),
             class_hash_to_class: Default::default(),
             global_class_hash_to_class: Default::default(),
+            cache_eviction_hook: Default::default(),
         }
     }
 }
@@ -575,6 +737,10 @@ impl<'a, S: State + ?Sized> State for MutRefState<'a, S> {
     ) -> StateResult<()> {
         self.0.set_compiled_class_hash(class_hash, compiled_class_hash)
     }
+
+    fn global_contract_cache(&self) -> GlobalContractCache {
+        self.0.global_contract_cache()
+    }
 }
 
 pub type TransactionalState<'a, S> = CachedState<MutRefState<'a, CachedState<S>>>;
@@ -611,6 +777,47 @@ impl<'a, S: StateReader> TransactionalState<'a, S> {
     pub fn abort(self) {}
 }
 
+/// A state nested over a state of any type, e.g., a `dyn State`; as with [TransactionalState], its
+/// changes are either committed to the parent state, or discarded.
+pub type NestedState<'a, S> = CachedState<MutRefState<'a, S>>;
+
+impl<'a, S: State + ?Sized> NestedState<'a, S> {
+    /// Creates a state nested over the given state, sharing its global contract class cache.
+    pub fn create_nested(state: &'a mut S) -> Self {
+        let global_contract_cache = state.global_contract_cache();
+        CachedState::new(MutRefState::new(state), global_contract_cache)
+    }
+
+    /// Commits the changes in the child state to its parent, through the [State] API.
+    pub fn commit_to_parent(self) -> StateResult<()> {
+        let parent = self.state.0;
+        let cache = self.cache;
+        for ((contract_address, key), value) in cache.storage_writes {
+            parent.set_storage_at(contract_address, key, value)?;
+        }
+        // Nonces only change by increments.
+        for (contract_address, nonce) in cache.nonce_writes {
+            let parent_nonce = parent.get_nonce_at(contract_address)?;
+            let n_increments =
+                usize::try_from(nonce.0)?.saturating_sub(usize::try_from(parent_nonce.0)?);
+            for _ in 0..n_increments {
+                parent.increment_nonce(contract_address)?;
+            }
+        }
+        for (contract_address, class_hash) in cache.class_hash_writes {
+            parent.set_class_hash_at(contract_address, class_hash)?;
+        }
+        for (class_hash, compiled_class_hash) in cache.compiled_class_hash_writes {
+            parent.set_compiled_class_hash(class_hash, compiled_class_hash)?;
+        }
+        for (class_hash, contract_class) in self.class_hash_to_class {
+            parent.set_contract_class(class_hash, contract_class)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Represents the interim state, containing the changes made by a transaction after execution but
 /// before commitment to the state. Can be passed to external services that validate and count
 /// resources to decide whether the transaction should be committed or aborted.
@@ -636,6 +843,22 @@ pub struct CommitmentStateDiff {
     pub class_hash_to_compiled_class_hash: IndexMap<ClassHash, CompiledClassHash>,
 }
 
+/// A serializable snapshot of the writes done through a `CachedState`; entries are sorted by key.
+/// Note: contract classes are not captured, and should be re-declared or read from the reader.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshot {
+    pub nonce_writes: Vec<(ContractAddress, Nonce)>,
+    pub class_hash_writes: Vec<(ContractAddress, ClassHash)>,
+    pub storage_writes: Vec<(ContractAddress, StorageKey, StarkFelt)>,
+    pub compiled_class_hash_writes: Vec<(ClassHash, CompiledClassHash)>,
+}
+
+fn sorted_entries<K: Copy + Ord, V: Copy>(mapping: &HashMap<K, V>) -> Vec<(K, V)> {
+    let mut entries: Vec<(K, V)> = mapping.iter().map(|(key, value)| (*key, *value)).collect();
+    entries.sort_by_key(|(key, _value)| *key);
+    entries
+}
+
 /// Holds the state changes.
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct StateChanges {
@@ -683,6 +906,18 @@ impl From<&StateChanges> for StateChangesCount {
     }
 }
 
+/// Callback invoked with the class hash of an entry evicted from the global contract cache.
+pub type CacheEvictionCallback = Box<dyn FnMut(ClassHash) + Send>;
+
+#[derive(Default)]
+struct CacheEvictionHook(Option<CacheEvictionCallback>);
+
+impl std::fmt::Debug for CacheEvictionHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("CacheEvictionHook").field(&self.0.is_some()).finish()
+    }
+}
+
 // Note: `ContractClassLRUCache` key-value types must align with `ContractClassMapping`.
 type ContractClassLRUCache = SizedCache<ClassHash, ContractClass>;
 type LockedContractClassCache<'a> = MutexGuard<'a, ContractClassLRUCache>;
@@ -697,13 +932,50 @@ impl GlobalContractCache {
 
     /// Locks the cache for atomic access. Although conceptually shared, writing to this cache is
     /// only possible for one writer at a time.
-    pub fn lock(&mut self) -> LockedContractClassCache<'_> {
+    pub fn lock(&self) -> LockedContractClassCache<'_> {
         self.0.lock().expect("Global contract cache is poisoned.")
     }
 
+    pub fn get(&self, class_hash: &ClassHash) -> Option<ContractClass> {
+        self.lock().cache_get(class_hash).cloned()
+    }
+
+    pub fn insert(&self, class_hash: ClassHash, contract_class: ContractClass) {
+        self.lock().cache_set(class_hash, contract_class);
+    }
+
+    /// Inserts the given class, returning the class hash of the least recently used entry if it
+    /// was evicted to make room for it.
+    pub fn insert_and_get_evicted(
+        &self,
+        class_hash: ClassHash,
+        contract_class: ContractClass,
+    ) -> Option<ClassHash> {
+        let mut cache = self.lock();
+        let is_full = cache.cache_capacity().is_some_and(|capacity| cache.cache_size() >= capacity);
+        let evicted_class_hash = if is_full && !cache.key_order().any(|key| *key == class_hash) {
+            cache.key_order().last().copied()
+        } else {
+            None
+        };
+        cache.cache_set(class_hash, contract_class);
+
+        evicted_class_hash
+    }
+
     pub fn clear(&mut self) {
         self.lock().cache_clear();
     }
+
+    /// Returns the number of lookups served by the cache.
+    pub fn cache_hits(&self) -> Option<u64> {
+        self.lock().cache_hits()
+    }
+
+    /// Returns the number of lookups not served by the cache.
+    pub fn cache_misses(&self) -> Option<u64> {
+        self.lock().cache_misses()
+    }
 }
 
 impl Default for GlobalContractCache {
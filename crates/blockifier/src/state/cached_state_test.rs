@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use assert_matches::assert_matches;
 use indexmap::indexmap;
@@ -10,7 +11,7 @@ use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
 use crate::block_context::BlockContext;
 use crate::state::cached_state::*;
 use crate::test_utils::cached_state::deprecated_create_test_state;
-use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::dict_state_reader::{DictStateReader, DictStateReaderBuilder};
 use crate::test_utils::{get_test_contract_class, TEST_CLASS_HASH, TEST_EMPTY_CONTRACT_CLASS_HASH};
 
 fn set_initial_state_values(
@@ -255,6 +256,41 @@ fn cached_state_state_diff_conversion() {
     assert_eq!(expected_state_diff, state.to_state_diff());
 }
 
+#[test]
+fn cached_state_starknet_api_state_diff_conversion() {
+    let replaced_address = contract_address!("0x100");
+    let deployed_address = contract_address!("0x200");
+    let key = StorageKey(patricia_key!("0x10"));
+    let mut state = CachedState::from(DictStateReader {
+        address_to_class_hash: HashMap::from([(
+            replaced_address,
+            class_hash!(TEST_EMPTY_CONTRACT_CLASS_HASH),
+        )]),
+        ..Default::default()
+    });
+
+    let new_class_hash = class_hash!(TEST_CLASS_HASH);
+    state.set_class_hash_at(replaced_address, new_class_hash).unwrap();
+    state.set_class_hash_at(deployed_address, new_class_hash).unwrap();
+    let value = stark_felt!("0x12345678");
+    state.set_storage_at(deployed_address, key, value).unwrap();
+    state.increment_nonce(deployed_address).unwrap();
+    let declared_class_hash = class_hash!("0x11111111");
+    let compiled_class_hash = CompiledClassHash(stark_felt!(1_u8));
+    state.set_compiled_class_hash(declared_class_hash, compiled_class_hash).unwrap();
+
+    let state_diff = state.to_starknet_api_state_diff();
+    assert_eq!(state_diff.deployed_contracts, indexmap! {deployed_address => new_class_hash});
+    assert_eq!(state_diff.replaced_classes, indexmap! {replaced_address => new_class_hash});
+    assert_eq!(state_diff.storage_diffs, indexmap! {deployed_address => indexmap! {key => value}});
+    assert_eq!(state_diff.nonces, indexmap! {deployed_address => Nonce(stark_felt!(1_u8))});
+    assert_eq!(
+        state_diff.declared_classes,
+        indexmap! {declared_class_hash => (compiled_class_hash, Default::default())}
+    );
+    assert!(state_diff.deprecated_declared_classes.is_empty());
+}
+
 fn create_state_changes_for_test<S: StateReader>(
     state: &mut CachedState<S>,
     fee_token_address: ContractAddress,
@@ -305,7 +341,7 @@ fn test_state_changes_merge() {
     let mut state: CachedState<DictStateReader> = CachedState::default();
     let mut transactional_state = CachedState::create_transactional(&mut state);
     let block_context = BlockContext::create_for_testing();
-    let fee_token_address = block_context.fee_token_addresses.eth_fee_token_address;
+    let fee_token_address = block_context.chain_info.fee_token_addresses.eth_fee_token_address;
     let state_changes1 = create_state_changes_for_test(&mut transactional_state, fee_token_address);
     transactional_state.commit();
 
@@ -371,6 +407,141 @@ fn test_state_changes_merge() {
     );
 }
 
+/// A `DictStateReader` counting its contract class reads.
+#[derive(Default)]
+struct ClassReadCountingStateReader {
+    state: DictStateReader,
+    n_class_reads: usize,
+}
+
+impl StateReader for ClassReadCountingStateReader {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        self.state.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&mut self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.state.get_nonce_at(contract_address)
+    }
+
+    fn get_class_hash_at(&mut self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.state.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.n_class_reads += 1;
+        self.state.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.state.get_compiled_class_hash(class_hash)
+    }
+}
+
+#[test]
+fn test_prefetch_classes() {
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let empty_class_hash = class_hash!(TEST_EMPTY_CONTRACT_CLASS_HASH);
+    let mut state = CachedState::from(ClassReadCountingStateReader {
+        state: DictStateReader {
+            class_hash_to_class: HashMap::from([
+                (class_hash, get_test_contract_class()),
+                (empty_class_hash, get_test_contract_class()),
+            ]),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    // Duplicates are read once.
+    state.prefetch_classes(&[class_hash, empty_class_hash, class_hash]).unwrap();
+    assert_eq!(state.state.n_class_reads, 2);
+
+    state.get_compiled_contract_class(class_hash).unwrap();
+    state.get_compiled_contract_class(empty_class_hash).unwrap();
+    state.prefetch_classes(&[class_hash]).unwrap();
+    assert_eq!(state.state.n_class_reads, 2);
+
+    // Undeclared classes fail the prefetch.
+    let undeclared_class_hash = class_hash!("0x11111111");
+    assert_matches!(
+        state.prefetch_classes(&[undeclared_class_hash]),
+        Err(StateError::UndeclaredClassHash(class_hash)) if class_hash == undeclared_class_hash
+    );
+}
+
+#[test]
+fn test_get_compiled_contract_class_populates_global_cache() {
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let contract_class = get_test_contract_class();
+    let reader = ClassReadCountingStateReader {
+        state: DictStateReader {
+            class_hash_to_class: HashMap::from([(class_hash, contract_class.clone())]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let global_cache = GlobalContractCache::default();
+    let mut state = CachedState::new(reader, global_cache.clone());
+
+    // A miss reads the class and populates the global cache.
+    assert_eq!(state.get_compiled_contract_class(class_hash).unwrap(), contract_class);
+    assert_eq!(state.state.n_class_reads, 1);
+    assert_eq!(global_cache.cache_misses(), Some(1));
+    assert_eq!(global_cache.cache_hits(), Some(0));
+
+    // A fresh state sharing the global cache is served by it.
+    let mut state = CachedState::new(state.state, global_cache.clone());
+    assert_eq!(state.get_compiled_contract_class(class_hash).unwrap(), contract_class);
+    assert_eq!(state.state.n_class_reads, 1);
+    assert_eq!(global_cache.cache_hits(), Some(1));
+
+    // Undeclared classes are not cached.
+    let undeclared_class_hash = class_hash!("0x11111111");
+    assert_matches!(
+        state.get_compiled_contract_class(undeclared_class_hash),
+        Err(StateError::UndeclaredClassHash(class_hash)) if class_hash == undeclared_class_hash
+    );
+    assert_eq!(global_cache.lock().cache_size(), 1);
+}
+
+#[test]
+fn test_on_cache_evict() {
+    let mut state = CachedState::from(DictStateReader::default());
+    let evicted_class_hashes = Arc::new(Mutex::new(Vec::new()));
+    let evicted_class_hashes_clone = evicted_class_hashes.clone();
+    state.on_cache_evict(Box::new(move |class_hash| {
+        evicted_class_hashes_clone.lock().unwrap().push(class_hash);
+    }));
+
+    // Fill the global cache to its capacity; nothing is evicted.
+    let class_hashes: Vec<ClassHash> =
+        (0..=GlobalContractCache::CACHE_SIZE).map(|i| ClassHash(stark_felt!(i as u64))).collect();
+    for &class_hash in &class_hashes[..GlobalContractCache::CACHE_SIZE] {
+        state.set_contract_class(class_hash, get_test_contract_class()).unwrap();
+        state.move_classes_to_global_cache();
+    }
+    assert!(evicted_class_hashes.lock().unwrap().is_empty());
+
+    // Re-inserting a cached class does not evict.
+    state.set_contract_class(class_hashes[1], get_test_contract_class()).unwrap();
+    state.move_classes_to_global_cache();
+    assert!(evicted_class_hashes.lock().unwrap().is_empty());
+
+    // Going past capacity evicts the least recently used class.
+    state
+        .set_contract_class(
+            class_hashes[GlobalContractCache::CACHE_SIZE],
+            get_test_contract_class(),
+        )
+        .unwrap();
+    state.move_classes_to_global_cache();
+    assert_eq!(*evicted_class_hashes.lock().unwrap(), vec![class_hashes[0]]);
+}
+
 #[test]
 fn global_contract_cache_is_used() {
     // Initialize the global cache with a single class, and initialize an empty state with this
@@ -397,3 +568,92 @@ fn global_contract_cache_is_used() {
     assert_eq!(global_cache.lock().cache_hits().unwrap(), 1);
     assert_eq!(global_cache.lock().cache_size(), 1);
 }
+
+#[test]
+fn test_global_contract_cache_concurrent_inserts() {
+    let global_cache = GlobalContractCache::default();
+    let contract_class = get_test_contract_class();
+    let n_threads = 8_u8;
+
+    let handles: Vec<_> = (0..n_threads)
+        .map(|i| {
+            let global_cache = global_cache.clone();
+            let contract_class = contract_class.clone();
+            std::thread::spawn(move || {
+                global_cache.insert(class_hash!(i + 1), contract_class);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    // All insertions are visible through any handle to the cache, and from new states.
+    assert_eq!(global_cache.lock().cache_size(), usize::from(n_threads));
+    for i in 0..n_threads {
+        assert_eq!(global_cache.get(&class_hash!(i + 1)).unwrap(), contract_class);
+    }
+    let mut state = CachedState::new(DictStateReader::default(), global_cache);
+    assert_eq!(state.get_compiled_contract_class(class_hash!(1_u8)).unwrap(), contract_class);
+}
+
+#[test]
+fn test_state_snapshot_round_trip() {
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let initial_value = stark_felt!("0x1");
+    let create_reader =
+        || DictStateReaderBuilder::new().with_storage(contract_address, key, initial_value).build();
+
+    let class_hash = class_hash!("0x20");
+    let compiled_class_hash = CompiledClassHash(stark_felt!("0x21"));
+    let deployed_address = contract_address!("0x200");
+    let new_value = stark_felt!("0xA");
+
+    let mut state = CachedState::from(create_reader());
+    state.set_storage_at(contract_address, key, new_value).unwrap();
+    state.set_class_hash_at(deployed_address, class_hash).unwrap();
+    state.increment_nonce(deployed_address).unwrap();
+    state.set_compiled_class_hash(class_hash, compiled_class_hash).unwrap();
+
+    let snapshot = state.export_snapshot();
+    let serialized = serde_json::to_string(&snapshot).unwrap();
+    let deserialized: StateSnapshot = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, snapshot);
+
+    let mut restored_state = CachedState::from(create_reader());
+    restored_state.import_snapshot(deserialized);
+    assert_eq!(restored_state.get_storage_at(contract_address, key).unwrap(), new_value);
+    assert_eq!(restored_state.get_class_hash_at(deployed_address).unwrap(), class_hash);
+    assert_eq!(restored_state.get_nonce_at(deployed_address).unwrap(), Nonce(stark_felt!(1_u8)));
+    assert_eq!(restored_state.get_compiled_class_hash(class_hash).unwrap(), compiled_class_hash);
+    assert_eq!(restored_state.to_state_diff(), state.to_state_diff());
+}
+
+#[test]
+fn test_with_transaction() {
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let value = stark_felt!("0xA");
+    let mut state: CachedState<DictStateReader> = CachedState::default();
+
+    // Failure: the writes are discarded.
+    let result = state.with_transaction(|transactional_state| {
+        transactional_state.set_storage_at(contract_address, key, value)?;
+        transactional_state.increment_nonce(contract_address)?;
+        Err::<(), _>(StateError::UnavailableContractAddress(contract_address))
+    });
+    assert_matches!(result, Err(StateError::UnavailableContractAddress(_)));
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), StarkFelt::default());
+    assert_eq!(state.get_nonce_at(contract_address).unwrap(), Nonce::default());
+    let state_diff = state.to_state_diff();
+    assert!(state_diff.storage_updates.is_empty() && state_diff.address_to_nonce.is_empty());
+
+    // Success: the writes are committed, and the result is returned.
+    let result = state.with_transaction(|transactional_state| {
+        transactional_state.set_storage_at(contract_address, key, value)?;
+        transactional_state.get_storage_at(contract_address, key)
+    });
+    assert_eq!(result.unwrap(), value);
+    assert_eq!(state.get_storage_at(contract_address, key).unwrap(), value);
+}
@@ -3,19 +3,26 @@ use starknet_api::core::{ClassHash, ContractAddress};
 use starknet_api::StarknetApiError;
 use thiserror::Error;
 
+use crate::utils::format_felt_hex;
+
 #[derive(Debug, Error)]
 pub enum StateError {
     #[error("Cannot deploy contract at address 0.")]
     OutOfRangeContractAddress,
     #[error(transparent)]
     ProgramError(#[from] ProgramError),
-    #[error("Requested {0:?} is unavailable for deployment.")]
+    #[error(
+        "Requested contract address {} is unavailable for deployment.",
+        format_felt_hex(.0.0.key())
+    )]
     UnavailableContractAddress(ContractAddress),
-    #[error("Class with hash {0:#?} is not declared.")]
+    #[error("Class with hash {} is not declared.", format_felt_hex(&.0.0))]
     UndeclaredClassHash(ClassHash),
     #[error(transparent)]
     StarknetApiError(#[from] StarknetApiError),
     /// Represents all unexpected errors that may occur while reading from state.
     #[error("Failed to read from state: {0}.")]
     StateReadError(String),
+    #[error("Unsupported state operation: {0}.")]
+    UnsupportedOperation(String),
 }
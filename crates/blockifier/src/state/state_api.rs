@@ -1,3 +1,4 @@
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
@@ -5,8 +6,13 @@ use starknet_api::state::StorageKey;
 use crate::abi::abi_utils::get_fee_token_var_address;
 use crate::abi::sierra_types::next_storage_key;
 use crate::execution::contract_class::ContractClass;
+use crate::state::cached_state::GlobalContractCache;
 use crate::state::errors::StateError;
 
+#[cfg(test)]
+#[path = "state_api_test.rs"]
+mod test;
+
 pub type StateResult<T> = Result<T, StateError>;
 
 // TODO(barak, 01/10/2023): Remove this enum from here once it can be used from starknet_api.
@@ -43,6 +49,15 @@ pub trait StateReader {
     /// Returns the compiled class hash of the given class hash.
     fn get_compiled_class_hash(&mut self, class_hash: ClassHash) -> StateResult<CompiledClassHash>;
 
+    /// Returns whether a contract class of the given class hash is declared.
+    fn is_class_declared(&mut self, class_hash: ClassHash) -> StateResult<bool> {
+        match self.get_compiled_contract_class(class_hash) {
+            Ok(_) => Ok(true),
+            Err(StateError::UndeclaredClassHash(_)) => Ok(false),
+            Err(error) => Err(error),
+        }
+    }
+
     /// Returns the storage value representing the balance (in fee token) at the given address.
     // TODO(Dori, 1/7/2023): When a standard representation for large integers is set, change the
     //    return type to that.
@@ -60,6 +75,15 @@ pub trait StateReader {
 
         Ok((low, high))
     }
+
+    /// Returns a read-only view of the state as it was at the given block.
+    /// Default: readers without access to historical state do not support this operation.
+    fn at_block(&self, block_number: BlockNumber) -> StateResult<Box<dyn StateReader + '_>> {
+        Err(StateError::UnsupportedOperation(format!(
+            "reading historical state (requested block {})",
+            block_number.0
+        )))
+    }
 }
 
 /// A class defining the API for writing to Starknet global state.
@@ -100,4 +124,10 @@ pub trait State: StateReader {
         class_hash: ClassHash,
         compiled_class_hash: CompiledClassHash,
     ) -> StateResult<()>;
+
+    /// Returns the global contract class cache of the state, to be shared by states nested over
+    /// it. States that do not keep such a cache give a fresh, empty one.
+    fn global_contract_cache(&self) -> GlobalContractCache {
+        GlobalContractCache::default()
+    }
 }
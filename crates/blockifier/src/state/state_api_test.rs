@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::{contract_address, patricia_key, stark_felt};
+
+use crate::execution::contract_class::ContractClass;
+use crate::state::errors::StateError;
+use crate::state::state_api::{StateReader, StateResult};
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::{get_test_contract_class, TEST_CLASS_HASH};
+
+/// A mock archive reader, holding the storage of every block.
+struct HistoricalStateReader {
+    storage_by_block: HashMap<BlockNumber, HashMap<(ContractAddress, StorageKey), StarkFelt>>,
+}
+
+/// A view of [HistoricalStateReader] at a specific block.
+struct HistoricalStateView<'a> {
+    reader: &'a HistoricalStateReader,
+    block_number: BlockNumber,
+}
+
+impl StateReader for HistoricalStateView<'_> {
+    fn get_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        Ok(self
+            .reader
+            .storage_by_block
+            .get(&self.block_number)
+            .and_then(|storage| storage.get(&(contract_address, key)))
+            .copied()
+            .unwrap_or_default())
+    }
+
+    fn get_nonce_at(&mut self, _contract_address: ContractAddress) -> StateResult<Nonce> {
+        Ok(Nonce::default())
+    }
+
+    fn get_class_hash_at(&mut self, _contract_address: ContractAddress) -> StateResult<ClassHash> {
+        Ok(ClassHash::default())
+    }
+
+    fn get_compiled_contract_class(&mut self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        Err(StateError::UndeclaredClassHash(class_hash))
+    }
+
+    fn get_compiled_class_hash(
+        &mut self,
+        _class_hash: ClassHash,
+    ) -> StateResult<CompiledClassHash> {
+        Ok(CompiledClassHash::default())
+    }
+}
+
+fn historical_reads_only<T>() -> StateResult<T> {
+    Err(StateError::UnsupportedOperation("Only historical reads are supported.".to_string()))
+}
+
+impl StateReader for HistoricalStateReader {
+    fn get_storage_at(
+        &mut self,
+        _contract_address: ContractAddress,
+        _key: StorageKey,
+    ) -> StateResult<StarkFelt> {
+        historical_reads_only()
+    }
+
+    fn get_nonce_at(&mut self, _contract_address: ContractAddress) -> StateResult<Nonce> {
+        historical_reads_only()
+    }
+
+    fn get_class_hash_at(&mut self, _contract_address: ContractAddress) -> StateResult<ClassHash> {
+        historical_reads_only()
+    }
+
+    fn get_compiled_contract_class(
+        &mut self,
+        _class_hash: ClassHash,
+    ) -> StateResult<ContractClass> {
+        historical_reads_only()
+    }
+
+    fn get_compiled_class_hash(
+        &mut self,
+        _class_hash: ClassHash,
+    ) -> StateResult<CompiledClassHash> {
+        historical_reads_only()
+    }
+
+    fn at_block(&self, block_number: BlockNumber) -> StateResult<Box<dyn StateReader + '_>> {
+        Ok(Box::new(HistoricalStateView { reader: self, block_number }))
+    }
+}
+
+#[test]
+fn test_at_block() {
+    let contract_address = contract_address!("0x100");
+    let key = StorageKey(patricia_key!("0x10"));
+    let (old_value, new_value) = (stark_felt!(1_u8), stark_felt!(2_u8));
+    let reader = HistoricalStateReader {
+        storage_by_block: HashMap::from([
+            (BlockNumber(1), HashMap::from([((contract_address, key), old_value)])),
+            (BlockNumber(2), HashMap::from([((contract_address, key), new_value)])),
+        ]),
+    };
+
+    let mut view = reader.at_block(BlockNumber(1)).unwrap();
+    assert_eq!(view.get_storage_at(contract_address, key).unwrap(), old_value);
+    let mut view = reader.at_block(BlockNumber(2)).unwrap();
+    assert_eq!(view.get_storage_at(contract_address, key).unwrap(), new_value);
+
+    // Reads not bound to a block are unsupported by the archive reader.
+    let mut reader = HistoricalStateReader { storage_by_block: HashMap::new() };
+    assert_matches!(
+        reader.get_storage_at(contract_address, key).unwrap_err(),
+        StateError::UnsupportedOperation(_)
+    );
+}
+
+#[test]
+fn test_at_block_unsupported_by_default() {
+    let reader = DictStateReader::default();
+    assert_matches!(
+        reader.at_block(BlockNumber(1)).err(),
+        Some(StateError::UnsupportedOperation(_))
+    );
+}
+
+#[test]
+fn test_is_class_declared() {
+    let class_hash = ClassHash(stark_felt!(TEST_CLASS_HASH));
+    let mut state = DictStateReader {
+        class_hash_to_class: HashMap::from([(class_hash, get_test_contract_class())]),
+        ..Default::default()
+    };
+
+    assert!(state.is_class_declared(class_hash).unwrap());
+    assert!(!state.is_class_declared(ClassHash(stark_felt!("0x11111111"))).unwrap());
+}
@@ -0,0 +1,152 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+use cached::{Cached, SizedCache};
+
+#[cfg(test)]
+#[path = "timed_sized_cache_test.rs"]
+mod test;
+
+/// A monotonic clock, returning the time elapsed since some fixed starting point.
+pub type Clock = Box<dyn Fn() -> Duration + Send + Sync>;
+
+/// A size-bounded LRU cache whose entries expire after a fixed time-to-live; expired entries are
+/// treated as misses and evicted on access. Suits long-running nodes, where classes replaced via
+/// `replace_class` or reorgs should eventually leave the cache.
+pub struct TimedSizedCache<K: Hash + Eq + Clone, V> {
+    store: SizedCache<K, (Duration, V)>,
+    ttl: Duration,
+    clock: Clock,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Hash + Eq + Clone, V> TimedSizedCache<K, V> {
+    pub fn with_size_and_ttl(size: usize, ttl: Duration) -> Self {
+        let start = Instant::now();
+        Self::with_size_ttl_and_clock(size, ttl, Box::new(move || start.elapsed()))
+    }
+
+    /// Creates a cache that measures entry age using the given clock.
+    pub fn with_size_ttl_and_clock(size: usize, ttl: Duration, clock: Clock) -> Self {
+        Self { store: SizedCache::with_size(size), ttl, clock, hits: 0, misses: 0 }
+    }
+
+    /// Evicts the entry of the given key if it has expired; returns whether a live entry remains.
+    fn evict_if_expired<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let now = (self.clock)();
+        let Some((inserted_at, _value)) = self.store.cache_get(key) else { return false };
+        if now.saturating_sub(*inserted_at) < self.ttl {
+            return true;
+        }
+
+        self.store.cache_remove(key);
+        false
+    }
+
+    fn record_access(&mut self, is_hit: bool) {
+        if is_hit {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Cached<K, V> for TimedSizedCache<K, V> {
+    fn cache_get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let is_hit = self.evict_if_expired(key);
+        self.record_access(is_hit);
+        if !is_hit {
+            return None;
+        }
+
+        self.store.cache_get(key).map(|(_inserted_at, value)| value)
+    }
+
+    fn cache_get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let is_hit = self.evict_if_expired(key);
+        self.record_access(is_hit);
+        if !is_hit {
+            return None;
+        }
+
+        self.store.cache_get_mut(key).map(|(_inserted_at, value)| value)
+    }
+
+    fn cache_get_or_set_with<F: FnOnce() -> V>(&mut self, key: K, f: F) -> &mut V {
+        let is_hit = self.evict_if_expired(&key);
+        self.record_access(is_hit);
+        let now = (self.clock)();
+        let (_inserted_at, value) = self.store.cache_get_or_set_with(key, || (now, f()));
+        value
+    }
+
+    fn cache_set(&mut self, key: K, value: V) -> Option<V> {
+        let is_live = self.evict_if_expired(&key);
+        let now = (self.clock)();
+        let previous = self.store.cache_set(key, (now, value));
+        previous.filter(|_| is_live).map(|(_inserted_at, value)| value)
+    }
+
+    fn cache_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let is_live = self.evict_if_expired(key);
+        if !is_live {
+            return None;
+        }
+
+        self.store.cache_remove(key).map(|(_inserted_at, value)| value)
+    }
+
+    fn cache_clear(&mut self) {
+        self.store.cache_clear();
+    }
+
+    fn cache_reset(&mut self) {
+        self.store.cache_reset();
+        self.cache_reset_metrics();
+    }
+
+    fn cache_reset_metrics(&mut self) {
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// Includes expired entries that were not accessed since expiring.
+    fn cache_size(&self) -> usize {
+        self.store.cache_size()
+    }
+
+    fn cache_hits(&self) -> Option<u64> {
+        Some(self.hits)
+    }
+
+    fn cache_misses(&self) -> Option<u64> {
+        Some(self.misses)
+    }
+
+    fn cache_capacity(&self) -> Option<usize> {
+        self.store.cache_capacity()
+    }
+
+    fn cache_lifespan(&self) -> Option<u64> {
+        Some(self.ttl.as_secs())
+    }
+}
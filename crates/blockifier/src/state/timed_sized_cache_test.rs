@@ -0,0 +1,44 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use cached::Cached;
+
+use crate::state::timed_sized_cache::TimedSizedCache;
+
+const CLASS: u8 = 1;
+const RENEWED_CLASS: u8 = 2;
+
+#[test]
+fn test_entries_expire_after_ttl() {
+    // A simulated clock, in seconds.
+    let now = Arc::new(AtomicU64::new(0));
+    let clock_now = now.clone();
+    let clock = Box::new(move || Duration::from_secs(clock_now.load(Ordering::SeqCst)));
+    let mut cache = TimedSizedCache::with_size_ttl_and_clock(10, Duration::from_secs(60), clock);
+
+    cache.cache_set(CLASS, 1);
+    cache.cache_set(RENEWED_CLASS, 2);
+    now.store(59, Ordering::SeqCst);
+    assert_eq!(cache.cache_get(&CLASS), Some(&1));
+
+    // Re-setting an entry renews it.
+    cache.cache_set(RENEWED_CLASS, 2);
+    now.store(60, Ordering::SeqCst);
+    assert_eq!(cache.cache_get(&CLASS), None);
+    assert_eq!(cache.cache_get(&RENEWED_CLASS), Some(&2));
+    assert_eq!(cache.cache_size(), 1);
+    assert_eq!(cache.cache_hits(), Some(2));
+    assert_eq!(cache.cache_misses(), Some(1));
+
+    // A renewed entry expires once the TTL has passed since its renewal.
+    now.store(118, Ordering::SeqCst);
+    assert_eq!(cache.cache_get(&RENEWED_CLASS), Some(&2));
+    now.store(119, Ordering::SeqCst);
+    assert_eq!(cache.cache_get(&RENEWED_CLASS), None);
+    assert_eq!(cache.cache_size(), 0);
+
+    // Expired entries are recomputed.
+    now.store(200, Ordering::SeqCst);
+    assert_eq!(*cache.cache_get_or_set_with(RENEWED_CLASS, || 3), 3);
+}
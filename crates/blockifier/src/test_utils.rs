@@ -30,7 +30,7 @@ use crate::abi::constants::{self};
 use crate::execution::contract_class::{ContractClass, ContractClassV0};
 use crate::execution::entry_point::{CallEntryPoint, CallType};
 use crate::execution::execution_utils::felt_to_stark_felt;
-use crate::utils::const_max;
+use crate::utils::{const_max, format_felt_hex};
 
 // TODO(Dori, 1/2/2024): Remove these constants once all tests use the `contracts` and
 //   `initial_test_state` modules for testing.
@@ -166,13 +166,16 @@ impl SaltManager {
 }
 
 pub fn pad_address_to_64(address: &str) -> String {
-    let trimmed_address = address.strip_prefix("0x").unwrap_or(address);
-    String::from("0x") + format!("{trimmed_address:0>64}").as_str()
+    format_felt_hex(&stark_felt!(address))
+}
+
+/// Resolves a contract path relative to the crate root.
+pub fn get_contract_path(contract_path: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), contract_path].iter().collect()
 }
 
 pub fn get_raw_contract_class(contract_path: &str) -> String {
-    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), contract_path].iter().collect();
-    fs::read_to_string(path).unwrap()
+    fs::read_to_string(get_contract_path(contract_path)).unwrap()
 }
 
 pub fn get_deprecated_contract_class(contract_path: &str) -> DeprecatedContractClass {
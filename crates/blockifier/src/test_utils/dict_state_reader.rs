@@ -9,8 +9,12 @@ use crate::state::cached_state::StorageEntry;
 use crate::state::errors::StateError;
 use crate::state::state_api::{StateReader, StateResult};
 
+#[cfg(test)]
+#[path = "dict_state_reader_test.rs"]
+mod test;
+
 /// A simple implementation of `StateReader` using `HashMap`s as storage.
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct DictStateReader {
     pub storage_view: HashMap<StorageEntry, StarkFelt>,
     pub address_to_nonce: HashMap<ContractAddress, Nonce>,
@@ -58,3 +62,66 @@ impl StateReader for DictStateReader {
         Ok(compiled_class_hash)
     }
 }
+
+impl DictStateReader {
+    /// Returns a copy of the current contents of the reader, to be restored later.
+    pub fn snapshot(&self) -> DictStateReaderSnapshot {
+        DictStateReaderSnapshot(self.clone())
+    }
+
+    /// Rolls the reader back to the given snapshot, discarding all changes made since.
+    pub fn restore(&mut self, snapshot: DictStateReaderSnapshot) {
+        *self = snapshot.0;
+    }
+}
+
+/// The contents of a `DictStateReader` at some point in time; see `DictStateReader::snapshot`.
+#[derive(Clone, Debug)]
+pub struct DictStateReaderSnapshot(DictStateReader);
+
+/// Builds a `DictStateReader`, entry by entry.
+#[derive(Debug, Default)]
+pub struct DictStateReaderBuilder {
+    reader: DictStateReader,
+}
+
+impl DictStateReaderBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_storage(
+        mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    ) -> Self {
+        self.reader.storage_view.insert((contract_address, key), value);
+        self
+    }
+
+    pub fn with_nonce(mut self, contract_address: ContractAddress, nonce: Nonce) -> Self {
+        self.reader.address_to_nonce.insert(contract_address, nonce);
+        self
+    }
+
+    /// Declares the given class.
+    pub fn with_class(mut self, class_hash: ClassHash, contract_class: ContractClass) -> Self {
+        self.reader.class_hash_to_class.insert(class_hash, contract_class);
+        self
+    }
+
+    /// Deploys an instance of the given class at the given address.
+    pub fn with_deployed(
+        mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> Self {
+        self.reader.address_to_class_hash.insert(contract_address, class_hash);
+        self
+    }
+
+    pub fn build(self) -> DictStateReader {
+        self.reader
+    }
+}
@@ -0,0 +1,38 @@
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::state::state_api::StateReader;
+use crate::test_utils::dict_state_reader::DictStateReaderBuilder;
+use crate::test_utils::{get_test_contract_class, TEST_CLASS_HASH, TEST_CONTRACT_ADDRESS};
+
+#[test]
+fn test_builder_and_snapshot_restore() {
+    let contract_address = contract_address!(TEST_CONTRACT_ADDRESS);
+    let class_hash = class_hash!(TEST_CLASS_HASH);
+    let key = StorageKey(patricia_key!("0x10"));
+    let value = stark_felt!("0x1");
+    let nonce = Nonce(stark_felt!(2_u8));
+
+    let mut reader = DictStateReaderBuilder::new()
+        .with_class(class_hash, get_test_contract_class())
+        .with_deployed(contract_address, class_hash)
+        .with_storage(contract_address, key, value)
+        .with_nonce(contract_address, nonce)
+        .build();
+    assert_eq!(reader.get_class_hash_at(contract_address).unwrap(), class_hash);
+    assert_eq!(reader.get_compiled_contract_class(class_hash).unwrap(), get_test_contract_class());
+    assert_eq!(reader.get_storage_at(contract_address, key).unwrap(), value);
+    assert_eq!(reader.get_nonce_at(contract_address).unwrap(), nonce);
+
+    // Mutate the reader, then roll back.
+    let snapshot = reader.snapshot();
+    reader.storage_view.insert((contract_address, key), stark_felt!("0x2"));
+    reader.address_to_nonce.remove(&contract_address);
+    reader.restore(snapshot);
+
+    assert_eq!(reader.get_storage_at(contract_address, key).unwrap(), value);
+    assert_eq!(reader.get_nonce_at(contract_address).unwrap(), nonce);
+}
@@ -17,15 +17,24 @@ use super::{
 };
 use crate::abi::constants;
 use crate::abi::constants::{MAX_STEPS_PER_TX, MAX_VALIDATE_STEPS_PER_TX};
-use crate::block_context::{BlockContext, FeeTokenAddresses, GasPrices};
+use crate::block_context::{
+    BlockContext, BlockInfo, BlockResourceLimits, CalldataL1GasCosts, ChainInfo, FeeRecipient,
+    FeeTokenAddresses, GasPrices, SyscallGasCosts,
+};
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
 use crate::execution::contract_class::{ContractClassV0, ContractClassV1};
 use crate::execution::entry_point::{
     CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult, ExecutionResources,
+    ExecutionTimings,
 };
 use crate::state::state_api::State;
-use crate::test_utils::get_raw_contract_class;
+use crate::test_utils::get_contract_path;
+use crate::transaction::constants::{
+    MAX_BYTECODE_LENGTH, MAX_CONTRACT_CLASS_SIZE, MAX_EVENT_DATA_SIZE, MAX_L1_MESSAGE_PAYLOAD_SIZE,
+    MAX_SUPPORTED_COMPILER_VERSION, MIN_SUPPORTED_COMPILER_VERSION,
+};
 use crate::transaction::objects::{AccountTransactionContext, DeprecatedAccountTransactionContext};
+use crate::versioned_constants::VersionedConstants;
 
 impl CallEntryPoint {
     /// Executes the call directly, without account context. Limits the number of steps by resource
@@ -54,6 +63,89 @@ impl CallEntryPoint {
         self.execute(state, &mut ExecutionResources::default(), &mut context)
     }
 
+    /// Executes the call directly under the given block context, without account context.
+    pub fn execute_directly_given_block_context(
+        self,
+        state: &mut dyn State,
+        block_context: BlockContext,
+    ) -> EntryPointExecutionResult<CallInfo> {
+        let (call_info, _context) =
+            self.execute_directly_with_custom_context(state, &block_context, |_context| {})?;
+
+        Ok(call_info)
+    }
+
+    /// Executes the call directly, without account context, and returns the timings of its
+    /// execution phases. Intended for micro-benchmarking entry points.
+    pub fn execute_directly_timed(
+        &self,
+        state: &mut dyn State,
+    ) -> EntryPointExecutionResult<(CallInfo, ExecutionTimings)> {
+        let (call_info, context) = self.clone().execute_directly_with_custom_context(
+            state,
+            &BlockContext::create_for_testing(),
+            |context| context.record_execution_timings = true,
+        )?;
+        let execution_timings =
+            context.execution_timings.expect("Execution timings should be recorded.");
+
+        Ok((call_info, execution_timings))
+    }
+
+    /// Executes the call directly, without account context, failing the syscalls set in `faults`.
+    #[cfg(any(feature = "fault-injection", test))]
+    pub fn execute_directly_with_fault_injection(
+        &self,
+        state: &mut dyn State,
+        faults: &crate::execution::entry_point::FaultConfig,
+    ) -> EntryPointExecutionResult<CallInfo> {
+        let (call_info, _context) = self.clone().execute_directly_with_custom_context(
+            state,
+            &BlockContext::create_for_testing(),
+            |context| context.faults = faults.clone(),
+        )?;
+
+        Ok(call_info)
+    }
+
+    /// Executes the call directly, without account context, and returns the final memory of the
+    /// run.
+    #[cfg(feature = "prover-io")]
+    pub fn execute_directly_with_memory_segments(
+        &self,
+        state: &mut dyn State,
+    ) -> EntryPointExecutionResult<(CallInfo, crate::execution::entry_point::VmMemorySegments)>
+    {
+        let (call_info, context) = self.clone().execute_directly_with_custom_context(
+            state,
+            &BlockContext::create_for_testing(),
+            |context| context.record_memory_segments = true,
+        )?;
+
+        Ok((call_info, context.memory_segments.expect("Memory segments should be recorded.")))
+    }
+
+    /// Executes the call directly under the given block context, without account context, after
+    /// customizing the execution context; returns the context along with the call info, for
+    /// inspecting what the execution recorded in it.
+    fn execute_directly_with_custom_context(
+        self,
+        state: &mut dyn State,
+        block_context: &BlockContext,
+        customize_context: impl FnOnce(&mut EntryPointExecutionContext),
+    ) -> EntryPointExecutionResult<(CallInfo, EntryPointExecutionContext)> {
+        let mut context = EntryPointExecutionContext::new_invoke(
+            block_context,
+            &AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext::default()),
+            true,
+        )
+        .unwrap();
+        customize_context(&mut context);
+        let call_info = self.execute(state, &mut ExecutionResources::default(), &mut context)?;
+
+        Ok((call_info, context))
+    }
+
     /// Executes the call directly in validate mode, without account context. Limits the number of
     /// steps by resource bounds.
     pub fn execute_directly_in_validate_mode(
@@ -84,27 +176,58 @@ impl CallEntryPoint {
     }
 }
 
-impl BlockContext {
-    pub fn create_for_testing() -> BlockContext {
-        BlockContext {
-            chain_id: ChainId(CHAIN_ID_NAME.to_string()),
+impl BlockInfo {
+    pub fn create_for_testing() -> Self {
+        Self {
             block_number: BlockNumber(CURRENT_BLOCK_NUMBER),
             block_timestamp: BlockTimestamp(CURRENT_BLOCK_TIMESTAMP),
+            prev_block_timestamp: None,
             sequencer_address: contract_address!(TEST_SEQUENCER_ADDRESS),
+            gas_prices: GasPrices {
+                eth_l1_gas_price: DEFAULT_ETH_L1_GAS_PRICE,
+                strk_l1_gas_price: DEFAULT_STRK_L1_GAS_PRICE,
+            },
+            block_hashes: HashMap::default(),
+        }
+    }
+}
+
+impl ChainInfo {
+    pub fn create_for_testing() -> Self {
+        Self {
+            chain_id: ChainId(CHAIN_ID_NAME.to_string()),
             fee_token_addresses: FeeTokenAddresses {
                 eth_fee_token_address: contract_address!(TEST_ERC20_CONTRACT_ADDRESS),
                 strk_fee_token_address: contract_address!(TEST_ERC20_CONTRACT_ADDRESS2),
             },
             vm_resource_fee_cost: Default::default(),
-            gas_prices: GasPrices {
-                eth_l1_gas_price: DEFAULT_ETH_L1_GAS_PRICE,
-                strk_l1_gas_price: DEFAULT_STRK_L1_GAS_PRICE,
-            },
+            fee_recipient: FeeRecipient::Sequencer,
+            enforce_l1_handler_fee: false,
             invoke_tx_max_n_steps: MAX_STEPS_PER_TX as u32,
             validate_max_n_steps: MAX_VALIDATE_STEPS_PER_TX as u32,
             max_recursion_depth: 50,
+            max_contract_class_size: MAX_CONTRACT_CLASS_SIZE,
+            max_bytecode_length: MAX_BYTECODE_LENGTH,
+            supported_compiler_versions: MIN_SUPPORTED_COMPILER_VERSION
+                ..=MAX_SUPPORTED_COMPILER_VERSION,
+            max_event_data_size: MAX_EVENT_DATA_SIZE,
+            max_l1_message_payload_size: MAX_L1_MESSAGE_PAYLOAD_SIZE,
+            max_storage_writes: None,
+            block_resource_limits: BlockResourceLimits::default(),
+            syscall_gas_costs: SyscallGasCosts::default(),
+            calldata_l1_gas_costs: CalldataL1GasCosts::default(),
         }
     }
+}
+
+impl BlockContext {
+    pub fn create_for_testing() -> BlockContext {
+        BlockContext::new(
+            BlockInfo::create_for_testing(),
+            ChainInfo::create_for_testing(),
+            VersionedConstants::latest(),
+        )
+    }
 
     pub fn create_for_account_testing() -> BlockContext {
         let vm_resource_fee_cost = Arc::new(HashMap::from([
@@ -117,7 +240,8 @@ impl BlockContext {
             (OUTPUT_BUILTIN_NAME.to_string(), 1_f64),
             (EC_OP_BUILTIN_NAME.to_string(), 1_f64),
         ]));
-        BlockContext { vm_resource_fee_cost, ..BlockContext::create_for_testing() }
+        let chain_info = ChainInfo { vm_resource_fee_cost, ..ChainInfo::create_for_testing() };
+        BlockContext::new(BlockInfo::create_for_testing(), chain_info, VersionedConstants::latest())
     }
 }
 
@@ -131,14 +255,12 @@ impl CallExecution {
 
 impl ContractClassV0 {
     pub fn from_file(contract_path: &str) -> Self {
-        let raw_contract_class = get_raw_contract_class(contract_path);
-        Self::try_from_json_string(&raw_contract_class).unwrap()
+        Self::try_from_file(get_contract_path(contract_path)).unwrap()
     }
 }
 
 impl ContractClassV1 {
     pub fn from_file(contract_path: &str) -> Self {
-        let raw_contract_class = get_raw_contract_class(contract_path);
-        Self::try_from_json_string(&raw_contract_class).unwrap()
+        Self::try_from_file(get_contract_path(contract_path)).unwrap()
     }
 }
@@ -1,9 +1,11 @@
 pub mod account_transaction;
+pub mod conformance;
 pub mod constants;
 pub mod errors;
 pub mod objects;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
+pub mod trace;
 pub mod transaction_execution;
 pub mod transaction_types;
 pub mod transaction_utils;
@@ -15,7 +15,7 @@ use crate::execution::entry_point::{
 };
 use crate::fee::actual_cost::{ActualCost, ActualCostBuilder};
 use crate::fee::fee_checks::{FeeCheckReportFields, PostExecutionReport};
-use crate::fee::fee_utils::{get_fee_by_l1_gas_usage, verify_can_pay_committed_bounds};
+use crate::fee::fee_utils::{self, get_fee_by_l1_gas_usage, verify_can_pay_committed_bounds};
 use crate::fee::gas_usage::estimate_minimal_l1_gas;
 use crate::retdata;
 use crate::state::cached_state::{CachedState, TransactionalState};
@@ -33,7 +33,7 @@ use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transaction_utils::update_remaining_gas;
 use crate::transaction::transactions::{
     DeclareTransaction, DeployAccountTransaction, Executable, ExecutableTransaction,
-    InvokeTransaction, ValidatableTransaction,
+    ExecutionFlags, InvokeTransaction, ValidatableTransaction,
 };
 
 #[cfg(test)]
@@ -93,6 +93,16 @@ impl AccountTransaction {
         selector_from_name(validate_entry_point_name)
     }
 
+    /// Returns the length of the calldata of the transaction's execution; a declare transaction has
+    /// none.
+    pub fn calldata_length(&self) -> usize {
+        match self {
+            Self::Declare(_) => 0,
+            Self::DeployAccount(tx) => tx.constructor_calldata().0.len(),
+            Self::Invoke(tx) => tx.calldata().0.len(),
+        }
+    }
+
     // Calldata for validation contains transaction fields that cannot be obtained by calling
     // `get_tx_info()`.
     fn validate_entrypoint_calldata(&self) -> Calldata {
@@ -185,8 +195,10 @@ impl AccountTransaction {
                     })?;
                 }
 
-                let actual_l1_gas_price =
-                    block_context.gas_prices.get_by_fee_type(&account_tx_context.fee_type());
+                let actual_l1_gas_price = block_context
+                    .block_info
+                    .gas_prices
+                    .get_by_fee_type(&account_tx_context.fee_type());
                 if max_l1_gas_price < actual_l1_gas_price {
                     return Err(TransactionFeeError::MaxL1GasPriceTooLow {
                         max_l1_gas_price,
@@ -194,16 +206,13 @@ impl AccountTransaction {
                     })?;
                 }
             }
-            AccountTransactionContext::Deprecated(context) => {
-                let max_fee = context.max_fee;
+            AccountTransactionContext::Deprecated(_) => {
                 let min_fee = get_fee_by_l1_gas_usage(
                     block_context,
                     minimal_l1_gas_amount,
                     &account_tx_context.fee_type(),
                 );
-                if max_fee < min_fee {
-                    return Err(TransactionFeeError::MaxFeeTooLow { min_fee, max_fee })?;
-                }
+                fee_utils::check_fee_bounds(account_tx_context, min_fee)?;
             }
         };
         Ok(())
@@ -218,22 +227,39 @@ impl AccountTransaction {
             return Ok(());
         }
 
+        match Self::validate_nonce(state, account_tx_context) {
+            Ok(()) => {}
+            // Non-strict checks accept future nonces.
+            Err(TransactionPreValidationError::NonceTooHigh { .. }) if !strict => {}
+            Err(error) => return Err(error),
+        }
+        Ok(state.increment_nonce(account_tx_context.sender_address())?)
+    }
+
+    /// Validates that the transaction nonce equals the current nonce of the sending account.
+    pub fn validate_nonce<S: StateReader + ?Sized>(
+        state: &mut S,
+        account_tx_context: &AccountTransactionContext,
+    ) -> TransactionPreValidationResult<()> {
         let address = account_tx_context.sender_address();
         let account_nonce = state.get_nonce_at(address)?;
         let incoming_tx_nonce = account_tx_context.nonce();
-        let valid_nonce = if strict {
-            account_nonce == incoming_tx_nonce
-        } else {
-            account_nonce <= incoming_tx_nonce
-        };
-        if valid_nonce {
-            return Ok(state.increment_nonce(address)?);
+        if incoming_tx_nonce < account_nonce {
+            return Err(TransactionPreValidationError::NonceTooLow {
+                address,
+                account_nonce,
+                incoming_tx_nonce,
+            });
         }
-        Err(TransactionPreValidationError::InvalidNonce {
-            address,
-            account_nonce,
-            incoming_tx_nonce,
-        })
+        if incoming_tx_nonce > account_nonce {
+            return Err(TransactionPreValidationError::NonceTooHigh {
+                address,
+                account_nonce,
+                incoming_tx_nonce,
+            });
+        }
+
+        Ok(())
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -267,28 +293,54 @@ impl AccountTransaction {
         block_context: &BlockContext,
         actual_fee: Fee,
         charge_fee: bool,
-    ) -> TransactionExecutionResult<Option<CallInfo>> {
+    ) -> TransactionExecutionResult<Vec<CallInfo>> {
         if !charge_fee || actual_fee == Fee(0) {
             // Fee charging is not enforced in some transaction simulations and tests.
-            return Ok(None);
+            return Ok(vec![]);
         }
 
         // Charge fee.
         let account_tx_context = self.get_account_tx_context();
-        let fee_transfer_call_info =
-            Self::execute_fee_transfer(state, block_context, account_tx_context, actual_fee)?;
-
-        Ok(Some(fee_transfer_call_info))
+        Self::execute_fee_transfer(state, block_context, account_tx_context, actual_fee)
     }
 
+    /// Transfers the fee to the chain's fee recipients, one transfer per recipient, and returns
+    /// the transfers' call infos in order.
     fn execute_fee_transfer(
         state: &mut dyn State,
         block_context: &BlockContext,
         account_tx_context: AccountTransactionContext,
         actual_fee: Fee,
+    ) -> TransactionExecutionResult<Vec<CallInfo>> {
+        let mut context =
+            EntryPointExecutionContext::new_invoke(block_context, &account_tx_context, true)?;
+
+        block_context
+            .fee_transfers(actual_fee)
+            .into_iter()
+            .map(|(recipient, amount)| {
+                Self::execute_single_fee_transfer(
+                    state,
+                    block_context,
+                    &account_tx_context,
+                    &mut context,
+                    recipient,
+                    amount,
+                )
+            })
+            .collect()
+    }
+
+    fn execute_single_fee_transfer(
+        state: &mut dyn State,
+        block_context: &BlockContext,
+        account_tx_context: &AccountTransactionContext,
+        context: &mut EntryPointExecutionContext,
+        recipient: ContractAddress,
+        amount: Fee,
     ) -> TransactionExecutionResult<CallInfo> {
         // The least significant 128 bits of the amount transferred.
-        let lsb_amount = StarkFelt::from(actual_fee.0);
+        let lsb_amount = StarkFelt::from(amount.0);
         // The most significant 128 bits of the amount transferred.
         let msb_amount = StarkFelt::from(0_u8);
 
@@ -300,7 +352,7 @@ impl AccountTransaction {
             entry_point_type: EntryPointType::External,
             entry_point_selector: selector_from_name(constants::TRANSFER_ENTRY_POINT_NAME),
             calldata: calldata![
-                *block_context.sequencer_address.0.key(), // Recipient.
+                *recipient.0.key(), // Recipient.
                 lsb_amount,
                 msb_amount
             ],
@@ -311,26 +363,38 @@ impl AccountTransaction {
             initial_gas: abi_constants::INITIAL_GAS_COST,
         };
 
-        let mut context =
-            EntryPointExecutionContext::new_invoke(block_context, &account_tx_context, true)?;
-
         Ok(fee_transfer_call
-            .execute(state, &mut ExecutionResources::default(), &mut context)
+            .execute(state, &mut ExecutionResources::default(), context)
             .map_err(TransactionFeeError::ExecuteFeeTransferError)?)
     }
 
-    fn run_execute<S: State>(
+    /// Runs the execution phase; fails if it modifies more storage slots than the chain allows.
+    fn run_execute<S: StateReader>(
         &self,
-        state: &mut S,
+        state: &mut CachedState<S>,
         resources: &mut ExecutionResources,
         context: &mut EntryPointExecutionContext,
         remaining_gas: &mut u64,
     ) -> TransactionExecutionResult<Option<CallInfo>> {
-        match &self {
+        let n_storage_updates_before_execution = state.n_storage_updates();
+        let execute_call_info = match &self {
             Self::Declare(tx) => tx.run_execute(state, resources, context, remaining_gas),
             Self::DeployAccount(tx) => tx.run_execute(state, resources, context, remaining_gas),
             Self::Invoke(tx) => tx.run_execute(state, resources, context, remaining_gas),
+        }?;
+
+        if let Some(max_storage_writes) = context.block_context.chain_info.max_storage_writes {
+            let n_storage_writes =
+                state.n_storage_updates().saturating_sub(n_storage_updates_before_execution);
+            if n_storage_writes > max_storage_writes {
+                return Err(TransactionExecutionError::TooManyStorageWrites {
+                    n_storage_writes,
+                    max_storage_writes,
+                });
+            }
         }
+
+        Ok(execute_call_info)
     }
 
     fn run_non_revertible<S: StateReader>(
@@ -577,8 +641,77 @@ impl AccountTransaction {
         )
     }
 
+    /// Runs only the pre-validation and validation phases of the transaction; nothing is executed
+    /// and no fee is charged. The state is left unchanged: the state changes of the run, including
+    /// the nonce increment, are discarded. Since `DeployAccount` transactions are validated
+    /// against the deployed account, their deployment is run first.
+    fn run_validate_only<S: StateReader>(
+        &self,
+        state: &mut TransactionalState<'_, S>,
+        account_tx_context: &AccountTransactionContext,
+        block_context: &BlockContext,
+        validate: bool,
+        charge_fee: bool,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        let mut validation_state = CachedState::create_transactional(state);
+        let strict_nonce_check = true;
+        self.perform_pre_validation_stage(
+            &mut validation_state,
+            account_tx_context,
+            block_context,
+            charge_fee,
+            strict_nonce_check,
+        )?;
+
+        let mut resources = ExecutionResources::default();
+        let mut remaining_gas = Transaction::initial_gas();
+        let limit_steps_by_resources = charge_fee;
+        if matches!(self, Self::DeployAccount(_)) {
+            let mut execution_context = EntryPointExecutionContext::new_validate(
+                block_context,
+                account_tx_context,
+                limit_steps_by_resources,
+            )?;
+            self.run_execute(
+                &mut validation_state,
+                &mut ExecutionResources::default(),
+                &mut execution_context,
+                &mut remaining_gas,
+            )?;
+        }
+
+        let validate_call_info = self.handle_validate_tx(
+            &mut validation_state,
+            &mut resources,
+            account_tx_context,
+            &mut remaining_gas,
+            block_context,
+            validate,
+            limit_steps_by_resources,
+        )?;
+        let ActualCost { actual_fee, actual_resources } = self
+            .into_actual_cost_builder(block_context)
+            .with_validate_call_info(&validate_call_info)
+            .try_add_state_changes(&mut validation_state)?
+            .build(&resources)?;
+
+        validation_state.abort();
+
+        Ok(TransactionExecutionInfo {
+            validate_call_info,
+            execute_call_info: None,
+            fee_transfer_call_info: None,
+            additional_fee_transfer_call_infos: vec![],
+            actual_fee,
+            actual_resources,
+            estimated_resources: None,
+            revert_error: None,
+        })
+    }
+
     pub fn into_actual_cost_builder(&self, block_context: &BlockContext) -> ActualCostBuilder<'_> {
         ActualCostBuilder::new(block_context, self.get_account_tx_context(), self.tx_type())
+            .with_calldata_length(self.calldata_length())
     }
 }
 
@@ -587,13 +720,23 @@ impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
         self,
         state: &mut TransactionalState<'_, S>,
         block_context: &BlockContext,
-        charge_fee: bool,
-        validate: bool,
+        execution_flags: ExecutionFlags,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        let ExecutionFlags { charge_fee, validate, execute } = execution_flags;
         let account_tx_context = self.get_account_tx_context();
 
         self.verify_tx_version(account_tx_context.version())?;
 
+        if !execute {
+            return self.run_validate_only(
+                state,
+                &account_tx_context,
+                block_context,
+                validate,
+                charge_fee,
+            );
+        }
+
         // Nonce and fee check should be done before running user code.
         let strict_nonce_check = true;
         self.perform_pre_validation_stage(
@@ -613,15 +756,17 @@ impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
             final_cost: ActualCost { actual_fee: final_fee, actual_resources: final_resources },
         } = self.run_or_revert(state, &mut remaining_gas, block_context, validate, charge_fee)?;
 
-        let fee_transfer_call_info =
-            self.handle_fee(state, block_context, final_fee, charge_fee)?;
+        let mut fee_transfer_call_infos =
+            self.handle_fee(state, block_context, final_fee, charge_fee)?.into_iter();
 
         let tx_execution_info = TransactionExecutionInfo {
             validate_call_info,
             execute_call_info,
-            fee_transfer_call_info,
+            fee_transfer_call_info: fee_transfer_call_infos.next(),
+            additional_fee_transfer_call_infos: fee_transfer_call_infos.collect(),
             actual_fee: final_fee,
             actual_resources: final_resources,
+            estimated_resources: None,
             revert_error,
         };
         Ok(tx_execution_info)
@@ -3,6 +3,7 @@ use std::collections::{HashMap, HashSet};
 use assert_matches::assert_matches;
 use cairo_felt::Felt252;
 use cairo_vm::vm::runners::cairo_runner::ResourceTracker;
+use itertools::concat;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
 use starknet_api::core::{
@@ -11,8 +12,8 @@ use starknet_api::core::{
 use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
-    Calldata, ContractAddressSalt, DeclareTransactionV2, Fee, ResourceBoundsMapping,
-    TransactionHash, TransactionVersion,
+    Calldata, ContractAddressSalt, DeclareTransactionV2, Fee, Resource, ResourceBoundsMapping,
+    TransactionHash, TransactionSignature, TransactionVersion,
 };
 use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
 
@@ -20,14 +21,19 @@ use crate::abi::abi_utils::{
     get_fee_token_var_address, get_storage_var_address, selector_from_name,
 };
 use crate::abi::constants as abi_constants;
-use crate::block_context::BlockContext;
+use crate::block_context::{BlockContext, CalldataL1GasCosts, FeeRecipient, FeeSplit};
+use crate::execution::call_info::CallInfo;
 use crate::execution::contract_class::{ContractClass, ContractClassV1};
 use crate::execution::entry_point::EntryPointExecutionContext;
 use crate::execution::errors::{EntryPointExecutionError, VirtualMachineExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
-use crate::fee::fee_utils::{calculate_tx_l1_gas_usage, get_fee_by_l1_gas_usage};
+use crate::execution::syscalls::SyscallSelector;
+use crate::fee::fee_utils::{
+    calculate_tx_l1_gas_usage, get_fee_by_l1_gas_usage, l1_gas_for_messages,
+};
 use crate::fee::gas_usage::estimate_minimal_l1_gas;
 use crate::state::cached_state::CachedState;
+use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader};
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::declare::declare_tx;
@@ -40,15 +46,16 @@ use crate::test_utils::{
 };
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::constants::TRANSFER_ENTRY_POINT_NAME;
-use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::errors::{TransactionExecutionError, TransactionFeeError};
 use crate::transaction::objects::{FeeType, HasRelatedFeeType};
 use crate::transaction::test_utils::{
     account_invoke_tx, block_context, create_account_tx_for_validate_test, create_test_init_data,
     deploy_and_fund_account, l1_resource_bounds, max_fee, max_resource_bounds, run_invoke_tx,
     FaultyAccountTxCreatorArgs, TestInitData, INVALID,
 };
+use crate::transaction::transaction_execution::Transaction;
 use crate::transaction::transaction_types::TransactionType;
-use crate::transaction::transactions::{DeclareTransaction, ExecutableTransaction};
+use crate::transaction::transactions::{DeclareTransaction, ExecutableTransaction, ExecutionFlags};
 use crate::{
     check_transaction_execution_error_for_invalid_scenario, declare_tx_args,
     deploy_account_tx_args, invoke_tx_args,
@@ -106,6 +113,126 @@ fn test_enforce_fee_false_works(block_context: BlockContext, #[case] version: Tr
     assert_eq!(tx_execution_info.actual_fee, Fee(0));
 }
 
+#[rstest]
+fn test_validate_only(block_context: BlockContext, max_fee: Fee) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+    let fee_token_address = block_context.fee_token_address(&FeeType::Eth);
+    let initial_balance = state.get_fee_token_balance(account_address, fee_token_address).unwrap();
+    let initial_nonce = state.get_nonce_at(account_address).unwrap();
+    let account_tx = account_invoke_tx(invoke_tx_args! {
+        max_fee,
+        sender_address: account_address,
+        calldata: create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]),
+        version: TransactionVersion::ONE,
+        nonce: nonce_manager.next(account_address),
+    });
+
+    let execution_flags = ExecutionFlags { charge_fee: true, validate: true, execute: false };
+    let tx_execution_info =
+        account_tx.execute_with_flags(&mut state, &block_context, execution_flags).unwrap();
+    assert!(tx_execution_info.validate_call_info.is_some());
+    assert!(tx_execution_info.execute_call_info.is_none());
+    assert!(tx_execution_info.fee_transfer_call_info.is_none());
+    assert_eq!(
+        state.get_fee_token_balance(account_address, fee_token_address).unwrap(),
+        initial_balance
+    );
+    // The state is left unchanged.
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), initial_nonce);
+}
+
+#[rstest]
+fn test_skip_validate_with_invalid_signature(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let faulty_account = FeatureContract::FaultyAccount(cairo_version);
+    let state = &mut test_state(&block_context, BALANCE, &[(faulty_account, 1)]);
+    let sender_address = faulty_account.get_instance_address(0);
+    let mut nonce_manager = NonceManager::default();
+
+    // The signature makes `__validate__` fail.
+    let account_tx = create_account_tx_for_validate_test(
+        &mut nonce_manager,
+        FaultyAccountTxCreatorArgs {
+            tx_type: TransactionType::InvokeFunction,
+            scenario: INVALID,
+            sender_address,
+            class_hash: faulty_account.get_class_hash(),
+            max_fee: Fee(MAX_FEE),
+            ..Default::default()
+        },
+    );
+
+    let execution_flags = ExecutionFlags { charge_fee: true, validate: false, execute: true };
+    let tx_execution_info =
+        account_tx.execute_with_flags(state, &block_context, execution_flags).unwrap();
+    assert!(tx_execution_info.validate_call_info.is_none());
+    assert!(tx_execution_info.execute_call_info.is_some());
+    assert!(tx_execution_info.revert_error.is_none());
+    assert!(tx_execution_info.actual_fee > Fee(0));
+    // Nonce is still checked and incremented.
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce_manager.next(sender_address));
+}
+
+#[rstest]
+fn test_validate_failure_vs_execute_failure(block_context: BlockContext, max_fee: Fee) {
+    // Validation failure: rejected, nonce is not incremented.
+    let faulty_account = FeatureContract::FaultyAccount(CairoVersion::Cairo0);
+    let state = &mut test_state(&block_context, BALANCE, &[(faulty_account, 1)]);
+    let sender_address = faulty_account.get_instance_address(0);
+    let account_tx = create_account_tx_for_validate_test(
+        &mut NonceManager::default(),
+        FaultyAccountTxCreatorArgs {
+            tx_type: TransactionType::InvokeFunction,
+            scenario: INVALID,
+            sender_address,
+            class_hash: faulty_account.get_class_hash(),
+            max_fee,
+            ..Default::default()
+        },
+    );
+    let error = account_tx.execute(state, &block_context, true, true).unwrap_err();
+    assert_matches!(error, TransactionExecutionError::ValidateTransactionError(_));
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), Nonce::default());
+
+    // Execution failure: calls a non-deployed contract.
+    let TestInitData { mut state, account_address, contract_address: _, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+    let calldata = create_calldata(contract_address!(2_u8), "return_result", &[stark_felt!(2_u8)]);
+
+    // Revertible transactions are reverted and charged.
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata: calldata.clone(),
+            version: TransactionVersion::ONE,
+            nonce: nonce_manager.next(account_address),
+        },
+    )
+    .unwrap();
+    assert!(tx_execution_info.is_reverted());
+    assert!(tx_execution_info.actual_fee > Fee(0));
+
+    // Non-revertible transactions fail with a distinct error.
+    let error = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata,
+            version: TransactionVersion::ZERO,
+        },
+    )
+    .unwrap_err();
+    assert_matches!(error, TransactionExecutionError::ExecutionError(_));
+}
+
 // TODO(Dori, 15/9/2023): Convert version variance to attribute macro.
 // TODO(Dori, 10/10/2023): Add V3 case once `get_account_tx_context` is supported for V3.
 #[rstest]
@@ -198,7 +325,7 @@ fn test_infinite_recursion(
     mut block_context: BlockContext,
 ) {
     // Limit the number of execution steps (so we quickly hit the limit).
-    block_context.invoke_tx_max_n_steps = 4000;
+    block_context.chain_info_mut().invoke_tx_max_n_steps = 4000;
 
     let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
         create_test_init_data(&block_context, CairoVersion::Cairo0);
@@ -288,9 +415,8 @@ fn test_max_fee_limit_validate(
     assert_matches!(
         error,
         TransactionExecutionError::ValidateTransactionError(
-            EntryPointExecutionError::VirtualMachineExecutionErrorWithTrace { trace, .. }
+            EntryPointExecutionError::StepsExceeded { .. }
         )
-        if trace.contains("no remaining steps")
     );
 
     // Deploy grindy account successfully this time.
@@ -339,7 +465,7 @@ fn test_max_fee_limit_validate(
             max_fee: estimated_min_fee,
             resource_bounds: l1_resource_bounds(
                 estimated_min_l1_gas as u64,
-                block_context.gas_prices.get_by_fee_type(&account_tx.fee_type())
+                block_context.block_info.gas_prices.get_by_fee_type(&account_tx.fee_type())
             ),
             ..tx_args
         },
@@ -349,9 +475,8 @@ fn test_max_fee_limit_validate(
     assert_matches!(
         error,
         TransactionExecutionError::ValidateTransactionError(
-            EntryPointExecutionError::VirtualMachineExecutionErrorWithTrace { trace, .. }
+            EntryPointExecutionError::StepsExceeded { .. }
         )
-        if trace.contains("no remaining steps")
     );
 }
 
@@ -375,7 +500,7 @@ fn test_recursion_depth_exceeded(
     // reasons:
     // 1. An additional call is made initially before entering the recursion.
     // 2. The base case for recursion occurs at depth 0, not at depth 1.
-    let max_inner_recursion_depth = (block_context.max_recursion_depth - 2) as u8;
+    let max_inner_recursion_depth = (block_context.chain_info.max_recursion_depth - 2) as u8;
 
     let recursive_syscall_entry_point_name = "recursive_syscall";
     let calldata = create_calldata(
@@ -579,6 +704,44 @@ fn test_fail_declare(block_context: BlockContext, max_fee: Fee) {
     );
 }
 
+/// Tests that a declare transaction whose fee transfer fails does not leave the class declared.
+#[rstest]
+fn test_declare_fee_transfer_failure(mut block_context: BlockContext) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+
+    // Charge the fee in a "token" that does not implement `transfer`; fund the account in it, so
+    // that the pre-validation balance check passes.
+    block_context.chain_info_mut().fee_token_addresses.eth_fee_token_address = contract_address;
+    fund_account(&block_context, account_address, BALANCE, &mut state);
+
+    let empty_contract = FeatureContract::Empty(CairoVersion::Cairo0);
+    let class_hash = class_hash!(0xdeadeadeaf72_u128);
+    let declare_account_tx = declare_tx(
+        declare_tx_args! {
+            class_hash,
+            sender_address: account_address,
+            max_fee: Fee(MAX_FEE),
+            nonce: nonce_manager.next(account_address),
+        },
+        empty_contract.get_class(),
+    );
+    assert_matches!(
+        declare_account_tx.execute(&mut state, &block_context, true, true).unwrap_err(),
+        TransactionExecutionError::TransactionFeeError(
+            TransactionFeeError::ExecuteFeeTransferError(_)
+        )
+    );
+
+    // The declaration is rolled back along with the rest of the transaction.
+    assert_matches!(
+        state.get_compiled_contract_class(class_hash),
+        Err(StateError::UndeclaredClassHash(undeclared_class_hash))
+        if undeclared_class_hash == class_hash
+    );
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), Nonce::default());
+}
+
 fn recursive_function_calldata(
     contract_address: &ContractAddress,
     depth: u32,
@@ -608,7 +771,7 @@ fn test_reverted_reach_steps_limit(
         create_test_init_data(&block_context, cairo_version);
 
     // Limit the number of execution steps (so we quickly hit the limit).
-    block_context.invoke_tx_max_n_steps = 5000;
+    block_context.chain_info_mut().invoke_tx_max_n_steps = 5000;
     let recursion_base_args = invoke_tx_args! {
         max_fee,
         resource_bounds: max_resource_bounds,
@@ -627,7 +790,7 @@ fn test_reverted_reach_steps_limit(
         },
     )
     .unwrap();
-    let n_steps_0 = result.actual_resources.n_steps();
+    let n_steps_0 = result.actual_resources.n_steps().unwrap();
     let actual_fee_0 = result.actual_fee.0;
     // Ensure the transaction was not reverted.
     assert!(!result.is_reverted());
@@ -643,7 +806,7 @@ fn test_reverted_reach_steps_limit(
         },
     )
     .unwrap();
-    let n_steps_1 = result.actual_resources.n_steps();
+    let n_steps_1 = result.actual_resources.n_steps().unwrap();
     let actual_fee_1 = result.actual_fee.0;
     // Ensure the transaction was not reverted.
     assert!(!result.is_reverted());
@@ -655,7 +818,7 @@ fn test_reverted_reach_steps_limit(
     // Calculate a recursion depth where the transaction will surely fail (not a minimal depth, as
     // base costs are neglected here).
     let steps_diff = n_steps_1 - n_steps_0;
-    let fail_depth = block_context.invoke_tx_max_n_steps / (steps_diff as u32);
+    let fail_depth = block_context.chain_info.invoke_tx_max_n_steps / (steps_diff as u32);
 
     // Invoke the `recurse` function with `fail_depth` iterations. This call should fail.
     let result = run_invoke_tx(
@@ -668,7 +831,7 @@ fn test_reverted_reach_steps_limit(
         },
     )
     .unwrap();
-    let n_steps_fail = result.actual_resources.n_steps();
+    let n_steps_fail = result.actual_resources.n_steps().unwrap();
     let actual_fee_fail: u128 = result.actual_fee.0;
     // Ensure the transaction was reverted.
     assert!(result.is_reverted());
@@ -689,7 +852,7 @@ fn test_reverted_reach_steps_limit(
         },
     )
     .unwrap();
-    let n_steps_fail_next = result.actual_resources.n_steps();
+    let n_steps_fail_next = result.actual_resources.n_steps().unwrap();
     let actual_fee_fail_next: u128 = result.actual_fee.0;
     // Ensure the transaction was reverted.
     assert!(result.is_reverted());
@@ -730,7 +893,7 @@ fn test_n_reverted_steps(
     // Ensure the transaction was reverted.
     assert!(result.is_reverted());
     let mut actual_resources_0 = result.actual_resources.clone();
-    let n_steps_0 = result.actual_resources.n_steps();
+    let n_steps_0 = result.actual_resources.n_steps().unwrap();
     let actual_fee_0 = result.actual_fee.0;
 
     // Invoke the `recursive_fail` function with 1 iterations. This call should fail.
@@ -747,7 +910,7 @@ fn test_n_reverted_steps(
     // Ensure the transaction was reverted.
     assert!(result.is_reverted());
     let actual_resources_1 = result.actual_resources;
-    let n_steps_1 = actual_resources_1.n_steps();
+    let n_steps_1 = actual_resources_1.n_steps().unwrap();
     let actual_fee_1 = result.actual_fee.0;
 
     // Invoke the `recursive_fail` function with 2 iterations. This call should fail.
@@ -761,7 +924,7 @@ fn test_n_reverted_steps(
         },
     )
     .unwrap();
-    let n_steps_2 = result.actual_resources.n_steps();
+    let n_steps_2 = result.actual_resources.n_steps().unwrap();
     let actual_fee_2 = result.actual_fee.0;
     // Ensure the transaction was reverted.
     assert!(result.is_reverted());
@@ -795,7 +958,7 @@ fn test_n_reverted_steps(
         },
     )
     .unwrap();
-    let n_steps_100 = result.actual_resources.n_steps();
+    let n_steps_100 = result.actual_resources.n_steps().unwrap();
     let actual_fee_100 = result.actual_fee.0;
     // Ensure the transaction was reverted.
     assert!(result.is_reverted());
@@ -817,7 +980,7 @@ fn test_max_fee_to_max_steps_conversion(
         create_test_init_data(&block_context, CairoVersion::Cairo0);
     let actual_gas_used = 6108;
     let actual_fee = actual_gas_used as u128 * 100000000000;
-    let actual_strk_gas_price = block_context.gas_prices.get_by_fee_type(&FeeType::Strk);
+    let actual_strk_gas_price = block_context.block_info.gas_prices.get_by_fee_type(&FeeType::Strk);
     let execute_calldata = create_calldata(
         contract_address,
         "with_arg",
@@ -841,7 +1004,7 @@ fn test_max_fee_to_max_steps_conversion(
     .unwrap();
     let max_steps_limit1 = execution_context1.vm_run_resources.get_n_steps();
     let tx_execution_info1 = account_tx1.execute(&mut state, &block_context, true, true).unwrap();
-    let n_steps1 = tx_execution_info1.actual_resources.n_steps();
+    let n_steps1 = tx_execution_info1.actual_resources.n_steps().unwrap();
     let gas_used1 =
         calculate_tx_l1_gas_usage(&tx_execution_info1.actual_resources, &block_context).unwrap();
 
@@ -862,7 +1025,7 @@ fn test_max_fee_to_max_steps_conversion(
     .unwrap();
     let max_steps_limit2 = execution_context2.vm_run_resources.get_n_steps();
     let tx_execution_info2 = account_tx2.execute(&mut state, &block_context, true, true).unwrap();
-    let n_steps2 = tx_execution_info2.actual_resources.n_steps();
+    let n_steps2 = tx_execution_info2.actual_resources.n_steps().unwrap();
     let gas_used2 =
         calculate_tx_l1_gas_usage(&tx_execution_info2.actual_resources, &block_context).unwrap();
 
@@ -981,6 +1144,122 @@ fn test_deploy_account_constructor_storage_write(
     assert_eq!(ctor_storage_arg, read_storage_arg);
 }
 
+#[rstest]
+fn test_l2_gas_consumed(block_context: BlockContext, max_resource_bounds: ResourceBoundsMapping) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo1);
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            sender_address: account_address,
+            calldata: create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]),
+            version: TransactionVersion::THREE,
+            resource_bounds: max_resource_bounds.clone(),
+            nonce: nonce_manager.next(account_address),
+        },
+    )
+    .unwrap();
+    assert!(!tx_execution_info.is_reverted());
+
+    // A cheap transaction consumes a fraction of the available gas, and is charged accordingly.
+    let validate_call_info = tx_execution_info.validate_call_info.as_ref().unwrap();
+    let execute_call_info = tx_execution_info.execute_call_info.as_ref().unwrap();
+    let l2_gas_consumed = tx_execution_info.l2_gas_consumed();
+    assert_eq!(
+        l2_gas_consumed,
+        validate_call_info.execution.gas_consumed + execute_call_info.execution.gas_consumed
+    );
+    assert!(0 < l2_gas_consumed && l2_gas_consumed < Transaction::initial_gas());
+    let l1_bounds = max_resource_bounds.0[&Resource::L1Gas];
+    assert!(
+        tx_execution_info.actual_fee.0
+            < u128::from(l1_bounds.max_amount) * l1_bounds.max_price_per_unit
+    );
+}
+
+#[rstest]
+fn test_factory_deploys_child(max_fee: Fee, block_context: BlockContext) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo1);
+    let child_class_hash = FeatureContract::TestContract(CairoVersion::Cairo1).get_class_hash();
+    let salt = ContractAddressSalt(stark_felt!(7_u8));
+    let ctor_calldata = calldata![stark_felt!(3_u8), stark_felt!(4_u8)];
+
+    // The test contract acts as the factory, deploying a child of its own class.
+    let deploy_calldata = create_calldata(
+        contract_address,
+        "test_deploy",
+        &[
+            child_class_hash.0, // Class hash.
+            salt.0,             // Contract address salt.
+            stark_felt!(2_u8),  // Constructor calldata length.
+            ctor_calldata.0[0], // Constructor calldata.
+            ctor_calldata.0[1],
+            stark_felt!(0_u8), // Deploy from zero.
+        ],
+    );
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata: deploy_calldata,
+            nonce: nonce_manager.next(account_address),
+        },
+    )
+    .unwrap();
+    assert!(!tx_execution_info.is_reverted());
+
+    // The child address is derived from the factory address, and its constructor runs within the
+    // transaction.
+    let child_address =
+        calculate_contract_address(salt, child_class_hash, &ctor_calldata, contract_address)
+            .unwrap();
+    let factory_call = &tx_execution_info.execute_call_info.unwrap().inner_calls[0];
+    let constructor_call = &factory_call.inner_calls[0];
+    assert_eq!(constructor_call.call.storage_address, child_address);
+    assert_eq!(constructor_call.call.caller_address, contract_address);
+    assert_eq!(state.get_class_hash_at(child_address).unwrap(), child_class_hash);
+}
+
+#[rstest]
+fn test_l2_to_l1_messages_l1_gas(max_fee: Fee, block_context: BlockContext) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo1);
+    let mut execute_and_get_gas_usage = |entry_point_name: &str, calldata: &[StarkFelt]| {
+        let account_tx = account_invoke_tx(invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata: create_calldata(contract_address, entry_point_name, calldata),
+            nonce: nonce_manager.next(account_address),
+        });
+        let tx_execution_info = account_tx.execute(&mut state, &block_context, true, true).unwrap();
+        assert!(!tx_execution_info.is_reverted());
+        (tx_execution_info.actual_resources.0[abi_constants::GAS_USAGE], tx_execution_info)
+    };
+
+    // Both transactions modify the same number of storage cells (nonce and fee balances).
+    let (gas_usage_without_message, _) = execute_and_get_gas_usage(
+        "test_emit_event",
+        &[stark_felt!(0_u8), stark_felt!(0_u8)], // Calldata: keys, data (empty arrays).
+    );
+    let (gas_usage_with_message, tx_execution_info) = execute_and_get_gas_usage(
+        "test_send_message_to_l1",
+        // Calldata: to_address, payload length, payload.
+        &[stark_felt!(1234_u16), stark_felt!(2_u8), stark_felt!(12_u8), stark_felt!(34_u8)],
+    );
+
+    let messages = tx_execution_info.ordered_l2_to_l1_messages();
+    assert_eq!(messages.len(), 1);
+    assert!(gas_usage_with_message > gas_usage_without_message);
+    assert_eq!(
+        (gas_usage_with_message - gas_usage_without_message) as u64,
+        l1_gas_for_messages(&messages)
+    );
+}
+
 // Test for counting actual storage changes.
 #[rstest]
 #[case(TransactionVersion::ONE, FeeType::Eth)]
@@ -1005,7 +1284,10 @@ fn test_count_actual_storage_changes(
     let mut nonce_manager = NonceManager::default();
 
     let initial_sequencer_balance = stark_felt_to_felt(
-        state.get_fee_token_balance(block_context.sequencer_address, fee_token_address).unwrap().0,
+        state
+            .get_fee_token_balance(block_context.block_info.sequencer_address, fee_token_address)
+            .unwrap()
+            .0,
     );
 
     // Calldata types.
@@ -1021,6 +1303,7 @@ fn test_count_actual_storage_changes(
 
     // Run transactions; using transactional state to count only storage changes of the current
     // transaction.
+    let execution_flags = ExecutionFlags { charge_fee: true, validate: true, execute: true };
     // First transaction: storage cell value changes from 0 to 1.
     let mut state = CachedState::create_transactional(&mut state);
     let invoke_args = invoke_tx_args! {
@@ -1032,7 +1315,8 @@ fn test_count_actual_storage_changes(
         nonce: nonce_manager.next(account_address),
     };
     let account_tx = account_invoke_tx(invoke_args.clone());
-    let execution_info = account_tx.execute_raw(&mut state, &block_context, true, true).unwrap();
+    let execution_info =
+        account_tx.execute_raw(&mut state, &block_context, execution_flags).unwrap();
 
     let fee_1 = execution_info.actual_fee;
     let storage_updates_1 = &state
@@ -1045,7 +1329,7 @@ fn test_count_actual_storage_changes(
         ((fee_token_address, get_fee_token_var_address(account_address)), stark_felt!(0_u8));
     let mut expected_sequencer_total_fee = initial_sequencer_balance + Felt252::from(fee_1.0);
     let mut expected_sequencer_fee_update = (
-        (fee_token_address, get_fee_token_var_address(block_context.sequencer_address)),
+        (fee_token_address, get_fee_token_var_address(block_context.block_info.sequencer_address)),
         felt_to_stark_felt(&expected_sequencer_total_fee),
     );
 
@@ -1065,7 +1349,8 @@ fn test_count_actual_storage_changes(
         nonce: nonce_manager.next(account_address),
         ..invoke_args.clone()
     });
-    let execution_info = account_tx.execute_raw(&mut state, &block_context, true, true).unwrap();
+    let execution_info =
+        account_tx.execute_raw(&mut state, &block_context, execution_flags).unwrap();
 
     let fee_2 = execution_info.actual_fee;
     let storage_updates_2 = &state
@@ -1089,7 +1374,8 @@ fn test_count_actual_storage_changes(
         calldata: transfer_calldata,
         ..invoke_args
     });
-    let execution_info = account_tx.execute_raw(&mut state, &block_context, true, true).unwrap();
+    let execution_info =
+        account_tx.execute_raw(&mut state, &block_context, execution_flags).unwrap();
 
     let fee_transfer = execution_info.actual_fee;
     let storage_updates_transfer = &state
@@ -1113,3 +1399,212 @@ fn test_count_actual_storage_changes(
     assert_eq!(expected_modified_contracts_transfer, storage_updates_transfer.modified_contracts);
     assert_eq!(expected_storage_update_transfer, storage_updates_transfer.storage_updates);
 }
+
+#[rstest]
+#[case::sequencer(FeeRecipient::Sequencer)]
+#[case::burn(FeeRecipient::Burn(contract_address!("0x7a57e")))]
+#[case::split(FeeRecipient::Split(
+    FeeSplit::new(vec![(contract_address!("0x5117"), 70), (contract_address!("0x5118"), 30)])
+        .unwrap()
+))]
+fn test_fee_recipient(
+    max_fee: Fee,
+    mut block_context: BlockContext,
+    #[case] fee_recipient: FeeRecipient,
+) {
+    block_context.chain_info_mut().fee_recipient = fee_recipient;
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+    let fee_token_address = block_context.fee_token_address(&FeeType::Eth);
+
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata: create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]),
+            nonce: nonce_manager.next(account_address),
+        },
+    )
+    .unwrap();
+    let actual_fee = tx_execution_info.actual_fee;
+
+    // Each recipient is paid in a separate, top-level transfer.
+    let fee_transfers = block_context.fee_transfers(actual_fee);
+    let fee_transfer_call_infos: Vec<&CallInfo> = tx_execution_info
+        .fee_transfer_call_info
+        .iter()
+        .chain(&tx_execution_info.additional_fee_transfer_call_infos)
+        .collect();
+    assert_eq!(fee_transfer_call_infos.len(), fee_transfers.len());
+    assert_eq!(fee_transfers.iter().map(|(_, amount)| amount.0).sum::<u128>(), actual_fee.0);
+    for ((recipient, amount), call_info) in fee_transfers.into_iter().zip(fee_transfer_call_infos) {
+        assert_eq!(call_info.call.calldata.0[0], *recipient.0.key());
+        assert!(call_info.inner_calls.is_empty());
+        assert_eq!(
+            state.get_fee_token_balance(recipient, fee_token_address).unwrap(),
+            (stark_felt!(amount.0), stark_felt!(0_u8))
+        );
+    }
+}
+
+/// Tests that the fee of a transaction covers the OS resources of its additional fee transfers.
+#[rstest]
+fn test_additional_fee_transfers_resources(max_fee: Fee, block_context: BlockContext) {
+    let n_steps_with_fee_recipient = |fee_recipient: FeeRecipient| {
+        let mut block_context = block_context.clone();
+        block_context.chain_info_mut().fee_recipient = fee_recipient;
+        let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+            create_test_init_data(&block_context, CairoVersion::Cairo0);
+        let tx_execution_info = run_invoke_tx(
+            &mut state,
+            &block_context,
+            invoke_tx_args! {
+                max_fee,
+                sender_address: account_address,
+                calldata: create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]),
+                nonce: nonce_manager.next(account_address),
+            },
+        )
+        .unwrap();
+        tx_execution_info.actual_resources.0[abi_constants::N_STEPS_RESOURCE]
+    };
+
+    let split = FeeSplit::new(vec![
+        (contract_address!("0x5117"), 50),
+        (contract_address!("0x5118"), 30),
+        (contract_address!("0x5119"), 20),
+    ])
+    .unwrap();
+    let call_contract_n_steps = block_context
+        .versioned_constants
+        .os_resources
+        .resources_for_syscall(&SyscallSelector::CallContract)
+        .n_steps;
+    assert_eq!(
+        n_steps_with_fee_recipient(FeeRecipient::Split(split)),
+        n_steps_with_fee_recipient(FeeRecipient::Sequencer) + 2 * call_contract_n_steps
+    );
+}
+
+/// Tests that a chain with zero gas prices executes transactions for free, without a fee transfer.
+#[rstest]
+fn test_zero_gas_price(
+    mut block_context: BlockContext,
+    max_fee: Fee,
+    #[values(TransactionVersion::ONE, TransactionVersion::THREE)] version: TransactionVersion,
+) {
+    block_context.block_info.gas_prices.eth_l1_gas_price = 0;
+    block_context.block_info.gas_prices.strk_l1_gas_price = 0;
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata: create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]),
+            version,
+            nonce: nonce_manager.next(account_address),
+        },
+    )
+    .unwrap();
+
+    assert!(!tx_execution_info.is_reverted());
+    assert_eq!(tx_execution_info.actual_fee, Fee(0));
+    assert!(tx_execution_info.fee_transfer_call_info.is_none());
+}
+
+#[rstest]
+fn test_calldata_and_signature_l1_gas(max_fee: Fee, mut block_context: BlockContext) {
+    let calldata_l1_gas_costs = CalldataL1GasCosts { per_calldata_felt: 16, per_signature_felt: 8 };
+    block_context.chain_info_mut().calldata_l1_gas_costs = calldata_l1_gas_costs;
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo1);
+    let mut execute = |event_data: Vec<StarkFelt>, signature_length: usize| {
+        // Calldata: keys, data.
+        let calldata =
+            concat(vec![vec![stark_felt!(0_u8), stark_felt!(event_data.len() as u64)], event_data]);
+        let tx_execution_info = run_invoke_tx(
+            &mut state,
+            &block_context,
+            invoke_tx_args! {
+                max_fee,
+                sender_address: account_address,
+                calldata: create_calldata(contract_address, "test_emit_event", &calldata),
+                // The account does not validate the signature.
+                signature: TransactionSignature(vec![stark_felt!(1_u8); signature_length]),
+                nonce: nonce_manager.next(account_address),
+            },
+        )
+        .unwrap();
+        assert!(!tx_execution_info.is_reverted());
+        (
+            tx_execution_info.actual_resources.0[abi_constants::GAS_USAGE],
+            tx_execution_info.actual_fee,
+        )
+    };
+
+    // All transactions modify the same storage cells (nonce and fee balances).
+    let (short_gas_usage, short_fee) = execute(vec![], 0);
+    let (long_calldata_gas_usage, long_calldata_fee) = execute(vec![stark_felt!(7_u8); 10], 0);
+    let (long_signature_gas_usage, long_signature_fee) = execute(vec![], 10);
+
+    assert_eq!(
+        long_calldata_gas_usage - short_gas_usage,
+        10 * calldata_l1_gas_costs.per_calldata_felt
+    );
+    assert!(long_calldata_fee > short_fee);
+    assert_eq!(
+        long_signature_gas_usage - short_gas_usage,
+        10 * calldata_l1_gas_costs.per_signature_felt
+    );
+    assert!(long_signature_fee > short_fee);
+}
+
+#[rstest]
+#[case::within_limit(3, false)]
+#[case::exceeding_limit(2, true)]
+fn test_max_storage_writes(
+    max_fee: Fee,
+    mut block_context: BlockContext,
+    #[case] max_storage_writes: usize,
+    #[case] is_reverted: bool,
+) {
+    block_context.chain_info_mut().max_storage_writes = Some(max_storage_writes);
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context, CairoVersion::Cairo0);
+
+    // Write to 3 storage slots.
+    let n_writes = stark_felt!(3_u8);
+    let tx_execution_info = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            max_fee,
+            sender_address: account_address,
+            calldata: create_calldata(
+                contract_address,
+                "write_a_lot",
+                &[n_writes, stark_felt!(1_u8)]
+            ),
+            version: TransactionVersion::ONE,
+            nonce: nonce_manager.next(account_address),
+        },
+    )
+    .unwrap();
+
+    assert_eq!(tx_execution_info.is_reverted(), is_reverted);
+    if is_reverted {
+        assert!(tx_execution_info
+            .revert_error
+            .unwrap()
+            .contains("modified 3 storage slots, exceeding the maximum: 2."));
+    }
+    let written_value =
+        state.get_storage_at(contract_address, StorageKey(patricia_key!(3_u8))).unwrap();
+    assert_eq!(written_value, if is_reverted { stark_felt!(0_u8) } else { stark_felt!(1_u8) });
+}
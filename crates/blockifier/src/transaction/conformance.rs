@@ -0,0 +1,122 @@
+use std::collections::BTreeSet;
+
+use crate::transaction::objects::TransactionExecutionInfo;
+
+#[cfg(test)]
+#[path = "conformance_test.rs"]
+mod test;
+
+/// The default relative tolerance for resource usage comparison.
+pub const RESOURCES_RELATIVE_TOLERANCE: f64 = 0.01;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum MismatchCategory {
+    Events,
+    Fee,
+    Messages,
+    Resources,
+    RevertStatus,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    pub category: MismatchCategory,
+    pub description: String,
+}
+
+/// The differences between two execution results of the same transaction, e.g., as computed by
+/// two different clients.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ConformanceReport {
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ConformanceReport {
+    pub fn is_conformant(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn mismatched_categories(&self) -> BTreeSet<MismatchCategory> {
+        self.mismatches.iter().map(|mismatch| mismatch.category).collect()
+    }
+
+    pub fn has_mismatch(&self, category: MismatchCategory) -> bool {
+        self.mismatches.iter().any(|mismatch| mismatch.category == category)
+    }
+
+    fn add(&mut self, category: MismatchCategory, description: String) {
+        self.mismatches.push(Mismatch { category, description });
+    }
+}
+
+impl TransactionExecutionInfo {
+    /// Compares this execution result against another one, allowing resource usages to differ by
+    /// [RESOURCES_RELATIVE_TOLERANCE].
+    pub fn conformance_report(&self, other: &Self) -> ConformanceReport {
+        self.conformance_report_with_tolerance(other, RESOURCES_RELATIVE_TOLERANCE)
+    }
+
+    /// Compares this execution result against another one; resource usages may differ by the given
+    /// fraction of the larger of the two.
+    pub fn conformance_report_with_tolerance(
+        &self,
+        other: &Self,
+        resources_relative_tolerance: f64,
+    ) -> ConformanceReport {
+        let mut report = ConformanceReport::default();
+
+        if self.actual_fee != other.actual_fee {
+            report.add(
+                MismatchCategory::Fee,
+                format!("Actual fee: {} != {}.", self.actual_fee.0, other.actual_fee.0),
+            );
+        }
+
+        let resource_names: BTreeSet<&String> =
+            self.actual_resources.0.keys().chain(other.actual_resources.0.keys()).collect();
+        for resource_name in resource_names {
+            let usage = self.actual_resources.get_or_zero(resource_name);
+            let other_usage = other.actual_resources.get_or_zero(resource_name);
+            let tolerance = resources_relative_tolerance * usage.max(other_usage) as f64;
+            if usage.abs_diff(other_usage) as f64 > tolerance {
+                report.add(
+                    MismatchCategory::Resources,
+                    format!("Resource {resource_name}: {usage} != {other_usage}."),
+                );
+            }
+        }
+
+        let (events, other_events) = (self.ordered_events(), other.ordered_events());
+        if events != other_events {
+            let description = match events.iter().zip(&other_events).position(|(a, b)| a != b) {
+                Some(index) => format!("Events differ at index {index}."),
+                None => format!("Number of events: {} != {}.", events.len(), other_events.len()),
+            };
+            report.add(MismatchCategory::Events, description);
+        }
+
+        let (messages, other_messages) =
+            (self.ordered_l2_to_l1_messages(), other.ordered_l2_to_l1_messages());
+        if messages != other_messages {
+            let description = match messages.iter().zip(&other_messages).position(|(a, b)| a != b) {
+                Some(index) => format!("L2-to-L1 messages differ at index {index}."),
+                None => format!(
+                    "Number of L2-to-L1 messages: {} != {}.",
+                    messages.len(),
+                    other_messages.len()
+                ),
+            };
+            report.add(MismatchCategory::Messages, description);
+        }
+
+        // Revert reasons are client-specific; only the status is compared.
+        if self.is_reverted() != other.is_reverted() {
+            report.add(
+                MismatchCategory::RevertStatus,
+                format!("Reverted: {} != {}.", self.is_reverted(), other.is_reverted()),
+            );
+        }
+
+        report
+    }
+}
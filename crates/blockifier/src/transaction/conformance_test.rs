@@ -0,0 +1,66 @@
+use std::collections::{BTreeSet, HashMap};
+
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::{EventContent, EventData, EventKey, Fee};
+
+use crate::execution::call_info::{CallExecution, CallInfo, OrderedEvent};
+use crate::transaction::conformance::MismatchCategory;
+use crate::transaction::objects::{ResourcesMapping, TransactionExecutionInfo};
+
+fn execution_info_with_events(events: &[(usize, u8)]) -> TransactionExecutionInfo {
+    let events = events
+        .iter()
+        .map(|&(order, key)| OrderedEvent {
+            order,
+            event: EventContent { keys: vec![EventKey(stark_felt!(key))], data: EventData(vec![]) },
+        })
+        .collect();
+
+    TransactionExecutionInfo {
+        execute_call_info: Some(CallInfo {
+            execution: CallExecution { events, ..Default::default() },
+            ..Default::default()
+        }),
+        actual_fee: Fee(100),
+        actual_resources: ResourcesMapping(HashMap::from([
+            ("l1_gas_usage".to_string(), 1000),
+            ("n_steps".to_string(), 500),
+        ])),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_conformance_report_event_order() {
+    let execution_info = execution_info_with_events(&[(0, 1), (1, 2)]);
+    assert!(execution_info
+        .conformance_report(&execution_info_with_events(&[(0, 1), (1, 2)]))
+        .is_conformant());
+
+    // Same events, emitted in a different order.
+    let other_execution_info = execution_info_with_events(&[(1, 1), (0, 2)]);
+    let report = execution_info.conformance_report(&other_execution_info);
+    assert_eq!(report.mismatched_categories(), BTreeSet::from([MismatchCategory::Events]));
+}
+
+#[test]
+fn test_conformance_report_resources_tolerance() {
+    let execution_info = execution_info_with_events(&[]);
+    let mut other_execution_info = execution_info_with_events(&[]);
+    other_execution_info.actual_resources.0.insert("n_steps".to_string(), 503);
+    assert!(execution_info.conformance_report(&other_execution_info).is_conformant());
+
+    other_execution_info.actual_resources.0.insert("n_steps".to_string(), 510);
+    other_execution_info.actual_fee = Fee(101);
+    other_execution_info.revert_error = Some("Reverted.".to_string());
+    let report = execution_info.conformance_report(&other_execution_info);
+    assert_eq!(
+        report.mismatched_categories(),
+        BTreeSet::from([
+            MismatchCategory::Fee,
+            MismatchCategory::Resources,
+            MismatchCategory::RevertStatus
+        ])
+    );
+}
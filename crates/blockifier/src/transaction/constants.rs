@@ -1,3 +1,5 @@
+use crate::execution::contract_class::CompilerVersion;
+
 pub const EXECUTE_ENTRY_POINT_NAME: &str = "__execute__";
 pub const TRANSFER_ENTRY_POINT_NAME: &str = "transfer";
 pub const VALIDATE_ENTRY_POINT_NAME: &str = "__validate__";
@@ -6,6 +8,19 @@ pub const VALIDATE_DEPLOY_ENTRY_POINT_NAME: &str = "__validate_deploy__";
 
 pub const TRANSFER_EVENT_NAME: &str = "Transfer";
 
+// Declared class limits, as published in the Starknet "Limits and triggers" documentation.
+pub const MAX_CONTRACT_CLASS_SIZE: usize = 4_089_446;
+pub const MAX_BYTECODE_LENGTH: usize = 81_290;
+pub const MIN_SUPPORTED_COMPILER_VERSION: CompilerVersion =
+    CompilerVersion { major: 1, minor: 0, patch: 0 };
+// Compared on (major, minor) only: any 2.5 patch version is supported.
+pub const MAX_SUPPORTED_COMPILER_VERSION: CompilerVersion =
+    CompilerVersion { major: 2, minor: 5, patch: 0 };
+
+// Emitted output limits (in felts), per event / message.
+pub const MAX_EVENT_DATA_SIZE: usize = 300;
+pub const MAX_L1_MESSAGE_PAYLOAD_SIZE: usize = 300;
+
 // Cairo constants.
 pub const FELT_FALSE: u64 = 0;
 pub const FELT_TRUE: u64 = 1;
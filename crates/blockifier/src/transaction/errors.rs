@@ -1,13 +1,26 @@
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize, Serializer};
 use starknet_api::core::{ClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::{Fee, TransactionVersion};
 use starknet_api::StarknetApiError;
+use strum_macros::IntoStaticStr;
 use thiserror::Error;
 
 use crate::execution::call_info::Retdata;
+use crate::execution::contract_class::CompilerVersion;
 use crate::execution::errors::EntryPointExecutionError;
 use crate::fee::fee_checks::FeeCheckError;
 use crate::state::errors::StateError;
+use crate::transaction::objects::ResourceName;
+use crate::utils::format_felt_hex;
+
+#[cfg(test)]
+#[path = "errors_test.rs"]
+pub mod test;
 
 #[derive(Debug, Error)]
 pub enum TransactionFeeError {
@@ -17,6 +30,8 @@ pub enum TransactionFeeError {
     ExecuteFeeTransferError(#[from] EntryPointExecutionError),
     #[error("Actual fee ({actual_fee:?}) exceeded max fee ({max_fee:?}).")]
     FeeTransferError { max_fee: Fee, actual_fee: Fee },
+    #[error("Fee ({fee:?}) with a margin of {margin_percent}% overflows.")]
+    FeeWithMarginOverflow { fee: Fee, margin_percent: u8 },
     #[error("Actual fee ({actual_fee:?}) exceeded paid fee on L1 ({paid_fee:?}).")]
     InsufficientL1Fee { paid_fee: Fee, actual_fee: Fee },
     #[error(
@@ -45,12 +60,26 @@ pub enum TransactionFeeError {
     MaxL1GasAmountTooLow { max_l1_gas_amount: u64, minimal_l1_gas_amount: u64 },
     #[error("Missing L1 gas bounds in resource bounds.")]
     MissingL1GasBounds,
+    #[error("Resource {} is missing from the resources mapping.", resource.name())]
+    MissingResource { resource: ResourceName },
     #[error(transparent)]
     StateError(#[from] StateError),
+    #[error("Unknown resource {name} in the resources mapping.")]
+    UnknownResource { name: String },
 }
 
-#[derive(Debug, Error)]
+#[derive(Debug, Error, IntoStaticStr)]
 pub enum TransactionExecutionError {
+    #[error(
+        "Contract class bytecode length ({bytecode_length} felts) exceeds the maximal bytecode \
+         length: {max_bytecode_length} felts."
+    )]
+    BytecodeLengthTooLarge { bytecode_length: usize, max_bytecode_length: usize },
+    #[error(
+        "Contract class size ({class_size} bytes) exceeds the maximal class size: \
+         {max_class_size} bytes."
+    )]
+    ContractClassSizeTooLarge { class_size: usize, max_class_size: usize },
     #[error(
         "Declare transaction version {declare_version:?} must have a contract class of Cairo \
          version {cairo_version:?}."
@@ -58,8 +87,10 @@ pub enum TransactionExecutionError {
     ContractClassVersionMismatch { declare_version: TransactionVersion, cairo_version: u64 },
     #[error("Contract constructor execution has failed: {0}")]
     ContractConstructorExecutionFailed(#[source] EntryPointExecutionError),
-    #[error("Class with hash {class_hash:?} is already declared.")]
+    #[error("Class with hash {} is already declared.", format_felt_hex(&class_hash.0))]
     DeclareTransactionError { class_hash: ClassHash },
+    /// The `__execute__` phase of a non-revertible transaction failed; revertible transactions
+    /// report such failures as a (fee-charged) revert instead.
     #[error("Transaction execution has failed: {0}")]
     ExecutionError(#[source] EntryPointExecutionError),
     #[error(transparent)]
@@ -80,23 +111,86 @@ pub enum TransactionExecutionError {
     StarknetApiError(#[from] StarknetApiError),
     #[error(transparent)]
     StateError(#[from] StateError),
+    #[error(
+        "Transaction execution modified {n_storage_writes} storage slots, exceeding the maximum: \
+         {max_storage_writes}."
+    )]
+    TooManyStorageWrites { n_storage_writes: usize, max_storage_writes: usize },
     #[error(transparent)]
     TransactionFeeError(#[from] TransactionFeeError),
     #[error(transparent)]
     TransactionPreValidationError(#[from] TransactionPreValidationError),
     #[error("Unexpected holes in the {object} order. No object with the order: {order}.")]
     UnexpectedHoles { object: String, order: usize },
+    #[error(
+        "Compiler version {compiler_version} is not supported. Supported versions: \
+         {supported_compiler_versions:?}."
+    )]
+    UnsupportedCompilerVersion {
+        compiler_version: String,
+        supported_compiler_versions: RangeInclusive<CompilerVersion>,
+    },
+    /// The `__validate__` phase failed; the transaction is rejected and no fee is charged.
     #[error("Transaction validation has failed: {0}")]
     ValidateTransactionError(#[source] EntryPointExecutionError),
 }
 
+/// Serializes as a [TransactionExecutionErrorRecord], since the underlying errors are not
+/// serializable.
+impl Serialize for TransactionExecutionError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TransactionExecutionErrorRecord::from(self).serialize(serializer)
+    }
+}
+
+/// A structural, serializable snapshot of a [TransactionExecutionError], which displays as the
+/// original error did; e.g., to store revert reasons and re-display them across node versions.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionExecutionErrorRecord {
+    /// The name of the error variant; e.g., `ValidateTransactionError`.
+    pub variant: String,
+    /// The display of the error.
+    pub message: String,
+    /// The displays of the underlying errors, outermost first.
+    pub sources: Vec<String>,
+}
+
+impl From<&TransactionExecutionError> for TransactionExecutionErrorRecord {
+    fn from(error: &TransactionExecutionError) -> Self {
+        let variant: &'static str = error.into();
+        let mut sources = Vec::new();
+        let mut source = error.source();
+        while let Some(current_source) = source {
+            sources.push(current_source.to_string());
+            source = current_source.source();
+        }
+
+        Self { variant: variant.to_string(), message: error.to_string(), sources }
+    }
+}
+
+impl Display for TransactionExecutionErrorRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum TransactionPreValidationError {
+    /// The transaction nonce is ahead of the account nonce (a future transaction).
+    #[error(
+        "Invalid transaction nonce of contract at address {}. Account nonce: {account_nonce:?}; \
+         got: {incoming_tx_nonce:?}, which is too high.",
+        format_felt_hex(address.0.key())
+    )]
+    NonceTooHigh { address: ContractAddress, account_nonce: Nonce, incoming_tx_nonce: Nonce },
+    /// The transaction nonce was already used by the account (a replay).
     #[error(
-        "Invalid transaction nonce of contract at address {address:?}. Account nonce: \
-         {account_nonce:?}; got: {incoming_tx_nonce:?}."
+        "Invalid transaction nonce of contract at address {}. Account nonce: {account_nonce:?}; \
+         got: {incoming_tx_nonce:?}, which was already used.",
+        format_felt_hex(address.0.key())
     )]
-    InvalidNonce { address: ContractAddress, account_nonce: Nonce, incoming_tx_nonce: Nonce },
+    NonceTooLow { address: ContractAddress, account_nonce: Nonce, incoming_tx_nonce: Nonce },
     #[error(transparent)]
     StateError(#[from] StateError),
     #[error(transparent)]
@@ -0,0 +1,35 @@
+use pretty_assertions::assert_eq;
+use starknet_api::core::ClassHash;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::{class_hash, stark_felt};
+
+use crate::execution::errors::EntryPointExecutionError;
+use crate::transaction::errors::{TransactionExecutionError, TransactionExecutionErrorRecord};
+
+fn assert_round_trip(error: TransactionExecutionError) -> TransactionExecutionErrorRecord {
+    let serialized = serde_json::to_string(&error).unwrap();
+    let record: TransactionExecutionErrorRecord = serde_json::from_str(&serialized).unwrap();
+
+    assert_eq!(record, TransactionExecutionErrorRecord::from(&error));
+    assert_eq!(record.to_string(), error.to_string());
+    record
+}
+
+#[test]
+fn test_error_record_round_trip() {
+    let record = assert_round_trip(TransactionExecutionError::DeclareTransactionError {
+        class_hash: class_hash!("0x1234"),
+    });
+    assert_eq!(record.variant, "DeclareTransactionError");
+    assert!(record.sources.is_empty());
+
+    let record = assert_round_trip(TransactionExecutionError::ValidateTransactionError(
+        EntryPointExecutionError::ExecutionFailed { error_data: vec![stark_felt!("0x1")] },
+    ));
+    assert_eq!(record.variant, "ValidateTransactionError");
+    assert_eq!(
+        record.sources,
+        vec![EntryPointExecutionError::ExecutionFailed { error_data: vec![stark_felt!("0x1")] }
+            .to_string()]
+    );
+}
@@ -1,16 +1,28 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use cairo_felt::Felt252;
+use cairo_vm::vm::runners::builtin_runner::{
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+    OUTPUT_BUILTIN_NAME, POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME,
+    SEGMENT_ARENA_BUILTIN_NAME, SIGNATURE_BUILTIN_NAME,
+};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use indexmap::IndexMap;
 use itertools::concat;
 use num_traits::Pow;
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use serde::{Deserialize, Deserializer, Serialize};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::hash::{pedersen_hash_array, StarkFelt};
 use starknet_api::transaction::{
-    AccountDeploymentData, Fee, PaymasterData, Resource, ResourceBounds, ResourceBoundsMapping,
-    Tip, TransactionHash, TransactionSignature, TransactionVersion,
+    AccountDeploymentData, Event, Fee, MessageToL1, PaymasterData, Resource, ResourceBounds,
+    ResourceBoundsMapping, Tip, TransactionHash, TransactionSignature, TransactionVersion,
 };
+use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
+use crate::abi::abi_utils::starknet_keccak;
+use crate::abi::constants as abi_constants;
 use crate::block_context::BlockContext;
 use crate::execution::call_info::CallInfo;
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
@@ -21,6 +33,10 @@ use crate::transaction::errors::{
     TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
 };
 
+#[cfg(test)]
+#[path = "objects_test.rs"]
+mod test;
+
 pub type TransactionExecutionResult<T> = Result<T, TransactionExecutionError>;
 pub type TransactionFeeResult<T> = Result<T, TransactionFeeError>;
 pub type TransactionPreValidationResult<T> = Result<T, TransactionPreValidationError>;
@@ -42,6 +58,33 @@ pub enum FeeType {
     Eth,
 }
 
+/// The unit a fee is given in: the smallest denomination of the fee token.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FeeUnit {
+    // Of ETH.
+    Wei,
+    // Of STRK.
+    Fri,
+}
+
+impl From<FeeType> for FeeUnit {
+    fn from(fee_type: FeeType) -> Self {
+        match fee_type {
+            FeeType::Eth => Self::Wei,
+            FeeType::Strk => Self::Fri,
+        }
+    }
+}
+
+/// A fee, along with the unit and the L1 gas price it was calculated with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FeeResult {
+    pub fee: Fee,
+    pub unit: FeeUnit,
+    // In units of `unit` per L1 gas.
+    pub gas_price: u128,
+}
+
 /// Contains the account information of the transaction (outermost call).
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum AccountTransactionContext {
@@ -65,6 +108,15 @@ impl AccountTransactionContext {
         }
     }
 
+    /// Checks the transaction signature using the given verifier, without running the account's
+    /// `__validate__` entry point; e.g., for off-chain validation.
+    pub fn verify_signature_with(
+        &self,
+        verifier: impl Fn(&TransactionHash, &TransactionSignature) -> bool,
+    ) -> bool {
+        verifier(&self.transaction_hash(), &self.signature())
+    }
+
     pub fn is_v0(&self) -> bool {
         self.version() == TransactionVersion::ZERO
     }
@@ -81,12 +133,20 @@ impl AccountTransactionContext {
     }
 
     pub fn enforce_fee(&self) -> TransactionFeeResult<bool> {
+        Ok(self.committed_fee()? != Fee(0))
+    }
+
+    /// Returns the maximal fee the sender committed to pay: the L1 gas resource bounds for
+    /// transactions of version 3 and above, and `max_fee` for older ones.
+    pub fn committed_fee(&self) -> TransactionFeeResult<Fee> {
         match self {
             AccountTransactionContext::Current(context) => {
                 let l1_bounds = context.l1_resource_bounds()?;
-                Ok(l1_bounds.max_amount as u128 * l1_bounds.max_price_per_unit > 0)
+                // Sender will not be charged by `max_price_per_unit`, but the cap should not
+                // depend on the current gas price.
+                Ok(Fee(l1_bounds.max_amount as u128 * l1_bounds.max_price_per_unit))
             }
-            AccountTransactionContext::Deprecated(context) => Ok(context.max_fee != Fee(0)),
+            AccountTransactionContext::Deprecated(context) => Ok(context.max_fee),
         }
     }
 }
@@ -147,23 +207,58 @@ pub struct TransactionExecutionInfo {
     pub execute_call_info: Option<CallInfo>,
     /// Fee transfer call info; [None] for `L1Handler`.
     pub fee_transfer_call_info: Option<CallInfo>,
+    /// Call infos of the fee transfers following the first, for chains dividing the fee between
+    /// several recipients (see [crate::block_context::FeeSplit]); empty otherwise.
+    pub additional_fee_transfer_call_infos: Vec<CallInfo>,
     /// The actual fee that was charged (in Wei).
     pub actual_fee: Fee,
     /// Actual execution resources the transaction is charged for,
     /// including L1 gas and additional OS resources estimation.
     pub actual_resources: ResourcesMapping,
+    /// Resources the transaction was estimated to consume prior to its execution, if such an
+    /// estimation was made; not set by the blockifier.
+    pub estimated_resources: Option<ResourcesMapping>,
     /// Error string for reverted transactions; [None] if transaction execution was successful.
     // TODO(Dori, 1/8/2023): If the `Eq` and `PartialEq` traits are removed, or implemented on all
     //   internal structs in this enum, this field should be `Option<TransactionExecutionError>`.
     pub revert_error: Option<String>,
 }
 
+/// The aggregate figures of a transaction execution; see [TransactionExecutionInfo::summary].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TransactionExecutionSummary {
+    pub actual_fee: Fee,
+    pub n_steps: usize,
+    pub l1_gas_usage: usize,
+    pub l2_gas_consumed: u64,
+    pub n_events: usize,
+    pub n_l2_to_l1_messages: usize,
+    pub reverted: bool,
+}
+
+/// A transaction execution as a flat record of columns of portable types (e.g., for Arrow); see
+/// [TransactionExecutionInfo::to_execution_row].
+#[cfg(feature = "execution-row")]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TransactionExecutionRow {
+    // A decimal string; columnar formats such as Arrow have no unsigned 128-bit integer type.
+    pub actual_fee: String,
+    pub n_steps: u64,
+    pub l1_gas_usage: u64,
+    pub l2_gas_consumed: u64,
+    pub n_events: u64,
+    pub n_l2_to_l1_messages: u64,
+    pub reverted: bool,
+    pub revert_error: Option<String>,
+}
+
 impl TransactionExecutionInfo {
     pub fn non_optional_call_infos(&self) -> impl Iterator<Item = &CallInfo> {
         self.validate_call_info
             .iter()
             .chain(self.execute_call_info.iter())
             .chain(self.fee_transfer_call_info.iter())
+            .chain(self.additional_fee_transfer_call_infos.iter())
     }
 
     /// Returns the set of class hashes that were executed during this transaction execution.
@@ -183,22 +278,473 @@ impl TransactionExecutionInfo {
     pub fn is_reverted(&self) -> bool {
         self.revert_error.is_some()
     }
+
+    /// Returns the L2 (Sierra) gas consumed by the validation and execution of this transaction,
+    /// which share the gas available to the transaction; the fee transfer is not metered against
+    /// it. Cairo 0 calls do not consume L2 gas.
+    pub fn l2_gas_consumed(&self) -> u64 {
+        self.validate_call_info
+            .iter()
+            .chain(self.execute_call_info.iter())
+            .map(|call_info| call_info.execution.gas_consumed)
+            .sum()
+    }
+
+    /// Returns the aggregate figures of this transaction execution.
+    pub fn summary(&self) -> TransactionExecutionSummary {
+        TransactionExecutionSummary {
+            actual_fee: self.actual_fee,
+            n_steps: self.actual_resources.get_or_zero(abi_constants::N_STEPS_RESOURCE),
+            l1_gas_usage: self.actual_resources.get_or_zero(abi_constants::GAS_USAGE),
+            l2_gas_consumed: self.l2_gas_consumed(),
+            n_events: self.n_events(),
+            n_l2_to_l1_messages: self.n_l2_to_l1_messages(),
+            reverted: self.is_reverted(),
+        }
+    }
+
+    /// Returns this transaction execution as a flat record, for columnar (e.g., Parquet) writers.
+    #[cfg(feature = "execution-row")]
+    pub fn to_execution_row(&self) -> TransactionExecutionRow {
+        let summary = self.summary();
+        // Counts beyond 64 bits (on wider platforms) saturate.
+        let to_u64 = |count: usize| u64::try_from(count).unwrap_or(u64::MAX);
+        TransactionExecutionRow {
+            actual_fee: summary.actual_fee.0.to_string(),
+            n_steps: to_u64(summary.n_steps),
+            l1_gas_usage: to_u64(summary.l1_gas_usage),
+            l2_gas_consumed: summary.l2_gas_consumed,
+            n_events: to_u64(summary.n_events),
+            n_l2_to_l1_messages: to_u64(summary.n_l2_to_l1_messages),
+            reverted: summary.reverted,
+            revert_error: self.revert_error.clone(),
+        }
+    }
+
+    /// Returns the ratio of actual to estimated Cairo steps; [None] if no estimation is set or if
+    /// either resource mapping lacks a (nonzero, for the estimation) step count.
+    pub fn resource_estimation_accuracy(&self) -> Option<f64> {
+        let estimated_resources = self.estimated_resources.as_ref()?;
+        let estimated_n_steps = *estimated_resources.0.get(abi_constants::N_STEPS_RESOURCE)?;
+        if estimated_n_steps == 0 {
+            return None;
+        }
+        let actual_n_steps = *self.actual_resources.0.get(abi_constants::N_STEPS_RESOURCE)?;
+
+        Some(actual_n_steps as f64 / estimated_n_steps as f64)
+    }
+
+    /// Returns the VM resources consumed by each contract during this transaction execution, in
+    /// order of first invocation. Each call is attributed its own resources only (excluding those
+    /// of its inner calls), under its storage address.
+    pub fn resources_by_contract(&self) -> IndexMap<ContractAddress, VmExecutionResources> {
+        let mut resources_by_contract = IndexMap::<_, VmExecutionResources>::new();
+        for call_info in self.non_optional_call_infos().flat_map(|call_info| call_info.into_iter())
+        {
+            let own_resources = call_info
+                .inner_calls
+                .iter()
+                .fold(call_info.vm_resources.clone(), |resources, inner_call| {
+                    &resources - &inner_call.vm_resources
+                });
+            *resources_by_contract.entry(call_info.call.storage_address).or_default() +=
+                &own_resources;
+        }
+
+        resources_by_contract
+    }
+
+    /// Returns the L2-to-L1 messages sent during this transaction execution, in canonical order:
+    /// by execution phase (validation, execution, fee transfer), and within each phase by the
+    /// order in which they were sent.
+    pub fn ordered_l2_to_l1_messages(&self) -> Vec<MessageToL1> {
+        self.non_optional_call_infos()
+            .flat_map(|call_info| {
+                let mut phase_messages: Vec<_> = call_info
+                    .into_iter()
+                    .flat_map(|inner_call| {
+                        let from_address = inner_call.call.storage_address;
+                        inner_call
+                            .execution
+                            .l2_to_l1_messages
+                            .iter()
+                            .map(move |ordered_message| (from_address, ordered_message))
+                    })
+                    .collect();
+                phase_messages.sort_by_key(|(_, ordered_message)| ordered_message.order);
+
+                phase_messages.into_iter().map(|(from_address, ordered_message)| MessageToL1 {
+                    from_address,
+                    to_address: ordered_message.message.to_address,
+                    payload: ordered_message.message.payload.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the events emitted during this transaction execution, in canonical order: by
+    /// execution phase, and within each phase by emission order. An event is attributed to the
+    /// storage address of the emitting call; i.e., events emitted by library calls are attributed
+    /// to the calling contract.
+    pub fn ordered_events(&self) -> Vec<Event> {
+        self.non_optional_call_infos()
+            .flat_map(|call_info| {
+                let mut phase_events: Vec<_> = call_info
+                    .into_iter()
+                    .flat_map(|inner_call| {
+                        let from_address = inner_call.call.storage_address;
+                        inner_call
+                            .execution
+                            .events
+                            .iter()
+                            .map(move |ordered_event| (from_address, ordered_event))
+                    })
+                    .collect();
+                phase_events.sort_by_key(|(_, ordered_event)| ordered_event.order);
+
+                phase_events.into_iter().map(|(from_address, ordered_event)| Event {
+                    from_address,
+                    content: ordered_event.event.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the number of events emitted during this transaction execution.
+    pub fn n_events(&self) -> usize {
+        self.non_optional_call_infos()
+            .flat_map(|call_info| call_info.into_iter())
+            .map(|call_info| call_info.execution.events.len())
+            .sum()
+    }
+
+    /// Returns the number of L2-to-L1 messages sent during this transaction execution.
+    pub fn n_l2_to_l1_messages(&self) -> usize {
+        self.non_optional_call_infos()
+            .flat_map(|call_info| call_info.into_iter())
+            .map(|call_info| call_info.execution.l2_to_l1_messages.len())
+            .sum()
+    }
+
+    /// Returns the number of felts in the keys and data of all events emitted during this
+    /// transaction execution; used for data availability accounting.
+    pub fn event_da_size(&self) -> u64 {
+        self.non_optional_call_infos()
+            .flat_map(|call_info| call_info.into_iter())
+            .flat_map(|call_info| call_info.execution.events.iter())
+            .map(|ordered_event| {
+                let event = &ordered_event.event;
+                (event.keys.len() + event.data.0.len()) as u64
+            })
+            .sum()
+    }
+
+    /// Returns a deterministic hash over the fee, the resources and the call trees of this
+    /// execution; equal execution results hash equally.
+    pub fn content_hash(&self) -> StarkFelt {
+        let mut data = vec![StarkFelt::from(self.actual_fee.0)];
+
+        // Hash resources in canonical (sorted) order.
+        let mut resources: Vec<_> = self.actual_resources.0.iter().collect();
+        resources.sort();
+        data.push(StarkFelt::from(resources.len() as u64));
+        for (name, usage) in resources {
+            data.push(felt_to_stark_felt(&starknet_keccak(name.as_bytes())));
+            data.push(StarkFelt::from(*usage as u64));
+        }
+
+        data.push(StarkFelt::from(u8::from(self.is_reverted())));
+        for call_info in
+            [&self.validate_call_info, &self.execute_call_info, &self.fee_transfer_call_info]
+        {
+            match call_info {
+                Some(call_info) => call_info_content(call_info, &mut data),
+                None => data.push(StarkFelt::default()),
+            }
+        }
+        for call_info in &self.additional_fee_transfer_call_infos {
+            call_info_content(call_info, &mut data);
+        }
+
+        pedersen_hash_array(&data)
+    }
+
+    /// Flattens the call trees of this transaction execution into parallel columns, with a row
+    /// per call, in execution phase order and pre-order within each phase.
+    pub fn to_columns(&self) -> CallColumns {
+        let mut columns = CallColumns::default();
+        for call_info in self.non_optional_call_infos() {
+            // Pop order is the reverse of push order.
+            let mut stack = vec![(call_info, 0)];
+            while let Some((call_info, depth)) = stack.pop() {
+                columns.addresses.push(call_info.call.storage_address);
+                columns.selectors.push(call_info.call.entry_point_selector);
+                columns.gas_consumed.push(call_info.execution.gas_consumed);
+                columns.depths.push(depth);
+                columns.succeeded.push(!call_info.execution.failed);
+                stack.extend(call_info.inner_calls.iter().rev().map(|inner| (inner, depth + 1)));
+            }
+        }
+
+        columns
+    }
+}
+
+/// The calls of a transaction execution in columnar form, for analytics; the i-th entries of all
+/// columns describe the same call. Depth is zero for the calls made directly by the protocol.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct CallColumns {
+    pub addresses: Vec<ContractAddress>,
+    pub selectors: Vec<EntryPointSelector>,
+    pub gas_consumed: Vec<u64>,
+    pub depths: Vec<usize>,
+    pub succeeded: Vec<bool>,
+}
+
+impl CallColumns {
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty()
+    }
+}
+
+/// Appends the content of the given call tree to the given data, in pre-order.
+fn call_info_content(call_info: &CallInfo, data: &mut Vec<StarkFelt>) {
+    for call_info in call_info.into_iter() {
+        let call = &call_info.call;
+        let execution = &call_info.execution;
+        data.extend([
+            call.class_hash.unwrap_or_default().0,
+            *call.storage_address.0.key(),
+            call.entry_point_selector.0,
+            StarkFelt::from(call.calldata.0.len() as u64),
+        ]);
+        data.extend(call.calldata.0.iter());
+        data.push(StarkFelt::from(execution.retdata.0.len() as u64));
+        data.extend(execution.retdata.0.iter());
+        data.push(StarkFelt::from(execution.events.len() as u64));
+        for ordered_event in &execution.events {
+            let event = &ordered_event.event;
+            data.push(StarkFelt::from(ordered_event.order as u64));
+            data.push(StarkFelt::from(event.keys.len() as u64));
+            data.extend(event.keys.iter().map(|key| key.0));
+            data.push(StarkFelt::from(event.data.0.len() as u64));
+            data.extend(event.data.0.iter());
+        }
+        data.push(StarkFelt::from(execution.l2_to_l1_messages.len() as u64));
+        for ordered_message in &execution.l2_to_l1_messages {
+            data.push(StarkFelt::from(ordered_message.order as u64));
+            data.push(StarkFelt::from(ordered_message.message.payload.0.len() as u64));
+            data.extend(ordered_message.message.payload.0.iter());
+        }
+        data.extend([
+            StarkFelt::from(u8::from(execution.failed)),
+            StarkFelt::from(execution.gas_consumed),
+            StarkFelt::from(call_info.inner_calls.len() as u64),
+        ]);
+    }
+}
+
+/// The known transaction execution resources, as keyed in [ResourcesMapping].
+#[derive(Clone, Copy, Debug, EnumIter, Eq, PartialEq)]
+pub enum ResourceName {
+    L1GasUsage,
+    NSteps,
+    Bitwise,
+    EcOp,
+    Ecdsa,
+    Keccak,
+    Output,
+    Pedersen,
+    Poseidon,
+    RangeCheck,
+    SegmentArena,
+}
+
+impl ResourceName {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::L1GasUsage => abi_constants::GAS_USAGE,
+            Self::NSteps => abi_constants::N_STEPS_RESOURCE,
+            Self::Bitwise => BITWISE_BUILTIN_NAME,
+            Self::EcOp => EC_OP_BUILTIN_NAME,
+            Self::Ecdsa => SIGNATURE_BUILTIN_NAME,
+            Self::Keccak => KECCAK_BUILTIN_NAME,
+            Self::Output => OUTPUT_BUILTIN_NAME,
+            Self::Pedersen => HASH_BUILTIN_NAME,
+            Self::Poseidon => POSEIDON_BUILTIN_NAME,
+            Self::RangeCheck => RANGE_CHECK_BUILTIN_NAME,
+            Self::SegmentArena => SEGMENT_ARENA_BUILTIN_NAME,
+        }
+    }
 }
 
 /// A mapping from a transaction execution resource to its actual usage.
+/// Serializes as a JSON object; deserializes from either an object or an array of
+/// `{name, value}` pairs.
 #[cfg_attr(test, derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(transparent)]
 pub struct ResourcesMapping(pub HashMap<String, usize>);
 
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SerializedResourcesMapping {
+    Object(HashMap<String, usize>),
+    Array(Vec<SerializedResource>),
+}
+
+#[derive(Deserialize)]
+struct SerializedResource {
+    name: String,
+    value: usize,
+}
+
+impl<'de> Deserialize<'de> for ResourcesMapping {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let resources = match SerializedResourcesMapping::deserialize(deserializer)? {
+            SerializedResourcesMapping::Object(resources) => resources,
+            SerializedResourcesMapping::Array(resources) => {
+                let mut mapping = HashMap::with_capacity(resources.len());
+                for SerializedResource { name, value } in resources {
+                    if mapping.contains_key(&name) {
+                        return Err(serde::de::Error::custom(format!(
+                            "Duplicate resource {name}."
+                        )));
+                    }
+                    mapping.insert(name, value);
+                }
+                mapping
+            }
+        };
+
+        Ok(Self(resources))
+    }
+}
+
 impl ResourcesMapping {
-    #[cfg(test)]
-    pub fn n_steps(&self) -> usize {
-        *self.0.get(crate::abi::constants::N_STEPS_RESOURCE).unwrap()
+    /// Returns the usage of the given resource; fails if it is missing from the mapping.
+    pub fn get(&self, resource: ResourceName) -> TransactionFeeResult<usize> {
+        self.0
+            .get(resource.name())
+            .copied()
+            .ok_or(TransactionFeeError::MissingResource { resource })
     }
 
-    #[cfg(test)]
-    pub fn gas_usage(&self) -> usize {
-        *self.0.get(crate::abi::constants::GAS_USAGE).unwrap()
+    /// Returns the usage under the given key, or zero if it is missing from the mapping.
+    pub fn get_or_zero(&self, key: &str) -> usize {
+        self.0.get(key).copied().unwrap_or_default()
+    }
+
+    pub fn l1_gas_usage(&self) -> TransactionFeeResult<usize> {
+        self.get(ResourceName::L1GasUsage)
+    }
+
+    pub fn n_steps(&self) -> TransactionFeeResult<usize> {
+        self.get(ResourceName::NSteps)
+    }
+
+    /// Returns the number of instances of the given builtin; unused builtins are omitted from
+    /// the mapping, hence count as zero.
+    pub fn builtin(&self, name: &str) -> usize {
+        self.get_or_zero(name)
+    }
+
+    /// Returns the entries of the mapping, sorted by key; a canonical form, independent of the
+    /// iteration order of the underlying map.
+    pub fn sorted(&self) -> BTreeMap<String, usize> {
+        self.0.iter().map(|(key, usage)| (key.clone(), *usage)).collect()
+    }
+}
+
+/// The known transaction execution resources as named fields; an allocation-free alternative to
+/// [ResourcesMapping] for hot paths. Note that memory holes are already counted as steps.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FixedResources {
+    pub l1_gas_usage: usize,
+    pub n_steps: usize,
+    pub bitwise: usize,
+    pub ec_op: usize,
+    pub ecdsa: usize,
+    pub keccak: usize,
+    pub output: usize,
+    pub pedersen: usize,
+    pub poseidon: usize,
+    pub range_check: usize,
+    pub segment_arena: usize,
+}
+
+impl FixedResources {
+    pub fn get(&self, resource: ResourceName) -> usize {
+        *self.field(resource)
+    }
+
+    pub fn set(&mut self, resource: ResourceName, usage: usize) {
+        *self.field_mut(resource) = usage;
+    }
+
+    fn field(&self, resource: ResourceName) -> &usize {
+        match resource {
+            ResourceName::L1GasUsage => &self.l1_gas_usage,
+            ResourceName::NSteps => &self.n_steps,
+            ResourceName::Bitwise => &self.bitwise,
+            ResourceName::EcOp => &self.ec_op,
+            ResourceName::Ecdsa => &self.ecdsa,
+            ResourceName::Keccak => &self.keccak,
+            ResourceName::Output => &self.output,
+            ResourceName::Pedersen => &self.pedersen,
+            ResourceName::Poseidon => &self.poseidon,
+            ResourceName::RangeCheck => &self.range_check,
+            ResourceName::SegmentArena => &self.segment_arena,
+        }
+    }
+
+    fn field_mut(&mut self, resource: ResourceName) -> &mut usize {
+        match resource {
+            ResourceName::L1GasUsage => &mut self.l1_gas_usage,
+            ResourceName::NSteps => &mut self.n_steps,
+            ResourceName::Bitwise => &mut self.bitwise,
+            ResourceName::EcOp => &mut self.ec_op,
+            ResourceName::Ecdsa => &mut self.ecdsa,
+            ResourceName::Keccak => &mut self.keccak,
+            ResourceName::Output => &mut self.output,
+            ResourceName::Pedersen => &mut self.pedersen,
+            ResourceName::Poseidon => &mut self.poseidon,
+            ResourceName::RangeCheck => &mut self.range_check,
+            ResourceName::SegmentArena => &mut self.segment_arena,
+        }
+    }
+}
+
+impl TryFrom<&ResourcesMapping> for FixedResources {
+    type Error = TransactionFeeError;
+
+    fn try_from(resources: &ResourcesMapping) -> TransactionFeeResult<Self> {
+        let mut fixed_resources = Self::default();
+        for (name, usage) in &resources.0 {
+            let resource = ResourceName::iter()
+                .find(|resource| resource.name() == name)
+                .ok_or_else(|| TransactionFeeError::UnknownResource { name: name.clone() })?;
+            fixed_resources.set(resource, *usage);
+        }
+
+        Ok(fixed_resources)
+    }
+}
+
+impl From<&FixedResources> for ResourcesMapping {
+    /// As in execution outputs, unused builtins are omitted.
+    fn from(fixed_resources: &FixedResources) -> Self {
+        let resources = ResourceName::iter().filter_map(|resource| {
+            let usage = fixed_resources.get(resource);
+            let is_builtin = !matches!(resource, ResourceName::L1GasUsage | ResourceName::NSteps);
+            (!is_builtin || usage > 0).then(|| (resource.name().to_string(), usage))
+        });
+
+        Self(resources.collect())
     }
 }
 
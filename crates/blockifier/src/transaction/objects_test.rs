@@ -0,0 +1,426 @@
+use std::collections::{BTreeMap, HashMap};
+
+use assert_matches::assert_matches;
+use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use rstest::rstest;
+use starknet_api::core::{ContractAddress, EntryPointSelector, EthAddress, PatriciaKey};
+use starknet_api::data_availability::DataAvailabilityMode;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{
+    AccountDeploymentData, EventContent, EventData, EventKey, Fee, L2ToL1Payload,
+    MessageToL1 as StarknetApiMessageToL1, PaymasterData, ResourceBoundsMapping, Tip,
+    TransactionHash, TransactionSignature,
+};
+use starknet_api::{contract_address, patricia_key, stark_felt};
+use strum::IntoEnumIterator;
+
+use crate::abi::constants as abi_constants;
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message,
+};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::transaction::errors::TransactionFeeError;
+use crate::transaction::objects::{
+    AccountTransactionContext, CallColumns, CommonAccountFields, CurrentAccountTransactionContext,
+    DeprecatedAccountTransactionContext, FixedResources, ResourceName, ResourcesMapping,
+    TransactionExecutionInfo, TransactionExecutionSummary,
+};
+use crate::transaction::test_utils::l1_resource_bounds;
+
+fn event_with_sizes(order: usize, n_keys: u8, n_data: u8) -> OrderedEvent {
+    OrderedEvent {
+        order,
+        event: EventContent {
+            keys: (0..n_keys).map(|i| EventKey(stark_felt!(i))).collect(),
+            data: EventData((0..n_data).map(|i| stark_felt!(i)).collect()),
+        },
+    }
+}
+
+#[test]
+fn test_event_da_size() {
+    let inner_call = CallInfo {
+        execution: CallExecution { events: vec![event_with_sizes(1, 2, 3)], ..Default::default() },
+        ..Default::default()
+    };
+    let execute_call_info = CallInfo {
+        execution: CallExecution { events: vec![event_with_sizes(0, 1, 4)], ..Default::default() },
+        inner_calls: vec![inner_call],
+        ..Default::default()
+    };
+    let tx_execution_info = TransactionExecutionInfo {
+        execute_call_info: Some(execute_call_info),
+        ..Default::default()
+    };
+
+    assert_eq!(tx_execution_info.event_da_size(), 10);
+    assert_eq!(TransactionExecutionInfo::default().event_da_size(), 0);
+}
+
+#[test]
+fn test_content_hash() {
+    let create_execution_info = |actual_fee: Fee| TransactionExecutionInfo {
+        execute_call_info: Some(CallInfo {
+            execution: CallExecution {
+                events: vec![event_with_sizes(0, 1, 2)],
+                ..Default::default()
+            },
+            inner_calls: vec![CallInfo::default()],
+            ..Default::default()
+        }),
+        actual_fee,
+        actual_resources: ResourcesMapping(HashMap::from([
+            (String::from("n_steps"), 100),
+            (String::from("l1_gas_usage"), 20),
+            (String::from("range_check_builtin"), 3),
+        ])),
+        ..Default::default()
+    };
+
+    let tx_execution_info = create_execution_info(Fee(10));
+    assert_eq!(tx_execution_info.content_hash(), create_execution_info(Fee(10)).content_hash());
+    assert_ne!(tx_execution_info.content_hash(), create_execution_info(Fee(11)).content_hash());
+}
+
+fn call_info_with_messages(
+    address: &str,
+    payloads_by_order: &[(usize, u8)],
+    inner_calls: Vec<CallInfo>,
+) -> CallInfo {
+    let l2_to_l1_messages = payloads_by_order
+        .iter()
+        .map(|&(order, payload)| OrderedL2ToL1Message {
+            order,
+            message: MessageToL1 {
+                to_address: EthAddress::try_from(stark_felt!(85_u8)).unwrap(),
+                payload: L2ToL1Payload(vec![stark_felt!(payload)]),
+            },
+        })
+        .collect();
+    CallInfo {
+        call: CallEntryPoint { storage_address: contract_address!(address), ..Default::default() },
+        execution: CallExecution { l2_to_l1_messages, ..Default::default() },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_ordered_l2_to_l1_messages() {
+    // The contract sends its first message from an inner call, and its second one after it
+    // returns; the validation phase has its own message order.
+    let inner_call = call_info_with_messages("0x2", &[(0, 10)], vec![]);
+    let execute_call_info = call_info_with_messages("0x1", &[(1, 11)], vec![inner_call]);
+    let validate_call_info = call_info_with_messages("0x1", &[(0, 1)], vec![]);
+    let tx_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(validate_call_info),
+        execute_call_info: Some(execute_call_info),
+        ..Default::default()
+    };
+
+    let expected_message = |from_address: &str, payload: u8| StarknetApiMessageToL1 {
+        from_address: contract_address!(from_address),
+        to_address: EthAddress::try_from(stark_felt!(85_u8)).unwrap(),
+        payload: L2ToL1Payload(vec![stark_felt!(payload)]),
+    };
+    assert_eq!(
+        tx_execution_info.ordered_l2_to_l1_messages(),
+        vec![expected_message("0x1", 1), expected_message("0x2", 10), expected_message("0x1", 11)]
+    );
+}
+
+#[test]
+fn test_to_columns() {
+    // The account (0x1) calls 0x2, which calls 0x3 and fails; the account then calls 0x3.
+    let mut failed_call = call_info_with_messages("0x3", &[], vec![]);
+    failed_call.execution.failed = true;
+    let inner_call = call_info_with_messages("0x2", &[], vec![failed_call]);
+    let mut execute_call_info = call_info_with_messages(
+        "0x1",
+        &[],
+        vec![inner_call, call_info_with_messages("0x3", &[], vec![])],
+    );
+    execute_call_info.execution.gas_consumed = 100;
+    let tx_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(call_info_with_messages("0x1", &[], vec![])),
+        execute_call_info: Some(execute_call_info),
+        fee_transfer_call_info: Some(call_info_with_messages("0x4", &[], vec![])),
+        ..Default::default()
+    };
+
+    let columns = tx_execution_info.to_columns();
+    let n_calls: usize = tx_execution_info
+        .non_optional_call_infos()
+        .map(|call_info| call_info.into_iter().count())
+        .sum();
+    assert_eq!(columns.len(), n_calls);
+    for column_len in [
+        columns.selectors.len(),
+        columns.gas_consumed.len(),
+        columns.depths.len(),
+        columns.succeeded.len(),
+    ] {
+        assert_eq!(column_len, n_calls);
+    }
+
+    let addresses = ["0x1", "0x1", "0x2", "0x3", "0x3", "0x4"];
+    assert_eq!(
+        columns,
+        CallColumns {
+            addresses: addresses.into_iter().map(|address| contract_address!(address)).collect(),
+            selectors: vec![EntryPointSelector::default(); n_calls],
+            gas_consumed: vec![0, 100, 0, 0, 0, 0],
+            depths: vec![0, 0, 1, 2, 1, 0],
+            succeeded: vec![true, true, true, false, true, true],
+        }
+    );
+}
+
+#[test]
+fn test_resources_by_contract() {
+    let vm_resources = |n_steps: usize, n_range_checks: usize| VmExecutionResources {
+        n_steps,
+        n_memory_holes: 0,
+        builtin_instance_counter: HashMap::from([(
+            RANGE_CHECK_BUILTIN_NAME.to_string(),
+            n_range_checks,
+        )]),
+    };
+    let call_info = |address: &str, resources: VmExecutionResources, inner_calls| CallInfo {
+        call: CallEntryPoint { storage_address: contract_address!(address), ..Default::default() },
+        vm_resources: resources,
+        inner_calls,
+        ..Default::default()
+    };
+
+    // The account (0x1) calls the contract (0x2) twice; resources include those of inner calls.
+    let execute_call_info = call_info(
+        "0x1",
+        vm_resources(100, 10),
+        vec![
+            call_info("0x2", vm_resources(30, 2), vec![]),
+            call_info("0x2", vm_resources(20, 3), vec![]),
+        ],
+    );
+    let validate_call_info = call_info("0x1", vm_resources(7, 1), vec![]);
+    let tx_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(validate_call_info),
+        execute_call_info: Some(execute_call_info),
+        ..Default::default()
+    };
+
+    let resources_by_contract = tx_execution_info.resources_by_contract();
+    assert_eq!(
+        resources_by_contract.keys().collect::<Vec<_>>(),
+        vec![&contract_address!("0x1"), &contract_address!("0x2")]
+    );
+    assert_eq!(resources_by_contract[&contract_address!("0x1")], vm_resources(57, 6));
+    assert_eq!(resources_by_contract[&contract_address!("0x2")], vm_resources(50, 5));
+}
+
+#[test]
+fn test_n_events_and_messages() {
+    let mut inner_call = call_info_with_messages("0x2", &[(0, 10), (2, 12)], vec![]);
+    inner_call.execution.events = vec![event_with_sizes(1, 1, 1), event_with_sizes(2, 1, 1)];
+    let mut execute_call_info = call_info_with_messages("0x1", &[(1, 11)], vec![inner_call]);
+    execute_call_info.execution.events = vec![event_with_sizes(0, 1, 1)];
+    let mut fee_transfer_call_info = CallInfo::default();
+    fee_transfer_call_info.execution.events = vec![event_with_sizes(0, 1, 3)];
+    let tx_execution_info = TransactionExecutionInfo {
+        execute_call_info: Some(execute_call_info),
+        fee_transfer_call_info: Some(fee_transfer_call_info),
+        ..Default::default()
+    };
+
+    assert_eq!(tx_execution_info.n_events(), 4);
+    assert_eq!(tx_execution_info.n_l2_to_l1_messages(), 3);
+    assert_eq!(TransactionExecutionInfo::default().n_events(), 0);
+    assert_eq!(TransactionExecutionInfo::default().n_l2_to_l1_messages(), 0);
+}
+
+fn sample_execution_info() -> TransactionExecutionInfo {
+    let mut execute_call_info = call_info_with_messages("0x1", &[(0, 10)], vec![]);
+    execute_call_info.execution.events = vec![event_with_sizes(0, 1, 1), event_with_sizes(1, 1, 1)];
+    execute_call_info.execution.gas_consumed = 100;
+    TransactionExecutionInfo {
+        execute_call_info: Some(execute_call_info),
+        actual_fee: Fee(1000),
+        actual_resources: ResourcesMapping(HashMap::from([
+            (abi_constants::N_STEPS_RESOURCE.to_string(), 500),
+            (abi_constants::GAS_USAGE.to_string(), 20),
+        ])),
+        revert_error: Some(String::from("Execution failed.")),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_summary() {
+    assert_eq!(
+        sample_execution_info().summary(),
+        TransactionExecutionSummary {
+            actual_fee: Fee(1000),
+            n_steps: 500,
+            l1_gas_usage: 20,
+            l2_gas_consumed: 100,
+            n_events: 2,
+            n_l2_to_l1_messages: 1,
+            reverted: true,
+        }
+    );
+}
+
+#[cfg(feature = "execution-row")]
+#[test]
+fn test_to_execution_row() {
+    let tx_execution_info = sample_execution_info();
+    let summary = tx_execution_info.summary();
+    let row = tx_execution_info.to_execution_row();
+
+    assert_eq!(row.actual_fee, summary.actual_fee.0.to_string());
+    assert_eq!(row.n_steps, summary.n_steps as u64);
+    assert_eq!(row.l1_gas_usage, summary.l1_gas_usage as u64);
+    assert_eq!(row.l2_gas_consumed, summary.l2_gas_consumed);
+    assert_eq!(row.n_events, summary.n_events as u64);
+    assert_eq!(row.n_l2_to_l1_messages, summary.n_l2_to_l1_messages as u64);
+    assert_eq!(row.reverted, summary.reverted);
+    assert_eq!(row.revert_error, tx_execution_info.revert_error);
+}
+
+#[test]
+fn test_resource_estimation_accuracy() {
+    let n_steps_mapping = |n_steps: usize| {
+        ResourcesMapping(HashMap::from([
+            (abi_constants::N_STEPS_RESOURCE.to_string(), n_steps),
+            (abi_constants::GAS_USAGE.to_string(), 1000),
+        ]))
+    };
+    let execution_info = |estimated_resources: Option<ResourcesMapping>| TransactionExecutionInfo {
+        actual_resources: n_steps_mapping(150),
+        estimated_resources,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        execution_info(Some(n_steps_mapping(100))).resource_estimation_accuracy(),
+        Some(1.5)
+    );
+    assert_eq!(
+        execution_info(Some(n_steps_mapping(300))).resource_estimation_accuracy(),
+        Some(0.5)
+    );
+    assert_eq!(execution_info(None).resource_estimation_accuracy(), None);
+    assert_eq!(execution_info(Some(n_steps_mapping(0))).resource_estimation_accuracy(), None);
+}
+
+#[test]
+fn test_fixed_resources_round_trip() {
+    let resources = ResourcesMapping(
+        ResourceName::iter()
+            .enumerate()
+            .map(|(i, resource)| (resource.name().to_string(), i + 1))
+            .collect(),
+    );
+    let fixed_resources = FixedResources::try_from(&resources).unwrap();
+    assert_eq!(fixed_resources.l1_gas_usage, 1);
+    assert_eq!(
+        fixed_resources.get(ResourceName::RangeCheck),
+        resources.builtin(RANGE_CHECK_BUILTIN_NAME)
+    );
+    assert_eq!(ResourcesMapping::from(&fixed_resources), resources);
+
+    // Unused builtins are omitted.
+    let fixed_resources = FixedResources { n_steps: 7, pedersen: 2, ..Default::default() };
+    assert_eq!(
+        ResourcesMapping::from(&fixed_resources),
+        ResourcesMapping(HashMap::from([
+            (ResourceName::L1GasUsage.name().to_string(), 0),
+            (ResourceName::NSteps.name().to_string(), 7),
+            (ResourceName::Pedersen.name().to_string(), 2),
+        ]))
+    );
+
+    let unknown_resources = ResourcesMapping(HashMap::from([(String::from("bad_resource"), 1)]));
+    assert_matches!(
+        FixedResources::try_from(&unknown_resources).unwrap_err(),
+        TransactionFeeError::UnknownResource { name } if name == "bad_resource"
+    );
+}
+
+fn current_account_tx_context(resource_bounds: ResourceBoundsMapping) -> AccountTransactionContext {
+    AccountTransactionContext::Current(CurrentAccountTransactionContext {
+        common_fields: CommonAccountFields::default(),
+        resource_bounds,
+        tip: Tip::default(),
+        nonce_data_availability_mode: DataAvailabilityMode::L1,
+        fee_data_availability_mode: DataAvailabilityMode::L1,
+        paymaster_data: PaymasterData::default(),
+        account_deployment_data: AccountDeploymentData::default(),
+    })
+}
+
+#[test]
+fn test_committed_fee() {
+    // Version 3: the cap is derived from the L1 gas resource bounds.
+    let context = current_account_tx_context(l1_resource_bounds(1000, 7));
+    assert_eq!(context.committed_fee().unwrap(), Fee(7000));
+    assert!(context.enforce_fee().unwrap());
+
+    let context = current_account_tx_context(l1_resource_bounds(0, 7));
+    assert_eq!(context.committed_fee().unwrap(), Fee(0));
+    assert!(!context.enforce_fee().unwrap());
+
+    let context = current_account_tx_context(ResourceBoundsMapping(BTreeMap::new()));
+    assert_matches!(context.committed_fee().unwrap_err(), TransactionFeeError::MissingL1GasBounds);
+
+    // Older versions: the cap is the max fee.
+    let context = AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext {
+        max_fee: Fee(1234),
+        ..Default::default()
+    });
+    assert_eq!(context.committed_fee().unwrap(), Fee(1234));
+    assert!(context.enforce_fee().unwrap());
+}
+
+#[test]
+fn test_verify_signature_with() {
+    let transaction_hash = TransactionHash(stark_felt!(7_u8));
+    let signature = TransactionSignature(vec![stark_felt!(1_u8), stark_felt!(2_u8)]);
+    let context = AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext {
+        common_fields: CommonAccountFields {
+            transaction_hash,
+            signature: signature.clone(),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+
+    // The verifier gets the transaction hash and signature, and its verdict is passed through.
+    assert!(
+        context.verify_signature_with(|hash, sig| *hash == transaction_hash && *sig == signature)
+    );
+    assert!(!context.verify_signature_with(|_hash, _sig| false));
+}
+
+#[rstest]
+#[case::object(r#"{"n_steps": 100, "l1_gas_usage": 7}"#)]
+#[case::array(r#"[{"name": "n_steps", "value": 100}, {"name": "l1_gas_usage", "value": 7}]"#)]
+fn test_resources_mapping_deserialization(#[case] serialized: &str) {
+    let expected = ResourcesMapping(HashMap::from([
+        (String::from("n_steps"), 100),
+        (String::from("l1_gas_usage"), 7),
+    ]));
+    let resources: ResourcesMapping = serde_json::from_str(serialized).unwrap();
+    assert_eq!(resources, expected);
+
+    // Serialization always produces the object form.
+    let serialized_resources = serde_json::to_value(&resources).unwrap();
+    assert_eq!(serialized_resources, serde_json::json!({"n_steps": 100, "l1_gas_usage": 7}));
+}
+
+#[test]
+fn test_resources_mapping_deserialization_rejects_duplicates() {
+    let serialized = r#"[{"name": "n_steps", "value": 1}, {"name": "n_steps", "value": 2}]"#;
+    assert!(serde_json::from_str::<ResourcesMapping>(serialized).is_err());
+}
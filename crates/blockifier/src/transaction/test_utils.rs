@@ -153,8 +153,9 @@ pub fn create_account_tx_test_state(
     // A random address that is unlikely to equal the result of the calculation of a contract
     // address.
     let test_account_address = contract_address!(account_address);
-    let test_strk_token_address = block_context.fee_token_addresses.strk_fee_token_address;
-    let test_eth_token_address = block_context.fee_token_addresses.eth_fee_token_address;
+    let test_strk_token_address =
+        block_context.chain_info.fee_token_addresses.strk_fee_token_address;
+    let test_eth_token_address = block_context.chain_info.fee_token_addresses.eth_fee_token_address;
     let address_to_class_hash = HashMap::from([
         (test_contract_address, test_contract_class_hash),
         (test_account_address, test_account_class_hash),
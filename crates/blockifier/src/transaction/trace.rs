@@ -0,0 +1,297 @@
+use cairo_vm::vm::runners::builtin_runner::{
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+    POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME, SEGMENT_ARENA_BUILTIN_NAME,
+    SIGNATURE_BUILTIN_NAME,
+};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use serde::Serialize;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::StarkFelt;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::abi::constants::CONSTRUCTOR_ENTRY_POINT_NAME;
+use crate::execution::call_info::{CallInfo, OrderedEvent};
+use crate::execution::entry_point::CallType;
+use crate::execution::execution_utils::stark_felt_to_felt;
+use crate::transaction::constants::{
+    EXECUTE_ENTRY_POINT_NAME, TRANSFER_ENTRY_POINT_NAME, VALIDATE_DECLARE_ENTRY_POINT_NAME,
+    VALIDATE_DEPLOY_ENTRY_POINT_NAME, VALIDATE_ENTRY_POINT_NAME,
+};
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::utils::eth_address_to_felt;
+
+#[cfg(test)]
+#[path = "trace_test.rs"]
+mod test;
+
+/// The trace of a transaction, shaped as in the Starknet JSON-RPC specification
+/// (`starknet_traceTransaction`), without the transaction type and the state diff.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct TransactionTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    /// [None] for `Declare`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub execute_invocation: Option<ExecuteInvocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    /// The fee transfers following the first, for chains dividing the fee between several
+    /// recipients; not part of the specification.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub additional_fee_transfer_invocations: Vec<FunctionInvocation>,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ExecuteInvocation {
+    Invocation(FunctionInvocation),
+    Reverted { revert_reason: String },
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct FunctionInvocation {
+    pub contract_address: ContractAddress,
+    pub entry_point_selector: EntryPointSelector,
+    pub calldata: Vec<StarkFelt>,
+    pub caller_address: ContractAddress,
+    /// [None] if the class of the called contract was not resolved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub class_hash: Option<ClassHash>,
+    pub entry_point_type: EntryPointType,
+    pub call_type: TraceCallType,
+    pub result: Vec<StarkFelt>,
+    pub calls: Vec<FunctionInvocation>,
+    pub events: Vec<TraceEvent>,
+    pub messages: Vec<TraceMessage>,
+    pub execution_resources: ComputationResources,
+}
+
+impl From<&CallInfo> for FunctionInvocation {
+    fn from(call_info: &CallInfo) -> Self {
+        let call = &call_info.call;
+        let execution = &call_info.execution;
+        let from_address = call.storage_address;
+
+        Self {
+            contract_address: call.storage_address,
+            entry_point_selector: call.entry_point_selector,
+            calldata: call.calldata.0.to_vec(),
+            caller_address: call.caller_address,
+            class_hash: call.class_hash,
+            entry_point_type: call.entry_point_type,
+            call_type: call.call_type.into(),
+            result: execution.retdata.0.clone(),
+            calls: call_info.inner_calls.iter().map(FunctionInvocation::from).collect(),
+            events: execution.events.iter().map(TraceEvent::from).collect(),
+            messages: execution
+                .l2_to_l1_messages
+                .iter()
+                .map(|ordered_message| TraceMessage {
+                    order: ordered_message.order,
+                    from_address,
+                    to_address: eth_address_to_felt(ordered_message.message.to_address),
+                    payload: ordered_message.message.payload.0.clone(),
+                })
+                .collect(),
+            execution_resources: ComputationResources::from(&call_info.vm_resources),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub enum TraceCallType {
+    #[serde(rename = "CALL")]
+    Call,
+    #[serde(rename = "LIBRARY_CALL")]
+    LibraryCall,
+}
+
+impl From<CallType> for TraceCallType {
+    fn from(call_type: CallType) -> Self {
+        match call_type {
+            CallType::Call => Self::Call,
+            CallType::Delegate => Self::LibraryCall,
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct TraceEvent {
+    pub order: usize,
+    pub keys: Vec<StarkFelt>,
+    pub data: Vec<StarkFelt>,
+}
+
+impl From<&OrderedEvent> for TraceEvent {
+    fn from(ordered_event: &OrderedEvent) -> Self {
+        Self {
+            order: ordered_event.order,
+            keys: ordered_event.event.keys.iter().map(|key| key.0).collect(),
+            data: ordered_event.event.data.0.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct TraceMessage {
+    pub order: usize,
+    pub from_address: ContractAddress,
+    pub to_address: StarkFelt,
+    pub payload: Vec<StarkFelt>,
+}
+
+/// The VM resources of an invocation, including those of its inner calls; unused builtins are
+/// omitted.
+#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+pub struct ComputationResources {
+    pub steps: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_holes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range_check_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pedersen_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poseidon_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ec_op_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ecdsa_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitwise_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keccak_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment_arena_builtin: Option<usize>,
+}
+
+impl From<&VmExecutionResources> for ComputationResources {
+    fn from(vm_resources: &VmExecutionResources) -> Self {
+        let builtin = |name: &str| {
+            vm_resources.builtin_instance_counter.get(name).copied().filter(|&count| count > 0)
+        };
+
+        Self {
+            steps: vm_resources.n_steps,
+            memory_holes: Some(vm_resources.n_memory_holes).filter(|&holes| holes > 0),
+            range_check_builtin_applications: builtin(RANGE_CHECK_BUILTIN_NAME),
+            pedersen_builtin_applications: builtin(HASH_BUILTIN_NAME),
+            poseidon_builtin_applications: builtin(POSEIDON_BUILTIN_NAME),
+            ec_op_builtin_applications: builtin(EC_OP_BUILTIN_NAME),
+            ecdsa_builtin_applications: builtin(SIGNATURE_BUILTIN_NAME),
+            bitwise_builtin_applications: builtin(BITWISE_BUILTIN_NAME),
+            keccak_builtin_applications: builtin(KECCAK_BUILTIN_NAME),
+            segment_arena_builtin: builtin(SEGMENT_ARENA_BUILTIN_NAME),
+        }
+    }
+}
+
+impl TransactionExecutionInfo {
+    /// Converts this execution info into an RPC trace; for a reverted transaction, the execution
+    /// invocation holds the revert reason.
+    pub fn to_rpc_trace(&self) -> TransactionTrace {
+        let execute_invocation = match &self.revert_error {
+            Some(revert_error) => {
+                Some(ExecuteInvocation::Reverted { revert_reason: revert_error.clone() })
+            }
+            None => self
+                .execute_call_info
+                .as_ref()
+                .map(|call_info| ExecuteInvocation::Invocation(call_info.into())),
+        };
+
+        TransactionTrace {
+            validate_invocation: self.validate_call_info.as_ref().map(FunctionInvocation::from),
+            execute_invocation,
+            fee_transfer_invocation: self
+                .fee_transfer_call_info
+                .as_ref()
+                .map(FunctionInvocation::from),
+            additional_fee_transfer_invocations: self
+                .additional_fee_transfer_call_infos
+                .iter()
+                .map(FunctionInvocation::from)
+                .collect(),
+        }
+    }
+}
+
+/// Entry points whose names are shown in formatted traces, instead of their selectors.
+const KNOWN_ENTRY_POINT_NAMES: [&str; 6] = [
+    CONSTRUCTOR_ENTRY_POINT_NAME,
+    EXECUTE_ENTRY_POINT_NAME,
+    TRANSFER_ENTRY_POINT_NAME,
+    VALIDATE_DECLARE_ENTRY_POINT_NAME,
+    VALIDATE_DEPLOY_ENTRY_POINT_NAME,
+    VALIDATE_ENTRY_POINT_NAME,
+];
+
+impl CallInfo {
+    /// Formats this call and its inner calls as an indented tree, one call per line: the entry
+    /// point (by name, if well-known), the called contract, the consumed gas and Cairo steps, and
+    /// a marker for failed calls.
+    pub fn format_trace(&self) -> String {
+        self.iter_with_depth()
+            .map(|(depth, call_info)| format!("{}{}\n", "  ".repeat(depth), format_call(call_info)))
+            .collect()
+    }
+}
+
+impl TransactionExecutionInfo {
+    /// Formats the calls of this transaction as indented trees (see [CallInfo::format_trace]), by
+    /// execution phase, followed by the revert reason of a reverted transaction.
+    pub fn format_trace(&self) -> String {
+        let phases = [
+            ("validate", self.validate_call_info.as_ref()),
+            ("execute", self.execute_call_info.as_ref()),
+            ("fee transfer", self.fee_transfer_call_info.as_ref()),
+        ]
+        .into_iter()
+        .chain(
+            self.additional_fee_transfer_call_infos
+                .iter()
+                .map(|call_info| ("fee transfer", Some(call_info))),
+        );
+        let mut trace = String::new();
+        for (phase, call_info) in phases {
+            if let Some(call_info) = call_info {
+                trace.push_str(&format!("{phase}:\n"));
+                for line in call_info.format_trace().lines() {
+                    trace.push_str(&format!("  {line}\n"));
+                }
+            }
+        }
+        if let Some(revert_error) = &self.revert_error {
+            trace.push_str(&format!("reverted: {revert_error}\n"));
+        }
+
+        trace
+    }
+}
+
+fn format_call(call_info: &CallInfo) -> String {
+    let call = &call_info.call;
+    let entry_point = KNOWN_ENTRY_POINT_NAMES
+        .into_iter()
+        .find(|&name| selector_from_name(name) == call.entry_point_selector)
+        .map(String::from)
+        .unwrap_or_else(|| format_felt_compact(&call.entry_point_selector.0));
+    let call_type = match call.call_type {
+        CallType::Call => "call",
+        CallType::Delegate => "library call",
+    };
+    let failure_marker = if call_info.execution.failed { " [FAILED]" } else { "" };
+
+    format!(
+        "{entry_point} @ {} ({call_type}) gas: {}, steps: {}{failure_marker}",
+        format_felt_compact(call.storage_address.0.key()),
+        call_info.execution.gas_consumed,
+        call_info.vm_resources.n_steps
+    )
+}
+
+/// Formats the given felt as a 0x-prefixed hex string, without leading zeros.
+fn format_felt_compact(felt: &StarkFelt) -> String {
+    format!("{:#x}", stark_felt_to_felt(*felt).to_biguint())
+}
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use cairo_vm::vm::runners::builtin_runner::{HASH_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{Calldata, EventContent, EventData, EventKey, L2ToL1Payload};
+use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
+};
+use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::retdata;
+use crate::transaction::constants::{EXECUTE_ENTRY_POINT_NAME, VALIDATE_ENTRY_POINT_NAME};
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::trace::ExecuteInvocation;
+
+const SIMPLE_INVOKE_TRACE: &str = include_str!("../../tests/fixtures/simple_invoke_trace.json");
+
+fn call_entry_point(
+    class_hash: &str,
+    address: &str,
+    selector: &str,
+    caller_address: &str,
+    calldata: Calldata,
+) -> CallEntryPoint {
+    CallEntryPoint {
+        class_hash: Some(class_hash!(class_hash)),
+        storage_address: contract_address!(address),
+        entry_point_selector: EntryPointSelector(stark_felt!(selector)),
+        caller_address: contract_address!(caller_address),
+        calldata,
+        ..Default::default()
+    }
+}
+
+fn vm_resources(
+    n_steps: usize,
+    n_memory_holes: usize,
+    builtins: &[(&str, usize)],
+) -> VmExecutionResources {
+    VmExecutionResources {
+        n_steps,
+        n_memory_holes,
+        builtin_instance_counter: builtins
+            .iter()
+            .map(|&(name, count)| (name.to_string(), count))
+            .collect::<HashMap<_, _>>(),
+    }
+}
+
+/// An account (0x1) invoking a contract (0x2) that emits an event and sends a message to L1.
+fn simple_invoke_execution_info() -> TransactionExecutionInfo {
+    let account_calldata = calldata![stark_felt!("0x2"), stark_felt!("0x200")];
+    let validate_call_info = CallInfo {
+        call: call_entry_point("0x11", "0x1", "0x100", "0x0", account_calldata.clone()),
+        vm_resources: vm_resources(20, 0, &[(RANGE_CHECK_BUILTIN_NAME, 1)]),
+        ..Default::default()
+    };
+
+    let inner_call = CallInfo {
+        call: call_entry_point("0x22", "0x2", "0x200", "0x1", calldata![]),
+        execution: CallExecution {
+            retdata: retdata![stark_felt!("0x7")],
+            events: vec![OrderedEvent {
+                order: 0,
+                event: EventContent {
+                    keys: vec![EventKey(stark_felt!("0x5"))],
+                    data: EventData(vec![stark_felt!("0x6")]),
+                },
+            }],
+            l2_to_l1_messages: vec![OrderedL2ToL1Message {
+                order: 0,
+                message: MessageToL1 {
+                    to_address: EthAddress::try_from(stark_felt!("0x85")).unwrap(),
+                    payload: L2ToL1Payload(vec![stark_felt!("0x9")]),
+                },
+            }],
+            ..Default::default()
+        },
+        vm_resources: vm_resources(30, 2, &[(HASH_BUILTIN_NAME, 1)]),
+        ..Default::default()
+    };
+    let execute_call_info = CallInfo {
+        call: call_entry_point("0x11", "0x1", "0x101", "0x0", account_calldata),
+        execution: CallExecution::from_retdata(retdata![stark_felt!("0x7")]),
+        vm_resources: vm_resources(50, 2, &[(RANGE_CHECK_BUILTIN_NAME, 2), (HASH_BUILTIN_NAME, 1)]),
+        inner_calls: vec![inner_call],
+        ..Default::default()
+    };
+
+    let fee_transfer_call_info = CallInfo {
+        call: CallEntryPoint {
+            call_type: CallType::Delegate,
+            ..call_entry_point(
+                "0x33",
+                "0x3",
+                "0x300",
+                "0x1",
+                calldata![stark_felt!("0x4"), stark_felt!("0x5"), stark_felt!("0x0")],
+            )
+        },
+        execution: CallExecution::from_retdata(retdata![stark_felt!("0x1")]),
+        vm_resources: vm_resources(10, 0, &[]),
+        ..Default::default()
+    };
+
+    TransactionExecutionInfo {
+        validate_call_info: Some(validate_call_info),
+        execute_call_info: Some(execute_call_info),
+        fee_transfer_call_info: Some(fee_transfer_call_info),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_simple_invoke_rpc_trace() {
+    let trace = simple_invoke_execution_info().to_rpc_trace();
+    let expected_trace: serde_json::Value = serde_json::from_str(SIMPLE_INVOKE_TRACE).unwrap();
+    assert_eq!(serde_json::to_value(trace).unwrap(), expected_trace);
+}
+
+#[test]
+fn test_reverted_rpc_trace() {
+    let revert_reason = String::from("Execution failed.");
+    let tx_execution_info = TransactionExecutionInfo {
+        execute_call_info: None,
+        revert_error: Some(revert_reason.clone()),
+        ..simple_invoke_execution_info()
+    };
+
+    let trace = tx_execution_info.to_rpc_trace();
+    assert_eq!(trace.execute_invocation, Some(ExecuteInvocation::Reverted { revert_reason }));
+    assert!(trace.validate_invocation.is_some());
+    assert!(trace.fee_transfer_invocation.is_some());
+}
+
+#[test]
+fn test_format_trace() {
+    let mut tx_execution_info = simple_invoke_execution_info();
+    let validate_call_info = tx_execution_info.validate_call_info.as_mut().unwrap();
+    validate_call_info.call.entry_point_selector = selector_from_name(VALIDATE_ENTRY_POINT_NAME);
+    let execute_call_info = tx_execution_info.execute_call_info.as_mut().unwrap();
+    execute_call_info.call.entry_point_selector = selector_from_name(EXECUTE_ENTRY_POINT_NAME);
+    execute_call_info.execution.gas_consumed = 1000;
+    execute_call_info.inner_calls[0].execution.failed = true;
+    tx_execution_info.revert_error = Some(String::from("Execution failed."));
+
+    assert_eq!(
+        tx_execution_info.execute_call_info.as_ref().unwrap().format_trace(),
+        "__execute__ @ 0x1 (call) gas: 1000, steps: 50\n  0x200 @ 0x2 (call) gas: 0, steps: 30 \
+         [FAILED]\n"
+    );
+    assert_eq!(
+        tx_execution_info.format_trace(),
+        "validate:\n  __validate__ @ 0x1 (call) gas: 0, steps: 20\nexecute:\n  __execute__ @ 0x1 \
+         (call) gas: 1000, steps: 50\n    0x200 @ 0x2 (call) gas: 0, steps: 30 [FAILED]\nfee \
+         transfer:\n  0x300 @ 0x3 (library call) gas: 0, steps: 10\nreverted: Execution failed.\n"
+    );
+}
@@ -13,7 +13,7 @@ use crate::transaction::errors::TransactionFeeError;
 use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
 use crate::transaction::transactions::{
     DeclareTransaction, DeployAccountTransaction, Executable, ExecutableTransaction,
-    InvokeTransaction, L1HandlerTransaction,
+    ExecutionFlags, InvokeTransaction, L1HandlerTransaction,
 };
 
 #[derive(Debug, derive_more::From)]
@@ -93,8 +93,7 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
         self,
         state: &mut TransactionalState<'_, S>,
         block_context: &BlockContext,
-        _charge_fee: bool,
-        _validate: bool,
+        _execution_flags: ExecutionFlags,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
         let tx_context = self.get_account_tx_context();
 
@@ -103,8 +102,7 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
         let mut remaining_gas = Transaction::initial_gas();
         let execute_call_info =
             self.run_execute(state, &mut execution_resources, &mut context, &mut remaining_gas)?;
-        // The calldata includes the "from" field, which is not a part of the payload.
-        let l1_handler_payload_size = self.tx.calldata.0.len() - 1;
+        let l1_handler_payload_size = self.payload_size();
 
         let ActualCost { actual_fee, actual_resources } =
             ActualCost::builder_for_l1_handler(block_context, tx_context, l1_handler_payload_size)
@@ -113,9 +111,13 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
                 .build(&execution_resources)?;
 
         let paid_fee = self.paid_fee_on_l1;
-        // For now, assert only that any amount of fee was paid.
+        // Unless enforced by the chain, assert only that any amount of fee was paid.
         // The error message still indicates the required fee.
-        if paid_fee == Fee(0) {
+        let is_fee_insufficient = match block_context.chain_info.enforce_l1_handler_fee {
+            true => paid_fee < actual_fee,
+            false => paid_fee == Fee(0),
+        };
+        if is_fee_insufficient {
             return Err(TransactionFeeError::InsufficientL1Fee { paid_fee, actual_fee })?;
         }
 
@@ -123,8 +125,10 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
             validate_call_info: None,
             execute_call_info,
             fee_transfer_call_info: None,
+            additional_fee_transfer_call_infos: vec![],
             actual_fee: Fee::default(),
             actual_resources,
+            estimated_resources: None,
             revert_error: None,
         })
     }
@@ -135,16 +139,13 @@ impl<S: StateReader> ExecutableTransaction<S> for Transaction {
         self,
         state: &mut TransactionalState<'_, S>,
         block_context: &BlockContext,
-        charge_fee: bool,
-        validate: bool,
+        execution_flags: ExecutionFlags,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
         match self {
             Self::AccountTransaction(account_tx) => {
-                account_tx.execute_raw(state, block_context, charge_fee, validate)
-            }
-            Self::L1HandlerTransaction(tx) => {
-                tx.execute_raw(state, block_context, charge_fee, validate)
+                account_tx.execute_raw(state, block_context, execution_flags)
             }
+            Self::L1HandlerTransaction(tx) => tx.execute_raw(state, block_context, execution_flags),
         }
     }
 }
@@ -4,6 +4,7 @@ use cairo_vm::vm::runners::builtin_runner::SEGMENT_ARENA_BUILTIN_NAME;
 use starknet_api::transaction::TransactionVersion;
 
 use crate::abi::constants;
+use crate::block_context::BlockContext;
 use crate::execution::call_info::CallInfo;
 use crate::execution::contract_class::ContractClass;
 use crate::execution::entry_point::ExecutionResources;
@@ -13,6 +14,7 @@ use crate::state::cached_state::StateChangesCount;
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::{ResourcesMapping, TransactionExecutionResult};
 use crate::transaction::transaction_types::TransactionType;
+use crate::versioned_constants::VersionedConstants;
 
 pub fn calculate_l1_gas_usage<'a>(
     call_infos: impl Iterator<Item = &'a CallInfo>,
@@ -37,13 +39,18 @@ pub fn calculate_l1_gas_usage<'a>(
 /// most-recent (recent w.r.t. application on the given state).
 /// I.e., Cairo VM execution resources.
 pub fn calculate_tx_resources(
+    versioned_constants: &VersionedConstants,
     execution_resources: &ExecutionResources,
     l1_gas_usage: usize,
     tx_type: TransactionType,
 ) -> TransactionExecutionResult<ResourcesMapping> {
     // Add additional Cairo resources needed for the OS to run the transaction.
     let total_vm_usage = &execution_resources.vm_resources
-        + &get_additional_os_resources(&execution_resources.syscall_counter, tx_type)?;
+        + &get_additional_os_resources(
+            versioned_constants.os_resources,
+            &execution_resources.syscall_counter,
+            tx_type,
+        )?;
     let mut total_vm_usage = total_vm_usage.filter_unused_builtins();
     // The segment arena" builtin is not part of SHARP (not in any proof layout).
     // Each instance requires approximately 10 steps in the OS.
@@ -94,3 +101,45 @@ pub fn verify_contract_class_version(
         }
     }
 }
+
+/// Verifies that a declared class satisfies the protocol limits: the size of the serialized class
+/// (if known), the bytecode length, and for Cairo 1 classes, the version of the compiler that
+/// produced it.
+pub fn validate_declared_class(
+    contract_class: &ContractClass,
+    class_size: Option<usize>,
+    block_context: &BlockContext,
+) -> TransactionExecutionResult<()> {
+    let chain_info = &block_context.chain_info;
+    if let Some(class_size) = class_size {
+        if class_size > chain_info.max_contract_class_size {
+            return Err(TransactionExecutionError::ContractClassSizeTooLarge {
+                class_size,
+                max_class_size: chain_info.max_contract_class_size,
+            });
+        }
+    }
+
+    let bytecode_length = contract_class.bytecode_length();
+    if bytecode_length > chain_info.max_bytecode_length {
+        return Err(TransactionExecutionError::BytecodeLengthTooLarge {
+            bytecode_length,
+            max_bytecode_length: chain_info.max_bytecode_length,
+        });
+    }
+
+    if let ContractClass::V1(class) = contract_class {
+        let supported_compiler_versions = &chain_info.supported_compiler_versions;
+        let is_supported = class.compiler_version().is_ok_and(|compiler_version| {
+            compiler_version.is_supported(supported_compiler_versions)
+        });
+        if !is_supported {
+            return Err(TransactionExecutionError::UnsupportedCompilerVersion {
+                compiler_version: class.compiler_version.clone(),
+                supported_compiler_versions: supported_compiler_versions.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
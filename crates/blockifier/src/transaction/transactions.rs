@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress, Nonce};
 use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::{
     AccountDeploymentData, Calldata, ContractAddressSalt, DeclareTransactionV2,
     DeclareTransactionV3, Fee, TransactionHash, TransactionSignature, TransactionVersion,
@@ -16,8 +17,8 @@ use crate::execution::entry_point::{
 };
 use crate::execution::execution_utils::execute_deployment;
 use crate::state::cached_state::{CachedState, TransactionalState};
-use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader};
+use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::constants;
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::{
@@ -25,7 +26,10 @@ use crate::transaction::objects::{
     DeprecatedAccountTransactionContext, HasRelatedFeeType, TransactionExecutionInfo,
     TransactionExecutionResult,
 };
-use crate::transaction::transaction_utils::{update_remaining_gas, verify_contract_class_version};
+use crate::transaction::transaction_utils::{
+    update_remaining_gas, validate_declared_class, verify_contract_class_version,
+};
+use crate::utils::eth_address_to_felt;
 
 #[cfg(test)]
 #[path = "transactions_test.rs"]
@@ -39,6 +43,19 @@ macro_rules! implement_inner_tx_getter_calls {
     };
 }
 
+/// Flags controlling the phases of a transaction execution.
+#[derive(Clone, Copy, Debug)]
+pub struct ExecutionFlags {
+    pub charge_fee: bool,
+    /// If false, the validation entry point is not called (e.g., when re-simulating historical
+    /// transactions whose signatures no longer verify). Nonce checks are performed regardless, as
+    /// they are part of the pre-validation stage.
+    pub validate: bool,
+    /// If false, only the validation phase of account transactions is run (e.g., for mempool
+    /// admission); nothing is executed and no fee is charged.
+    pub execute: bool,
+}
+
 pub trait ExecutableTransaction<S: StateReader>: Sized {
     /// Executes the transaction in a transactional manner
     /// (if it fails, given state does not modify).
@@ -48,11 +65,22 @@ pub trait ExecutableTransaction<S: StateReader>: Sized {
         block_context: &BlockContext,
         charge_fee: bool,
         validate: bool,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        let execution_flags = ExecutionFlags { charge_fee, validate, execute: true };
+        self.execute_with_flags(state, block_context, execution_flags)
+    }
+
+    /// Same as [ExecutableTransaction::execute], with control over the executed phases.
+    fn execute_with_flags(
+        self,
+        state: &mut CachedState<S>,
+        block_context: &BlockContext,
+        execution_flags: ExecutionFlags,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
         log::debug!("Executing Transaction...");
         let mut transactional_state = CachedState::create_transactional(state);
         let execution_result =
-            self.execute_raw(&mut transactional_state, block_context, charge_fee, validate);
+            self.execute_raw(&mut transactional_state, block_context, execution_flags);
 
         match execution_result {
             Ok(value) => {
@@ -74,8 +102,7 @@ pub trait ExecutableTransaction<S: StateReader>: Sized {
         self,
         state: &mut TransactionalState<'_, S>,
         block_context: &BlockContext,
-        charge_fee: bool,
-        validate: bool,
+        execution_flags: ExecutionFlags,
     ) -> TransactionExecutionResult<TransactionExecutionInfo>;
 }
 
@@ -109,6 +136,11 @@ pub struct DeclareTransaction {
     contract_class: ContractClass,
     // Indicates the presence of the only_query bit in the version.
     only_query: bool,
+    // Indicates whether the Casm hash computation of the declared class is charged for.
+    charge_casm_hash_computation: bool,
+    // The size of the declared class as serialized (e.g., its raw JSON), in bytes; the class size
+    // limit is only enforced if it is known.
+    class_size: Option<usize>,
 }
 
 impl DeclareTransaction {
@@ -120,7 +152,14 @@ impl DeclareTransaction {
     ) -> TransactionExecutionResult<Self> {
         let declare_version = declare_tx.version();
         let contract_class = verify_contract_class_version(contract_class, declare_version)?;
-        Ok(Self { tx: declare_tx, tx_hash, contract_class, only_query })
+        Ok(Self {
+            tx: declare_tx,
+            tx_hash,
+            contract_class,
+            only_query,
+            charge_casm_hash_computation: false,
+            class_size: None,
+        })
     }
 
     pub fn new(
@@ -153,6 +192,10 @@ impl DeclareTransaction {
         self.contract_class.clone()
     }
 
+    pub fn class_size(&self) -> Option<usize> {
+        self.class_size
+    }
+
     pub fn get_account_tx_context(&self) -> AccountTransactionContext {
         // TODO(Nir, 01/11/2023): Consider to move this (from all get_account_tx_context methods).
         let common_fields = CommonAccountFields {
@@ -195,16 +238,60 @@ impl DeclareTransaction {
     pub fn only_query(&self) -> bool {
         self.only_query
     }
+
+    /// Adds the estimated resources of the Casm hash computation of the declared class (done by
+    /// the OS) to the execution resources of the transaction.
+    pub fn with_casm_hash_computation_charge(mut self) -> Self {
+        self.charge_casm_hash_computation = true;
+        self
+    }
+
+    /// Sets the size of the declared class as serialized (e.g., the length of its raw JSON), in
+    /// bytes, to be checked against the class size limit.
+    pub fn with_class_size(mut self, class_size: usize) -> Self {
+        self.class_size = Some(class_size);
+        self
+    }
+}
+
+/// Declares the given class, whose serialized form (e.g., raw JSON) is `class_size` bytes long,
+/// charging for its Casm hash computation on top of the regular declare fee. Returns the execution
+/// info along with the (validated) size of the declared class, in bytes.
+pub fn execute_declare<S: StateReader>(
+    tx: starknet_api::transaction::DeclareTransaction,
+    tx_hash: TransactionHash,
+    contract_class: ContractClass,
+    class_size: usize,
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+) -> TransactionExecutionResult<(TransactionExecutionInfo, usize)> {
+    let declare_tx = DeclareTransaction::new(tx, tx_hash, contract_class)?
+        .with_casm_hash_computation_charge()
+        .with_class_size(class_size);
+    let (charge_fee, validate) = (true, true);
+    let tx_execution_info = AccountTransaction::Declare(declare_tx).execute(
+        state,
+        block_context,
+        charge_fee,
+        validate,
+    )?;
+
+    Ok((tx_execution_info, class_size))
 }
 
 impl<S: State> Executable<S> for DeclareTransaction {
     fn run_execute(
         &self,
         state: &mut S,
-        _resources: &mut ExecutionResources,
-        _context: &mut EntryPointExecutionContext,
+        resources: &mut ExecutionResources,
+        context: &mut EntryPointExecutionContext,
         _remaining_gas: &mut u64,
     ) -> TransactionExecutionResult<Option<CallInfo>> {
+        validate_declared_class(&self.contract_class, self.class_size, &context.block_context)?;
+        if self.charge_casm_hash_computation {
+            resources.vm_resources +=
+                &self.contract_class.estimate_casm_hash_computation_resources();
+        }
         let class_hash = self.class_hash();
 
         match &self.tx {
@@ -222,20 +309,16 @@ impl<S: State> Executable<S> for DeclareTransaction {
                 compiled_class_hash,
                 ..
             }) => {
-                match state.get_compiled_contract_class(class_hash) {
-                    Err(StateError::UndeclaredClassHash(_)) => {
-                        // Class is undeclared; declare it.
-                        state.set_contract_class(class_hash, self.contract_class.clone())?;
-                        state.set_compiled_class_hash(class_hash, *compiled_class_hash)?;
-                        Ok(None)
-                    }
-                    Err(error) => Err(error)?,
-                    Ok(_) => {
-                        // Class is already declared, cannot redeclare
-                        // (i.e., make sure the leaf is uninitialized).
-                        Err(TransactionExecutionError::DeclareTransactionError { class_hash })
-                    }
+                if state.is_class_declared(class_hash)? {
+                    // Class is already declared, cannot redeclare
+                    // (i.e., make sure the leaf is uninitialized).
+                    return Err(TransactionExecutionError::DeclareTransactionError { class_hash });
                 }
+
+                // Class is undeclared; declare it.
+                state.set_contract_class(class_hash, self.contract_class.clone())?;
+                state.set_compiled_class_hash(class_hash, *compiled_class_hash)?;
+                Ok(None)
             }
         }
     }
@@ -453,6 +536,38 @@ pub struct L1HandlerTransaction {
 }
 
 impl L1HandlerTransaction {
+    /// Creates a transaction handling the given message, sent from `from_address` on L1. The
+    /// sender address is prepended to the payload, as L1 handler entry points expect it as their
+    /// first argument.
+    pub fn from_l1_message(
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+        from_address: EthAddress,
+        payload: &[StarkFelt],
+        nonce: Nonce,
+        tx_hash: TransactionHash,
+        paid_fee_on_l1: Fee,
+    ) -> Self {
+        let calldata = [&[eth_address_to_felt(from_address)], payload].concat();
+        Self {
+            tx: starknet_api::transaction::L1HandlerTransaction {
+                version: TransactionVersion::ZERO,
+                nonce,
+                contract_address,
+                entry_point_selector,
+                calldata: Calldata(Arc::new(calldata)),
+            },
+            tx_hash,
+            paid_fee_on_l1,
+        }
+    }
+
+    /// Returns the size of the L1 message payload, i.e., the calldata without the sender address
+    /// (zero if the calldata is empty).
+    pub fn payload_size(&self) -> usize {
+        self.tx.calldata.0.len().saturating_sub(1)
+    }
+
     pub fn get_account_tx_context(&self) -> AccountTransactionContext {
         AccountTransactionContext::Deprecated(DeprecatedAccountTransactionContext {
             common_fields: CommonAccountFields {
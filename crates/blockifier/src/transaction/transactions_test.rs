@@ -22,7 +22,8 @@ use strum::IntoEnumIterator;
 use test_case::test_case;
 
 use crate::abi::abi_utils::{
-    get_fee_token_var_address, get_storage_var_address, selector_from_name,
+    get_fee_token_var_address, get_storage_var_address, get_storage_var_member_address,
+    selector_from_name,
 };
 use crate::abi::constants as abi_constants;
 use crate::abi::sierra_types::next_storage_key;
@@ -30,6 +31,7 @@ use crate::block_context::BlockContext;
 use crate::execution::call_info::{
     CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
 };
+use crate::execution::contract_class::{ContractClass, ContractClassV1, ContractClassV1Inner};
 use crate::execution::entry_point::{CallEntryPoint, CallType};
 use crate::execution::errors::{EntryPointExecutionError, VirtualMachineExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
@@ -69,7 +71,10 @@ use crate::transaction::test_utils::{
 };
 use crate::transaction::transaction_execution::Transaction;
 use crate::transaction::transaction_types::TransactionType;
-use crate::transaction::transactions::{ExecutableTransaction, L1HandlerTransaction};
+use crate::transaction::transaction_utils::validate_declared_class;
+use crate::transaction::transactions::{
+    execute_declare, ExecutableTransaction, L1HandlerTransaction,
+};
 use crate::{
     check_transaction_execution_error_for_custom_hint,
     check_transaction_execution_error_for_invalid_scenario, declare_tx_args,
@@ -159,7 +164,7 @@ fn expected_fee_transfer_call_info(
     fee_type: &FeeType,
     expected_fee_token_class_hash: ClassHash,
 ) -> Option<CallInfo> {
-    let expected_sequencer_address = *block_context.sequencer_address.0.key();
+    let expected_sequencer_address = *block_context.block_info.sequencer_address.0.key();
     // The least significant 128 bits of the expected amount transferred.
     let lsb_expected_amount = stark_felt!(actual_fee.0);
     // The most significant 128 bits of the expected amount transferred.
@@ -197,7 +202,8 @@ fn expected_fee_transfer_call_info(
     let sender_balance_key_low = get_fee_token_var_address(account_address);
     let sender_balance_key_high =
         next_storage_key(&sender_balance_key_low).expect("Cannot get sender balance high key.");
-    let sequencer_balance_key_low = get_fee_token_var_address(block_context.sequencer_address);
+    let sequencer_balance_key_low =
+        get_fee_token_var_address(block_context.block_info.sequencer_address);
     let sequencer_balance_key_high = next_storage_key(&sequencer_balance_key_low)
         .expect("Cannot get sequencer balance high key.");
     Some(CallInfo {
@@ -256,8 +262,9 @@ fn validate_final_balances(
     }
 
     // Verify balances of both accounts, of both fee types, are as expected.
-    let eth_fee_token_address = block_context.fee_token_addresses.eth_fee_token_address;
-    let strk_fee_token_address = block_context.fee_token_addresses.strk_fee_token_address;
+    let eth_fee_token_address = block_context.chain_info.fee_token_addresses.eth_fee_token_address;
+    let strk_fee_token_address =
+        block_context.chain_info.fee_token_addresses.strk_fee_token_address;
     for (fee_address, expected_account_balance, expected_sequencer_balance) in [
         (eth_fee_token_address, expected_account_balance_eth, expected_sequencer_balance_eth),
         (strk_fee_token_address, expected_account_balance_strk, expected_sequencer_balance_strk),
@@ -410,6 +417,7 @@ fn test_invoke_tx(
         validate_call_info: expected_validate_call_info,
         execute_call_info: expected_execute_call_info,
         fee_transfer_call_info: expected_fee_transfer_call_info,
+        additional_fee_transfer_call_infos: vec![],
         actual_fee: expected_actual_fee,
         actual_resources: ResourcesMapping(HashMap::from([
             (
@@ -424,6 +432,7 @@ fn test_invoke_tx(
             (RANGE_CHECK_BUILTIN_NAME.to_string(), expected_arguments.range_check),
             (abi_constants::N_STEPS_RESOURCE.to_string(), expected_arguments.n_steps),
         ])),
+        estimated_resources: None,
         revert_error: None,
     };
 
@@ -457,20 +466,18 @@ fn verify_storage_after_invoke_advanced_operations(
     expected_nonce: Nonce,
 ) {
     // Verify the two_counters values in storage.
-    let key = get_storage_var_address("two_counters", &[index]);
-    let value = state.get_storage_at(contract_address, key).unwrap();
-    assert_eq!(value, expected_counters[0]);
-    let key = next_storage_key(&key).unwrap();
-    let value = state.get_storage_at(contract_address, key).unwrap();
-    assert_eq!(value, expected_counters[1]);
+    for (offset, expected_counter) in expected_counters.into_iter().enumerate() {
+        let key = get_storage_var_member_address("two_counters", &[index], offset as u8);
+        let value = state.get_storage_at(contract_address, key).unwrap();
+        assert_eq!(value, expected_counter);
+    }
 
     // Verify the ec_point values in storage.
-    let key = get_storage_var_address("ec_point", &[]);
-    let value = state.get_storage_at(contract_address, key).unwrap();
-    assert_eq!(value, expected_ec_point[0]);
-    let key = next_storage_key(&key).unwrap();
-    let value = state.get_storage_at(contract_address, key).unwrap();
-    assert_eq!(value, expected_ec_point[1]);
+    for (offset, expected_coordinate) in expected_ec_point.into_iter().enumerate() {
+        let key = get_storage_var_member_address("ec_point", &[], offset as u8);
+        let value = state.get_storage_at(contract_address, key).unwrap();
+        assert_eq!(value, expected_coordinate);
+    }
 
     // Verify the nonce value in storage.
     let nonce_from_state = state.get_nonce_at(account_address).unwrap();
@@ -798,7 +805,7 @@ fn test_insufficient_resource_bounds(account_cairo_version: CairoVersion) {
 
     // Test V1 transaction.
 
-    let minimal_fee = Fee(minimal_l1_gas * block_context.gas_prices.eth_l1_gas_price);
+    let minimal_fee = Fee(minimal_l1_gas * block_context.block_info.gas_prices.eth_l1_gas_price);
     // Max fee too low (lower than minimal estimated fee).
     let invalid_max_fee = Fee(minimal_fee.0 - 1);
     let invalid_v1_tx = account_invoke_tx(
@@ -816,7 +823,7 @@ fn test_insufficient_resource_bounds(account_cairo_version: CairoVersion) {
     );
 
     // Test V3 transaction.
-    let actual_strk_l1_gas_price = block_context.gas_prices.strk_l1_gas_price;
+    let actual_strk_l1_gas_price = block_context.block_info.gas_prices.strk_l1_gas_price;
 
     // Max L1 gas amount too low.
     let insufficient_max_l1_gas_amount = (minimal_l1_gas - 1) as u64;
@@ -869,7 +876,7 @@ fn test_actual_fee_gt_resource_bounds(account_cairo_version: CairoVersion) {
 
     let minimal_l1_gas =
         estimate_minimal_l1_gas(block_context, &account_invoke_tx(invoke_tx_args.clone())).unwrap();
-    let minimal_fee = Fee(minimal_l1_gas * block_context.gas_prices.eth_l1_gas_price);
+    let minimal_fee = Fee(minimal_l1_gas * block_context.block_info.gas_prices.eth_l1_gas_price);
     // The estimated minimal fee is lower than the actual fee.
     let invalid_tx = account_invoke_tx(invoke_tx_args! { max_fee: minimal_fee, ..invoke_tx_args });
 
@@ -913,7 +920,7 @@ fn test_invalid_nonce(account_cairo_version: CairoVersion) {
     // Test error.
     assert_matches!(
         pre_validation_err,
-            TransactionPreValidationError::InvalidNonce {address, account_nonce, incoming_tx_nonce}
+            TransactionPreValidationError::NonceTooHigh {address, account_nonce, incoming_tx_nonce}
         if (address, account_nonce, incoming_tx_nonce) ==
         (valid_invoke_tx_args.sender_address, Nonce::default(), invalid_nonce)
     );
@@ -952,12 +959,51 @@ fn test_invalid_nonce(account_cairo_version: CairoVersion) {
     // Test error.
     assert_matches!(
         pre_validation_err,
-        TransactionPreValidationError::InvalidNonce {address, account_nonce, incoming_tx_nonce}
+        TransactionPreValidationError::NonceTooLow {address, account_nonce, incoming_tx_nonce}
         if (address, account_nonce, incoming_tx_nonce) ==
         (valid_invoke_tx_args.sender_address, Nonce(stark_felt!(1_u8)), invalid_nonce)
     );
 }
 
+#[test]
+fn test_validate_nonce() {
+    let block_context = &BlockContext::create_for_account_testing();
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state =
+        &mut test_state(block_context, BALANCE, &[(account_contract, 1), (test_contract, 1)]);
+    let sender_address = account_contract.get_instance_address(0);
+    state.increment_nonce(sender_address).unwrap();
+    let account_nonce = Nonce(stark_felt!(1_u8));
+    let invoke_tx_args =
+        default_invoke_tx_args(sender_address, test_contract.get_instance_address(0));
+    let validate_nonce = |state: &mut CachedState<DictStateReader>, nonce: Nonce| {
+        let tx = account_invoke_tx(invoke_tx_args! { nonce, ..invoke_tx_args.clone() });
+        AccountTransaction::validate_nonce(state, &tx.get_account_tx_context())
+    };
+
+    validate_nonce(state, account_nonce).unwrap();
+
+    // Replay.
+    let incoming_tx_nonce = Nonce(stark_felt!(0_u8));
+    assert_matches!(
+        validate_nonce(state, incoming_tx_nonce).unwrap_err(),
+        TransactionPreValidationError::NonceTooLow { address, account_nonce: nonce, .. }
+        if (address, nonce) == (sender_address, account_nonce)
+    );
+
+    // Future transaction.
+    let incoming_tx_nonce = Nonce(stark_felt!(2_u8));
+    assert_matches!(
+        validate_nonce(state, incoming_tx_nonce).unwrap_err(),
+        TransactionPreValidationError::NonceTooHigh { address, incoming_tx_nonce: nonce, .. }
+        if (address, nonce) == (sender_address, incoming_tx_nonce)
+    );
+
+    // Validation does not increment the nonce.
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), account_nonce);
+}
+
 /// Returns the expected number of range checks in a declare transaction.
 fn declare_expected_range_check_builtin(
     version: TransactionVersion,
@@ -1095,7 +1141,9 @@ fn test_declare_tx(
         validate_call_info: expected_validate_call_info,
         execute_call_info: None,
         fee_transfer_call_info: expected_fee_transfer_call_info,
+        additional_fee_transfer_call_infos: vec![],
         actual_fee: expected_actual_fee,
+        estimated_resources: None,
         revert_error: None,
         actual_resources: ResourcesMapping(HashMap::from([
             (abi_constants::GAS_USAGE.to_string(), declare_expected_l1_gas_usage(tx_version)),
@@ -1136,6 +1184,118 @@ fn test_declare_tx(
     assert_eq!(contract_class_from_state, contract_class);
 }
 
+#[test]
+fn test_execute_declare() {
+    let block_context = &BlockContext::create_for_account_testing();
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let declared_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let contract_class = declared_contract.get_class();
+    let declare_args = declare_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account.get_instance_address(0),
+        version: TransactionVersion::TWO,
+        class_hash: declared_contract.get_class_hash(),
+    };
+
+    let regular_execution_info = declare_tx(declare_args.clone(), contract_class.clone())
+        .execute(
+            &mut test_state(block_context, BALANCE, &[(account, 1)]),
+            block_context,
+            true,
+            true,
+        )
+        .unwrap();
+
+    let AccountTransaction::Declare(declare) = declare_tx(declare_args, contract_class.clone())
+    else {
+        unreachable!("declare_tx returns a Declare transaction.")
+    };
+    let state = &mut test_state(block_context, BALANCE, &[(account, 1)]);
+    let raw_class_size = declared_contract.get_raw_class().len();
+    let (tx_execution_info, class_size) = execute_declare(
+        declare.tx().clone(),
+        declare.tx_hash(),
+        contract_class.clone(),
+        raw_class_size,
+        state,
+        block_context,
+    )
+    .unwrap();
+    assert_eq!(class_size, raw_class_size);
+
+    // The Casm hash computation resources are charged on top of the regular declare resources.
+    let casm_hash_computation_resources = contract_class.estimate_casm_hash_computation_resources();
+    assert!(casm_hash_computation_resources.n_steps > 0);
+    let n_steps = |tx_execution_info: &TransactionExecutionInfo| {
+        tx_execution_info.actual_resources.0[abi_constants::N_STEPS_RESOURCE]
+    };
+    assert_eq!(
+        n_steps(&tx_execution_info),
+        n_steps(&regular_execution_info)
+            + casm_hash_computation_resources.n_steps
+            + casm_hash_computation_resources.n_memory_holes
+    );
+    assert!(tx_execution_info.actual_fee > regular_execution_info.actual_fee);
+}
+
+#[rstest]
+fn test_validate_declared_class(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let contract_class = test_contract.get_class();
+    let class_size = Some(test_contract.get_raw_class().len());
+    let mut block_context = BlockContext::create_for_testing();
+    validate_declared_class(&contract_class, class_size, &block_context).unwrap();
+
+    // Oversize class, within the bytecode length limit.
+    let class_size = class_size.unwrap();
+    block_context.chain_info_mut().max_contract_class_size = class_size - 1;
+    assert!(contract_class.bytecode_length() <= block_context.chain_info.max_bytecode_length);
+    assert_matches!(
+        validate_declared_class(&contract_class, Some(class_size), &block_context).unwrap_err(),
+        TransactionExecutionError::ContractClassSizeTooLarge { class_size: size, max_class_size }
+        if size == class_size && max_class_size == class_size - 1
+    );
+    // The size limit is not enforced on classes of unknown size.
+    validate_declared_class(&contract_class, None, &block_context).unwrap();
+
+    // Bytecode too long, within the size limit.
+    block_context.chain_info_mut().max_contract_class_size = class_size;
+    let bytecode_length = contract_class.bytecode_length();
+    block_context.chain_info_mut().max_bytecode_length = bytecode_length - 1;
+    assert_matches!(
+        validate_declared_class(&contract_class, Some(class_size), &block_context).unwrap_err(),
+        TransactionExecutionError::BytecodeLengthTooLarge {
+            bytecode_length: length,
+            max_bytecode_length,
+        } if length == bytecode_length && max_bytecode_length == bytecode_length - 1
+    );
+}
+
+#[test]
+fn test_validate_declared_class_unsupported_compiler_version() {
+    let block_context = BlockContext::create_for_testing();
+    let ContractClass::V1(contract_class) =
+        FeatureContract::TestContract(CairoVersion::Cairo1).get_class()
+    else {
+        panic!("Expected a Cairo 1 contract class.");
+    };
+
+    for compiler_version in ["0.12.2", "3.0.0", "invalid"] {
+        let contract_class = ContractClass::V1(ContractClassV1(Arc::new(ContractClassV1Inner {
+            compiler_version: compiler_version.to_string(),
+            ..(*contract_class.0).clone()
+        })));
+        assert_matches!(
+            validate_declared_class(&contract_class, None, &block_context).unwrap_err(),
+            TransactionExecutionError::UnsupportedCompilerVersion {
+                compiler_version: version, ..
+            } if version == compiler_version
+        );
+    }
+}
+
 #[rstest]
 #[case(83, 3893, CairoVersion::Cairo0)]
 #[case(85, 3949, CairoVersion::Cairo1)]
@@ -1220,7 +1380,9 @@ fn test_deploy_account_tx(
         validate_call_info: expected_validate_call_info,
         execute_call_info: expected_execute_call_info,
         fee_transfer_call_info: expected_fee_transfer_call_info,
+        additional_fee_transfer_call_infos: vec![],
         actual_fee: expected_actual_fee,
+        estimated_resources: None,
         revert_error: None,
         actual_resources: ResourcesMapping(HashMap::from([
             (
@@ -1447,7 +1609,7 @@ fn test_calculate_tx_gas_usage() {
     };
     let l1_gas_usage = calculate_tx_gas_usage(&[], state_changes_count, None);
 
-    assert_eq!(tx_execution_info.actual_resources.gas_usage(), l1_gas_usage);
+    assert_eq!(tx_execution_info.actual_resources.l1_gas_usage().unwrap(), l1_gas_usage);
 
     // A tx that changes the account and some other balance in execute.
     let some_other_account_address = account_contract.get_instance_address(17);
@@ -1482,7 +1644,7 @@ fn test_calculate_tx_gas_usage() {
     };
     let l1_gas_usage = calculate_tx_gas_usage(&[], state_changes_count, None);
 
-    assert_eq!(tx_execution_info.actual_resources.gas_usage(), l1_gas_usage);
+    assert_eq!(tx_execution_info.actual_resources.l1_gas_usage().unwrap(), l1_gas_usage);
 }
 
 #[rstest]
@@ -1577,18 +1739,20 @@ fn test_only_query_flag(#[case] only_query: bool) {
     assert!(!tx_execution_info.is_reverted())
 }
 
-fn l1_handler_tx(calldata: &Calldata, l1_fee: Fee) -> L1HandlerTransaction {
-    L1HandlerTransaction {
-        tx: starknet_api::transaction::L1HandlerTransaction {
-            version: TransactionVersion::ZERO,
-            nonce: Nonce::default(),
-            contract_address: contract_address!(TEST_CONTRACT_ADDRESS),
-            entry_point_selector: selector_from_name("l1_handler_set_value"),
-            calldata: calldata.clone(),
-        },
-        tx_hash: TransactionHash::default(),
-        paid_fee_on_l1: l1_fee,
-    }
+fn l1_handler_tx(
+    from_address: EthAddress,
+    payload: &[StarkFelt],
+    l1_fee: Fee,
+) -> L1HandlerTransaction {
+    L1HandlerTransaction::from_l1_message(
+        contract_address!(TEST_CONTRACT_ADDRESS),
+        selector_from_name("l1_handler_set_value"),
+        from_address,
+        payload,
+        Nonce::default(),
+        TransactionHash::default(),
+        l1_fee,
+    )
 }
 
 #[test]
@@ -1599,7 +1763,11 @@ fn test_l1_handler() {
     let key = StarkFelt::from_u128(0x876);
     let value = StarkFelt::from_u128(0x44);
     let calldata = calldata![from_address, key, value];
-    let tx = l1_handler_tx(&calldata, Fee(1));
+    let from_address = EthAddress::try_from(from_address).unwrap();
+    let expected_actual_fee = Fee(1741300000000000);
+    let tx = l1_handler_tx(from_address, &[key, value], Fee(1));
+    assert_eq!(tx.tx.calldata, calldata);
+    assert_eq!(tx.payload_size(), 2);
 
     let actual_execution_info = tx.execute(state, block_context, true, true).unwrap();
 
@@ -1644,8 +1812,10 @@ fn test_l1_handler() {
         validate_call_info: None,
         execute_call_info: Some(expected_call_info),
         fee_transfer_call_info: None,
+        additional_fee_transfer_call_infos: vec![],
         actual_fee: Fee(0),
         actual_resources: expected_resource_mapping,
+        estimated_resources: None,
         revert_error: None,
     };
 
@@ -1664,15 +1834,31 @@ fn test_l1_handler() {
     );
 
     // Negative flow: not enough fee paid on L1.
-    let tx_no_fee = l1_handler_tx(&calldata, Fee(0));
+    let tx_no_fee = l1_handler_tx(from_address, &[key, value], Fee(0));
     let error = tx_no_fee.execute(state, block_context, true, true).unwrap_err();
     // Today, we check that the paid_fee is positive, no matter what was the actual fee.
     assert_matches!(
         error,
         TransactionExecutionError::TransactionFeeError(
             TransactionFeeError::InsufficientL1Fee { paid_fee, actual_fee, })
-            if paid_fee == Fee(0) && actual_fee == Fee(1741300000000000)
+            if paid_fee == Fee(0) && actual_fee == expected_actual_fee
+    );
+
+    // When enforced, the fee paid on L1 must also cover the actual fee.
+    let mut enforcing_block_context = block_context.clone();
+    enforcing_block_context.chain_info_mut().enforce_l1_handler_fee = true;
+    let l1_fee = Fee(expected_actual_fee.0 - 1);
+    let tx_insufficient_fee = l1_handler_tx(from_address, &[key, value], l1_fee);
+    let error =
+        tx_insufficient_fee.execute(state, &enforcing_block_context, true, true).unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::TransactionFeeError(
+            TransactionFeeError::InsufficientL1Fee { paid_fee, actual_fee, })
+            if paid_fee == l1_fee && actual_fee == expected_actual_fee
     );
+    let tx_sufficient_fee = l1_handler_tx(from_address, &[key, value], expected_actual_fee);
+    tx_sufficient_fee.execute(state, &enforcing_block_context, true, true).unwrap();
 }
 
 #[test]
@@ -1,5 +1,14 @@
 use std::collections::HashMap;
 
+use cairo_felt::Felt252;
+use starknet_api::core::{ChainId, ContractAddress, EthAddress, Nonce};
+use starknet_api::hash::{pedersen_hash_array, StarkFelt};
+use starknet_api::transaction::{Calldata, Fee, TransactionHash, TransactionVersion};
+use thiserror::Error;
+
+use crate::execution::execution_utils::felt_to_stark_felt;
+use crate::transaction::transaction_types::TransactionType;
+
 #[cfg(test)]
 #[path = "utils_test.rs"]
 pub mod test;
@@ -19,3 +28,114 @@ where
 pub const fn const_max(a: u128, b: u128) -> u128 {
     [a, b][(a < b) as usize]
 }
+
+/// Formats the given felt as a 0x-prefixed hex string, zero-padded to 64 digits; this is the
+/// format in which addresses and hashes appear in error messages.
+pub fn format_felt_hex(felt: &StarkFelt) -> String {
+    let hex_digits: String = felt.bytes().iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("0x{hex_digits}")
+}
+
+/// Converts the given Ethereum address into a felt, left-padded with zeros.
+pub fn eth_address_to_felt(address: EthAddress) -> StarkFelt {
+    let address_as_bytes: [u8; 20] = address.0.to_fixed_bytes();
+    // Pad with 12 zeros.
+    let mut bytes = [0; 32];
+    bytes[12..32].copy_from_slice(&address_as_bytes);
+    StarkFelt::new(bytes).expect("Convert Ethereum address to StarkFelt")
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum FeeParsingError {
+    #[error("Invalid decimal fee string: {0:?}.")]
+    InvalidFormat(String),
+    #[error("Fee {fee:?} is out of range.")]
+    OutOfRange { fee: String },
+    #[error("Fee {fee:?} has more than {decimals} fractional digits.")]
+    TooManyFractionalDigits { fee: String, decimals: u8 },
+}
+
+/// Formats the given fee (in the smallest unit, e.g., Wei) as a decimal string in units of
+/// `10^decimals` (e.g., 18 decimals for ETH); trailing fractional zeros are omitted.
+pub fn fee_to_decimal_string(fee: Fee, decimals: u8) -> String {
+    let decimals = usize::from(decimals);
+    // Pad with leading zeros, to have at least a single integer digit.
+    let digits = format!("{:0>width$}", fee.0, width = decimals + 1);
+    let (integer_part, fractional_part) = digits.split_at(digits.len() - decimals);
+    let fractional_part = fractional_part.trim_end_matches('0');
+    if fractional_part.is_empty() {
+        integer_part.to_string()
+    } else {
+        format!("{integer_part}.{fractional_part}")
+    }
+}
+
+/// Parses a decimal string in units of `10^decimals` into a fee in the smallest unit; the inverse
+/// of [fee_to_decimal_string].
+pub fn fee_from_decimal_string(fee: &str, decimals: u8) -> Result<Fee, FeeParsingError> {
+    let (integer_part, fractional_part) = match fee.split_once('.') {
+        Some((_, "")) => return Err(FeeParsingError::InvalidFormat(fee.to_string())),
+        Some((integer_part, fractional_part)) => (integer_part, fractional_part),
+        None => (fee, ""),
+    };
+    let is_decimal = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if integer_part.is_empty() || !is_decimal(integer_part) || !is_decimal(fractional_part) {
+        return Err(FeeParsingError::InvalidFormat(fee.to_string()));
+    }
+    if fractional_part.len() > usize::from(decimals) {
+        return Err(FeeParsingError::TooManyFractionalDigits { fee: fee.to_string(), decimals });
+    }
+
+    let digits = format!("{integer_part}{fractional_part:0<width$}", width = usize::from(decimals));
+    digits
+        .parse::<u128>()
+        .map(Fee)
+        .map_err(|_| FeeParsingError::OutOfRange { fee: fee.to_string() })
+}
+
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum TransactionHashError {
+    #[error("Cannot compute the hash of {tx_type:?} transactions of version {version:?}.")]
+    UnsupportedTransaction { tx_type: TransactionType, version: TransactionVersion },
+}
+
+/// Calculates the hash of a version 1 (max-fee) account transaction, as computed by the protocol:
+/// the Pedersen hash of the transaction prefix, version, sender, entry point selector (zero),
+/// calldata hash, max fee, chain ID and nonce.
+/// The calldata is type-specific: the execute calldata of an invoke, the class hash of a declare,
+/// and the class hash, salt and constructor calldata of a deploy-account (whose sender is the
+/// deployed address).
+pub fn calculate_transaction_hash(
+    tx_type: TransactionType,
+    version: TransactionVersion,
+    sender: ContractAddress,
+    calldata: &Calldata,
+    max_fee: Fee,
+    chain_id: &ChainId,
+    nonce: Nonce,
+) -> Result<TransactionHash, TransactionHashError> {
+    let prefix = match tx_type {
+        TransactionType::Declare => "declare",
+        TransactionType::DeployAccount => "deploy_account",
+        TransactionType::InvokeFunction => "invoke",
+        TransactionType::L1Handler => {
+            return Err(TransactionHashError::UnsupportedTransaction { tx_type, version });
+        }
+    };
+    if version != TransactionVersion::ONE {
+        return Err(TransactionHashError::UnsupportedTransaction { tx_type, version });
+    }
+
+    let short_string_to_felt =
+        |value: &str| felt_to_stark_felt(&Felt252::from_bytes_be(value.as_bytes()));
+    Ok(TransactionHash(pedersen_hash_array(&[
+        short_string_to_felt(prefix),
+        version.0,
+        *sender.0.key(),
+        StarkFelt::ZERO,
+        pedersen_hash_array(&calldata.0),
+        StarkFelt::from(max_fee.0),
+        short_string_to_felt(&chain_id.0),
+        nonce.0,
+    ])))
+}
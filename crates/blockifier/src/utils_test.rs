@@ -1,8 +1,18 @@
 use std::collections::HashMap;
 
 use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{ChainId, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{Calldata, Fee, TransactionHash, TransactionVersion};
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
 
-use crate::utils::subtract_mappings;
+use crate::test_utils::{pad_address_to_64, SECURITY_TEST_CONTRACT_ADDRESS};
+use crate::transaction::transaction_types::TransactionType;
+use crate::utils::{
+    calculate_transaction_hash, fee_from_decimal_string, fee_to_decimal_string, format_felt_hex,
+    subtract_mappings, FeeParsingError, TransactionHashError,
+};
 
 #[test]
 fn test_subtract_mappings() {
@@ -18,3 +28,114 @@ fn test_subtract_mappings() {
     let expected = HashMap::from([("red", 1), ("blue", 3)]);
     assert_eq!(expected, subtract_mappings(&map1, &map2));
 }
+
+#[test]
+fn test_format_felt_hex() {
+    assert_eq!(
+        format_felt_hex(&stark_felt!(0x17_u8)),
+        "0x0000000000000000000000000000000000000000000000000000000000000017"
+    );
+    assert_eq!(
+        format_felt_hex(&stark_felt!(SECURITY_TEST_CONTRACT_ADDRESS)),
+        "0x0000000000000000000000000000000000000000000000000000000000000300"
+    );
+    assert_eq!(
+        format_felt_hex(&stark_felt!(SECURITY_TEST_CONTRACT_ADDRESS)),
+        pad_address_to_64(SECURITY_TEST_CONTRACT_ADDRESS)
+    );
+    assert_eq!(format_felt_hex(&StarkFelt::ZERO).len(), 66);
+}
+
+#[rstest]
+#[case::one_eth(1_000_000_000_000_000_000, 18, "1")]
+#[case::fraction(1_500_000_000_000_000_000, 18, "1.5")]
+#[case::sub_unit(1, 18, "0.000000000000000001")]
+#[case::zero(0, 18, "0")]
+#[case::no_decimals(1234, 0, "1234")]
+#[case::max_fee(u128::MAX, 18, "340282366920938463463.374607431768211455")]
+fn test_fee_decimal_string(#[case] fee: u128, #[case] decimals: u8, #[case] expected: &str) {
+    assert_eq!(fee_to_decimal_string(Fee(fee), decimals), expected);
+    assert_eq!(fee_from_decimal_string(expected, decimals).unwrap(), Fee(fee));
+}
+
+#[test]
+fn test_fee_from_decimal_string_errors() {
+    assert_eq!(fee_from_decimal_string("0.10", 18).unwrap(), Fee(100_000_000_000_000_000));
+    for invalid_fee in ["", ".5", "1.", "-1", "1.2.3", "1e18", " 1"] {
+        assert_eq!(
+            fee_from_decimal_string(invalid_fee, 18).unwrap_err(),
+            FeeParsingError::InvalidFormat(invalid_fee.to_string())
+        );
+    }
+    assert_eq!(
+        fee_from_decimal_string("0.123", 2).unwrap_err(),
+        FeeParsingError::TooManyFractionalDigits { fee: "0.123".to_string(), decimals: 2 }
+    );
+    assert_eq!(
+        fee_from_decimal_string("340282366920938463464", 18).unwrap_err(),
+        FeeParsingError::OutOfRange { fee: "340282366920938463464".to_string() }
+    );
+}
+
+/// The expected hashes were computed by an independent Pedersen implementation, checked against
+/// the crypto-cpp test vector.
+#[rstest]
+#[case::invoke(
+    TransactionType::InvokeFunction,
+    calldata![stark_felt!(1_u8), stark_felt!(2_u8)],
+    Nonce(stark_felt!(3_u8)),
+    "0x2b16ad1f59f5a7a452c90c37b647cbc4196149075f1faa9b7de4346d2a40ade"
+)]
+#[case::declare(
+    TransactionType::Declare,
+    // The class hash.
+    calldata![stark_felt!("0x1234")],
+    Nonce(stark_felt!(3_u8)),
+    "0x5a2af2b07db25e97da741e5714d9e484e3c88f294798c950771f4dc46874bcf"
+)]
+#[case::deploy_account(
+    TransactionType::DeployAccount,
+    // The class hash, the salt and the constructor calldata.
+    calldata![stark_felt!("0x1234"), stark_felt!(5_u8), stark_felt!(1_u8), stark_felt!(2_u8)],
+    Nonce(stark_felt!(0_u8)),
+    "0x2405a4b6d785d614f46615941b628ab3d226d9cb84f46080931cebc54892b13"
+)]
+fn test_calculate_transaction_hash(
+    #[case] tx_type: TransactionType,
+    #[case] calldata: Calldata,
+    #[case] nonce: Nonce,
+    #[case] expected_tx_hash: &str,
+) {
+    let tx_hash = calculate_transaction_hash(
+        tx_type,
+        TransactionVersion::ONE,
+        contract_address!("0x12345"),
+        &calldata,
+        Fee(1000),
+        &ChainId("SN_GOERLI".to_string()),
+        nonce,
+    );
+    assert_eq!(tx_hash.unwrap(), TransactionHash(stark_felt!(expected_tx_hash)));
+}
+
+#[rstest]
+#[case::unsupported_version(TransactionType::InvokeFunction, TransactionVersion::ZERO)]
+#[case::unsupported_type(TransactionType::L1Handler, TransactionVersion::ONE)]
+fn test_calculate_transaction_hash_unsupported(
+    #[case] tx_type: TransactionType,
+    #[case] version: TransactionVersion,
+) {
+    let tx_hash = calculate_transaction_hash(
+        tx_type,
+        version,
+        contract_address!("0x12345"),
+        &calldata![],
+        Fee(1000),
+        &ChainId("SN_GOERLI".to_string()),
+        Nonce::default(),
+    );
+    assert_eq!(
+        tx_hash.unwrap_err(),
+        TransactionHashError::UnsupportedTransaction { tx_type, version }
+    );
+}
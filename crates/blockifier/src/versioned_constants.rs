@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::abi::constants;
+use crate::block_context::BlockContextError;
+use crate::execution::syscalls::SyscallSelector;
+use crate::fee::os_resources::OS_RESOURCES;
+use crate::fee::os_usage::OsResources;
+
+#[cfg(test)]
+#[path = "versioned_constants_test.rs"]
+pub mod test;
+
+/// A Starknet protocol version whose execution constants are known.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum StarknetVersion {
+    V0_12_0,
+    #[default]
+    V0_13_0,
+}
+
+impl FromStr for StarknetVersion {
+    type Err = BlockContextError;
+
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        match version {
+            "0.12.0" => Ok(Self::V0_12_0),
+            "0.13.0" => Ok(Self::V0_13_0),
+            unknown_version => Err(BlockContextError::UnsupportedStarknetVersion {
+                version: unknown_version.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for StarknetVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let version = match self {
+            Self::V0_12_0 => "0.12.0",
+            Self::V0_13_0 => "0.13.0",
+        };
+        write!(f, "{version}")
+    }
+}
+
+/// The execution and fee constants of a Starknet protocol version.
+#[derive(Clone, Debug)]
+pub struct VersionedConstants {
+    pub version: StarknetVersion,
+
+    // Gas costs of Sierra execution.
+    // Syscalls missing from the mapping are not supported by the version.
+    syscall_gas_costs: HashMap<SyscallSelector, u64>,
+    pub keccak_round_gas_cost: u64,
+
+    // The resources of the OS, on top of the execution resources, per transaction type and
+    // syscall.
+    pub os_resources: &'static OsResources,
+}
+
+impl VersionedConstants {
+    pub fn new(version: StarknetVersion) -> Self {
+        let mut syscall_gas_costs = HashMap::from([
+            (SyscallSelector::CallContract, constants::CALL_CONTRACT_GAS_COST),
+            (SyscallSelector::Deploy, constants::DEPLOY_GAS_COST),
+            (SyscallSelector::EmitEvent, constants::EMIT_EVENT_GAS_COST),
+            (SyscallSelector::GetBlockHash, constants::GET_BLOCK_HASH_GAS_COST),
+            (SyscallSelector::GetExecutionInfo, constants::GET_EXECUTION_INFO_GAS_COST),
+            (SyscallSelector::Keccak, constants::KECCAK_GAS_COST),
+            (SyscallSelector::LibraryCall, constants::LIBRARY_CALL_GAS_COST),
+            (SyscallSelector::LibraryCallL1Handler, constants::LIBRARY_CALL_GAS_COST),
+            (SyscallSelector::ReplaceClass, constants::REPLACE_CLASS_GAS_COST),
+            (SyscallSelector::Secp256k1Add, constants::SECP256K1_ADD_GAS_COST),
+            (
+                SyscallSelector::Secp256k1GetPointFromX,
+                constants::SECP256K1_GET_POINT_FROM_X_GAS_COST,
+            ),
+            (SyscallSelector::Secp256k1GetXy, constants::SECP256K1_GET_XY_GAS_COST),
+            (SyscallSelector::Secp256k1Mul, constants::SECP256K1_MUL_GAS_COST),
+            (SyscallSelector::Secp256k1New, constants::SECP256K1_NEW_GAS_COST),
+            (SyscallSelector::Secp256r1Add, constants::SECP256R1_ADD_GAS_COST),
+            (
+                SyscallSelector::Secp256r1GetPointFromX,
+                constants::SECP256R1_GET_POINT_FROM_X_GAS_COST,
+            ),
+            (SyscallSelector::Secp256r1GetXy, constants::SECP256R1_GET_XY_GAS_COST),
+            (SyscallSelector::Secp256r1Mul, constants::SECP256R1_MUL_GAS_COST),
+            (SyscallSelector::Secp256r1New, constants::SECP256R1_NEW_GAS_COST),
+            (SyscallSelector::SendMessageToL1, constants::SEND_MESSAGE_TO_L1_GAS_COST),
+            (SyscallSelector::StorageRead, constants::STORAGE_READ_GAS_COST),
+            (SyscallSelector::StorageWrite, constants::STORAGE_WRITE_GAS_COST),
+        ]);
+        if version < StarknetVersion::V0_13_0 {
+            // The secp256r1 syscalls were introduced after Starknet 0.12.0.
+            syscall_gas_costs.retain(|selector, _| {
+                !matches!(
+                    selector,
+                    SyscallSelector::Secp256r1Add
+                        | SyscallSelector::Secp256r1GetPointFromX
+                        | SyscallSelector::Secp256r1GetXy
+                        | SyscallSelector::Secp256r1Mul
+                        | SyscallSelector::Secp256r1New
+                )
+            });
+        }
+
+        Self {
+            version,
+            syscall_gas_costs,
+            keccak_round_gas_cost: constants::KECCAK_ROUND_COST_GAS_COST,
+            os_resources: &OS_RESOURCES,
+        }
+    }
+
+    /// Returns the constants of the latest supported Starknet version.
+    pub fn latest() -> Self {
+        Self::new(StarknetVersion::default())
+    }
+
+    /// Returns the gas cost of the given syscall, or `None` if the version does not support it.
+    pub fn syscall_gas_cost(&self, selector: &SyscallSelector) -> Option<u64> {
+        self.syscall_gas_costs.get(selector).copied()
+    }
+}
@@ -0,0 +1,48 @@
+use std::str::FromStr;
+
+use assert_matches::assert_matches;
+
+use crate::abi::constants;
+use crate::block_context::{BlockContext, BlockContextError};
+use crate::execution::syscalls::SyscallSelector;
+use crate::versioned_constants::{StarknetVersion, VersionedConstants};
+
+#[test]
+fn test_starknet_version_from_str() {
+    for version in [StarknetVersion::V0_12_0, StarknetVersion::V0_13_0] {
+        assert_eq!(StarknetVersion::from_str(&version.to_string()).unwrap(), version);
+    }
+    assert_matches!(
+        StarknetVersion::from_str("0.11.0"),
+        Err(BlockContextError::UnsupportedStarknetVersion { version }) if version == "0.11.0"
+    );
+}
+
+#[test]
+fn test_syscall_gas_costs_by_version() {
+    let constants_v0_12_0 = VersionedConstants::new(StarknetVersion::V0_12_0);
+    let constants_v0_13_0 = VersionedConstants::new(StarknetVersion::V0_13_0);
+
+    // Syscalls supported by both versions.
+    assert_eq!(
+        constants_v0_12_0.syscall_gas_cost(&SyscallSelector::StorageRead),
+        constants_v0_13_0.syscall_gas_cost(&SyscallSelector::StorageRead)
+    );
+
+    let selector = SyscallSelector::Secp256r1New;
+    assert_eq!(constants_v0_12_0.syscall_gas_cost(&selector), None);
+    assert_eq!(
+        constants_v0_13_0.syscall_gas_cost(&selector),
+        Some(constants::SECP256R1_NEW_GAS_COST)
+    );
+}
+
+#[test]
+fn test_block_context_versioned_constants() {
+    let block_context = BlockContext::create_for_testing();
+    assert_eq!(block_context.versioned_constants.version, StarknetVersion::default());
+
+    // Contexts for subsequent blocks keep the version.
+    let next_block_context = block_context.with_block_info(block_context.block_info.clone());
+    assert_eq!(next_block_context.versioned_constants.version, StarknetVersion::default());
+}
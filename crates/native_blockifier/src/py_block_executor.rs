@@ -1,8 +1,13 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use blockifier::block_context::{BlockContext, FeeTokenAddresses, GasPrices};
+use blockifier::block_context::{
+    BlockContext, BlockInfo, BlockResourceLimits, CalldataL1GasCosts, ChainInfo, FeeRecipient,
+    FeeTokenAddresses, GasPrices, SyscallGasCosts,
+};
+use blockifier::execution::contract_class::CompilerVersion;
 use blockifier::state::cached_state::GlobalContractCache;
+use blockifier::versioned_constants::{StarknetVersion, VersionedConstants};
 use pyo3::prelude::*;
 use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::{ChainId, ContractAddress};
@@ -285,12 +290,19 @@ pub fn into_block_context(
     max_recursion_depth: usize,
 ) -> NativeBlockifierResult<BlockContext> {
     let starknet_os_config = general_config.starknet_os_config.clone();
-    let block_number = BlockNumber(block_info.block_number);
-    let block_context = BlockContext {
-        chain_id: starknet_os_config.chain_id,
-        block_number,
+    let block_info = BlockInfo {
+        block_number: BlockNumber(block_info.block_number),
         block_timestamp: BlockTimestamp(block_info.block_timestamp),
+        prev_block_timestamp: None,
         sequencer_address: ContractAddress::try_from(block_info.sequencer_address.0)?,
+        gas_prices: GasPrices {
+            eth_l1_gas_price: block_info.eth_l1_gas_price,
+            strk_l1_gas_price: block_info.strk_l1_gas_price,
+        },
+        block_hashes: HashMap::default(),
+    };
+    let chain_info = ChainInfo {
+        chain_id: starknet_os_config.chain_id,
         fee_token_addresses: FeeTokenAddresses {
             eth_fee_token_address: ContractAddress::try_from(
                 starknet_os_config.deprecated_fee_token_address.0,
@@ -300,14 +312,31 @@ pub fn into_block_context(
             )?,
         },
         vm_resource_fee_cost: general_config.cairo_resource_fee_weights.clone(),
-        gas_prices: GasPrices {
-            eth_l1_gas_price: block_info.eth_l1_gas_price,
-            strk_l1_gas_price: block_info.strk_l1_gas_price,
-        },
+        fee_recipient: FeeRecipient::default(),
+        enforce_l1_handler_fee: false,
         invoke_tx_max_n_steps: general_config.invoke_tx_max_n_steps,
         validate_max_n_steps: general_config.validate_max_n_steps,
         max_recursion_depth,
+        // The declared class and emitted output limits are not part of the general config; they
+        // are disabled, leaving their enforcement to the Python side.
+        max_contract_class_size: usize::MAX,
+        max_bytecode_length: usize::MAX,
+        supported_compiler_versions: CompilerVersion::default()..=CompilerVersion {
+            major: u64::MAX,
+            minor: u64::MAX,
+            patch: u64::MAX,
+        },
+        max_event_data_size: usize::MAX,
+        max_l1_message_payload_size: usize::MAX,
+        max_storage_writes: None,
+        block_resource_limits: BlockResourceLimits::default(),
+        syscall_gas_costs: SyscallGasCosts::default(),
+        calldata_l1_gas_costs: CalldataL1GasCosts::default(),
     };
+    // Pinned to the version whose constants the sequencer used before they were versioned, rather
+    // than following the latest version.
+    let versioned_constants = VersionedConstants::new(StarknetVersion::V0_13_0);
+    let block_context = BlockContext::new(block_info, chain_info, versioned_constants);
 
     Ok(block_context)
 }
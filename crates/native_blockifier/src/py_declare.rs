@@ -143,5 +143,6 @@ pub fn py_declare(
     };
 
     let tx_hash = TransactionHash(py_attr::<PyFelt>(py_tx, "hash_value")?.0);
-    Ok(DeclareTransaction::new(tx, tx_hash, contract_class)?)
+    Ok(DeclareTransaction::new(tx, tx_hash, contract_class)?
+        .with_class_size(raw_contract_class.len()))
 }
@@ -17,6 +17,8 @@ pub struct PyTransactionExecutionInfo {
     #[pyo3(get)]
     pub fee_transfer_call_info: Option<PyCallInfo>,
     #[pyo3(get)]
+    pub additional_fee_transfer_call_infos: Vec<PyCallInfo>,
+    #[pyo3(get)]
     pub actual_fee: u128,
     #[pyo3(get)]
     pub actual_resources: HashMap<String, usize>,
@@ -31,6 +33,10 @@ impl From<TransactionExecutionInfo> for PyTransactionExecutionInfo {
             validate_call_info: info.validate_call_info.map(PyCallInfo::from),
             execute_call_info: info.execute_call_info.map(PyCallInfo::from),
             fee_transfer_call_info: info.fee_transfer_call_info.map(PyCallInfo::from),
+            additional_fee_transfer_call_infos: to_py_vec(
+                info.additional_fee_transfer_call_infos,
+                PyCallInfo::from,
+            ),
             actual_fee: info.actual_fee.0,
             actual_resources: info.actual_resources.0,
             revert_error: info.revert_error,
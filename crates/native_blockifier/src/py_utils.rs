@@ -1,6 +1,7 @@
 use std::convert::TryFrom;
 
 use blockifier::transaction::errors::{TransactionExecutionError, TransactionFeeError};
+use blockifier::utils::eth_address_to_felt;
 use num_bigint::BigUint;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
@@ -39,11 +40,7 @@ impl From<ContractAddress> for PyFelt {
 
 impl From<EthAddress> for PyFelt {
     fn from(address: EthAddress) -> Self {
-        let address_as_bytes: [u8; 20] = address.0.to_fixed_bytes();
-        // Pad with 12 zeros.
-        let mut bytes = [0; 32];
-        bytes[12..32].copy_from_slice(&address_as_bytes);
-        PyFelt(StarkFelt::new(bytes).expect("Convert Ethereum address to StarkFelt"))
+        PyFelt(eth_address_to_felt(address))
     }
 }
 
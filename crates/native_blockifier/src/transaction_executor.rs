@@ -11,7 +11,9 @@ use blockifier::state::cached_state::{
 use blockifier::state::state_api::{State, StateReader};
 use blockifier::transaction::account_transaction::AccountTransaction;
 use blockifier::transaction::transaction_execution::Transaction;
-use blockifier::transaction::transactions::{ExecutableTransaction, ValidatableTransaction};
+use blockifier::transaction::transactions::{
+    ExecutableTransaction, ExecutionFlags, ValidatableTransaction,
+};
 use cairo_vm::vm::runners::builtin_runner::HASH_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources as VmExecutionResources;
 use pyo3::prelude::*;
@@ -78,10 +80,10 @@ impl<S: StateReader> TransactionExecutor<S> {
         let mut tx_executed_class_hashes = HashSet::<ClassHash>::new();
         let mut tx_visited_storage_entries = HashSet::<StorageEntry>::new();
         let mut transactional_state = CachedState::create_transactional(&mut self.state);
-        let validate = true;
+        let execution_flags = ExecutionFlags { charge_fee, validate: true, execute: true };
 
         let tx_execution_result = tx
-            .execute_raw(&mut transactional_state, &self.block_context, charge_fee, validate)
+            .execute_raw(&mut transactional_state, &self.block_context, execution_flags)
             .map_err(NativeBlockifierError::from);
         match tx_execution_result {
             Ok(tx_execution_info) => {